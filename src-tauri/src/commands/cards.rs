@@ -0,0 +1,30 @@
+use tauri::State;
+use crate::models::{Flashcard, ReviewStats};
+use crate::services::DatabaseService;
+use crate::errors::AppResult;
+
+#[tauri::command]
+pub async fn get_due_cards(
+    db: State<'_, DatabaseService>,
+    date: String,
+    limit: Option<i64>,
+) -> AppResult<Vec<Flashcard>> {
+    db.get_due_cards(&date, limit.unwrap_or(50)).await
+}
+
+#[tauri::command]
+pub async fn review_card(
+    db: State<'_, DatabaseService>,
+    node_id: String,
+    grade: u8,
+) -> AppResult<Flashcard> {
+    db.review_card(&node_id, grade).await
+}
+
+#[tauri::command]
+pub async fn get_review_stats(
+    db: State<'_, DatabaseService>,
+    date: String,
+) -> AppResult<ReviewStats> {
+    db.get_review_stats(&date).await
+}
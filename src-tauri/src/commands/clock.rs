@@ -0,0 +1,59 @@
+use tauri::State;
+use crate::models::{ClockEntry, TimeReport};
+use crate::services::{DatabaseService, GitManager};
+use crate::errors::AppResult;
+
+#[tauri::command]
+pub async fn clock_in(
+    db: State<'_, DatabaseService>,
+    git: State<'_, GitManager>,
+    node_id: String,
+) -> AppResult<ClockEntry> {
+    let entry = db.clock_in(&node_id).await?;
+    git.record_change();
+    Ok(entry)
+}
+
+#[tauri::command]
+pub async fn clock_out(
+    db: State<'_, DatabaseService>,
+    git: State<'_, GitManager>,
+    node_id: Option<String>,
+) -> AppResult<ClockEntry> {
+    let entry = db.clock_out(node_id.as_deref()).await?;
+    git.record_change();
+    Ok(entry)
+}
+
+#[tauri::command]
+pub async fn get_running_clock(
+    db: State<'_, DatabaseService>,
+) -> AppResult<Option<ClockEntry>> {
+    db.get_running_clock().await
+}
+
+#[tauri::command]
+pub async fn get_clock_entries(
+    db: State<'_, DatabaseService>,
+    node_id: String,
+) -> AppResult<Vec<ClockEntry>> {
+    db.get_clock_entries(&node_id).await
+}
+
+#[tauri::command]
+pub async fn get_time_report(
+    db: State<'_, DatabaseService>,
+    start: String,
+    end: String,
+) -> AppResult<TimeReport> {
+    db.get_time_report(&start, &end).await
+}
+
+#[tauri::command]
+pub async fn export_time_report_csv(
+    db: State<'_, DatabaseService>,
+    start: String,
+    end: String,
+) -> AppResult<String> {
+    db.export_time_report_csv(&start, &end).await
+}
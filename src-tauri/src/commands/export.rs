@@ -1,5 +1,10 @@
+use tauri::ipc::Channel;
 use tauri::State;
-use crate::services::DatabaseService;
+use crate::models::{BackupSettings, ImportProgress, ImportSummary, Snapshot};
+use crate::services::{DatabaseService, GitManager};
+use crate::services::database::export::{JsonImportMode, JsonImportReport, MarkdownExportSummary};
+use crate::services::database::backup::BackupSummary;
+use crate::services::database::publish::PublishSummary;
 use crate::errors::AppResult;
 use std::path::PathBuf;
 
@@ -15,10 +20,197 @@ pub async fn export_to_json(
 #[tauri::command]
 pub async fn import_from_json(
     db: State<'_, DatabaseService>,
+    git: State<'_, GitManager>,
     path: String,
+    mode: Option<JsonImportMode>,
+    dry_run: Option<bool>,
+) -> AppResult<JsonImportReport> {
+    let path = PathBuf::from(path);
+    let dry_run = dry_run.unwrap_or(false);
+    let report = db.import_from_json(&path, mode.unwrap_or_default(), dry_run).await?;
+    if !dry_run {
+        git.record_change();
+    }
+    Ok(report)
+}
+
+#[tauri::command]
+pub async fn create_backup(
+    db: State<'_, DatabaseService>,
+    path: String,
+) -> AppResult<BackupSummary> {
+    let path = PathBuf::from(path);
+    db.create_backup(&path).await
+}
+
+#[tauri::command]
+pub async fn restore_backup(
+    db: State<'_, DatabaseService>,
+    git: State<'_, GitManager>,
+    path: String,
+) -> AppResult<BackupSummary> {
+    let path = PathBuf::from(path);
+    let summary = db.restore_backup(&path).await?;
+    git.record_change();
+    Ok(summary)
+}
+
+#[tauri::command]
+pub async fn get_backup_settings(
+    db: State<'_, DatabaseService>,
+) -> AppResult<BackupSettings> {
+    db.get_backup_settings().await
+}
+
+#[tauri::command]
+pub async fn set_backup_settings(
+    db: State<'_, DatabaseService>,
+    settings: BackupSettings,
 ) -> AppResult<()> {
+    db.set_backup_settings(&settings).await
+}
+
+#[tauri::command]
+pub async fn list_snapshots(
+    db: State<'_, DatabaseService>,
+) -> AppResult<Vec<Snapshot>> {
+    db.list_snapshots().await
+}
+
+#[tauri::command]
+pub async fn create_snapshot(
+    db: State<'_, DatabaseService>,
+) -> AppResult<Snapshot> {
+    db.create_snapshot().await
+}
+
+#[tauri::command]
+pub async fn restore_snapshot(
+    db: State<'_, DatabaseService>,
+    git: State<'_, GitManager>,
+    file_name: String,
+) -> AppResult<Snapshot> {
+    let safety = db.restore_snapshot(&file_name).await?;
+    git.record_change();
+    Ok(safety)
+}
+
+#[tauri::command]
+pub async fn import_markdown_dir(
+    db: State<'_, DatabaseService>,
+    git: State<'_, GitManager>,
+    path: String,
+    on_progress: Channel<ImportProgress>,
+) -> AppResult<ImportSummary> {
+    let path = PathBuf::from(path);
+    let summary = db.import_markdown_dir(&path, |progress| {
+        let _ = on_progress.send(progress);
+    }).await?;
+    git.record_change();
+    Ok(summary)
+}
+
+#[tauri::command]
+pub async fn import_roam_json(
+    db: State<'_, DatabaseService>,
+    git: State<'_, GitManager>,
+    path: String,
+    on_progress: Channel<ImportProgress>,
+) -> AppResult<ImportSummary> {
+    let path = PathBuf::from(path);
+    let summary = db.import_roam_json(&path, |progress| {
+        let _ = on_progress.send(progress);
+    }).await?;
+    git.record_change();
+    Ok(summary)
+}
+
+#[tauri::command]
+pub async fn import_notion_zip(
+    db: State<'_, DatabaseService>,
+    git: State<'_, GitManager>,
+    path: String,
+    on_progress: Channel<ImportProgress>,
+) -> AppResult<ImportSummary> {
+    let path = PathBuf::from(path);
+    let summary = db.import_notion_zip(&path, |progress| {
+        let _ = on_progress.send(progress);
+    }).await?;
+    git.record_change();
+    Ok(summary)
+}
+
+#[tauri::command]
+pub async fn import_enex(
+    db: State<'_, DatabaseService>,
+    git: State<'_, GitManager>,
+    path: String,
+    on_progress: Channel<ImportProgress>,
+) -> AppResult<ImportSummary> {
+    let path = PathBuf::from(path);
+    let summary = db.import_enex(&path, |progress| {
+        let _ = on_progress.send(progress);
+    }).await?;
+    git.record_change();
+    Ok(summary)
+}
+
+#[tauri::command]
+pub async fn export_markdown_dir(
+    db: State<'_, DatabaseService>,
+    path: String,
+) -> AppResult<MarkdownExportSummary> {
+    let path = PathBuf::from(path);
+    db.export_markdown_dir(&path).await
+}
+
+#[tauri::command]
+pub async fn publish_site(
+    db: State<'_, DatabaseService>,
+    path: String,
+) -> AppResult<PublishSummary> {
+    let path = PathBuf::from(path);
+    db.publish_site(&path).await
+}
+
+#[tauri::command]
+pub async fn import_opml(
+    db: State<'_, DatabaseService>,
+    git: State<'_, GitManager>,
+    path: String,
+) -> AppResult<ImportSummary> {
     let path = PathBuf::from(path);
-    db.import_from_json(&path).await
+    let summary = db.import_opml(&path).await?;
+    git.record_change();
+    Ok(summary)
+}
+
+#[tauri::command]
+pub async fn export_node_to_opml(
+    db: State<'_, DatabaseService>,
+    node_id: String,
+) -> AppResult<String> {
+    db.export_node_to_opml(&node_id).await
+}
+
+#[tauri::command]
+pub async fn import_org(
+    db: State<'_, DatabaseService>,
+    git: State<'_, GitManager>,
+    path: String,
+) -> AppResult<ImportSummary> {
+    let path = PathBuf::from(path);
+    let summary = db.import_org(&path).await?;
+    git.record_change();
+    Ok(summary)
+}
+
+#[tauri::command]
+pub async fn export_node_to_org(
+    db: State<'_, DatabaseService>,
+    node_id: String,
+) -> AppResult<String> {
+    db.export_node_to_org(&node_id).await
 }
 
 #[tauri::command]
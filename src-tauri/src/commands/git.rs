@@ -0,0 +1,51 @@
+use tauri::State;
+use crate::models::{BlockBlame, GitCommit, GitSettings, Node};
+use crate::services::GitManager;
+use crate::errors::AppResult;
+
+#[tauri::command]
+pub async fn get_git_settings(
+    git: State<'_, GitManager>,
+) -> AppResult<GitSettings> {
+    git.get_settings().await
+}
+
+#[tauri::command]
+pub async fn set_git_settings(
+    git: State<'_, GitManager>,
+    settings: GitSettings,
+) -> AppResult<()> {
+    git.set_settings(&settings).await
+}
+
+#[tauri::command]
+pub async fn commit_git_mirror(
+    git: State<'_, GitManager>,
+) -> AppResult<Option<GitCommit>> {
+    git.commit_mirror().await
+}
+
+#[tauri::command]
+pub async fn get_page_history(
+    git: State<'_, GitManager>,
+    page_id: String,
+) -> AppResult<Vec<GitCommit>> {
+    git.page_history(&page_id).await
+}
+
+#[tauri::command]
+pub async fn blame_page(
+    git: State<'_, GitManager>,
+    page_id: String,
+) -> AppResult<Vec<BlockBlame>> {
+    git.blame_page(&page_id).await
+}
+
+#[tauri::command]
+pub async fn restore_page_from_commit(
+    git: State<'_, GitManager>,
+    page_id: String,
+    commit_id: String,
+) -> AppResult<Node> {
+    git.restore_page(&page_id, &commit_id).await
+}
@@ -0,0 +1,37 @@
+use tauri::State;
+use crate::models::{Habit, HabitReport};
+use crate::services::DatabaseService;
+use crate::errors::AppResult;
+
+#[tauri::command]
+pub async fn add_habit(
+    db: State<'_, DatabaseService>,
+    key: String,
+    name: Option<String>,
+) -> AppResult<Habit> {
+    db.add_habit(&key, name.as_deref()).await
+}
+
+#[tauri::command]
+pub async fn remove_habit(
+    db: State<'_, DatabaseService>,
+    key: String,
+) -> AppResult<()> {
+    db.remove_habit(&key).await
+}
+
+#[tauri::command]
+pub async fn list_habits(
+    db: State<'_, DatabaseService>,
+) -> AppResult<Vec<Habit>> {
+    db.list_habits().await
+}
+
+#[tauri::command]
+pub async fn get_habit_report(
+    db: State<'_, DatabaseService>,
+    start: String,
+    end: String,
+) -> AppResult<HabitReport> {
+    db.get_habit_report(&start, &end).await
+}
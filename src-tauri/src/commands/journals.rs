@@ -0,0 +1,154 @@
+use tauri::State;
+use crate::models::{Node, JournalEntry, JournalList, PeriodKind, PeriodRollup, ResurfacedNode};
+use crate::services::{DatabaseService, GitManager};
+use crate::errors::AppResult;
+use crate::utils::{format_iso_date, parse_date_expression, DateLocale};
+use chrono::Local;
+
+#[tauri::command]
+pub async fn get_daily_note(
+    db: State<'_, DatabaseService>,
+    date: String,
+) -> AppResult<Node> {
+    db.inner().get_daily_note(&date).await
+}
+
+#[tauri::command]
+pub async fn get_or_create_daily_note(
+    db: State<'_, DatabaseService>,
+    git: State<'_, GitManager>,
+    date: String,
+) -> AppResult<Node> {
+    let node = db.inner().get_or_create_daily_note(&date).await?;
+    git.record_change();
+    Ok(node)
+}
+
+#[tauri::command]
+pub async fn list_journals(
+    db: State<'_, DatabaseService>,
+    start: Option<String>,
+    end: Option<String>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> AppResult<JournalList> {
+    let limit = limit.unwrap_or(10) as i64;
+    let offset = offset.unwrap_or(0) as i64;
+    db.list_journals(start.as_deref(), end.as_deref(), limit, offset).await
+}
+
+#[tauri::command]
+pub async fn get_journal_title_format(
+    db: State<'_, DatabaseService>,
+) -> AppResult<String> {
+    db.get_journal_title_format().await
+}
+
+#[tauri::command]
+pub async fn set_journal_title_format(
+    db: State<'_, DatabaseService>,
+    format: String,
+) -> AppResult<()> {
+    db.set_journal_title_format(&format).await
+}
+
+#[tauri::command]
+pub async fn get_or_create_weekly_note(
+    db: State<'_, DatabaseService>,
+    git: State<'_, GitManager>,
+    date: String,
+) -> AppResult<Node> {
+    let node = db.get_or_create_periodic_note(PeriodKind::Week, &date).await?;
+    git.record_change();
+    Ok(node)
+}
+
+#[tauri::command]
+pub async fn get_or_create_monthly_note(
+    db: State<'_, DatabaseService>,
+    git: State<'_, GitManager>,
+    date: String,
+) -> AppResult<Node> {
+    let node = db.get_or_create_periodic_note(PeriodKind::Month, &date).await?;
+    git.record_change();
+    Ok(node)
+}
+
+#[tauri::command]
+pub async fn get_or_create_quarterly_note(
+    db: State<'_, DatabaseService>,
+    git: State<'_, GitManager>,
+    date: String,
+) -> AppResult<Node> {
+    let node = db.get_or_create_periodic_note(PeriodKind::Quarter, &date).await?;
+    git.record_change();
+    Ok(node)
+}
+
+#[tauri::command]
+pub async fn get_or_create_yearly_note(
+    db: State<'_, DatabaseService>,
+    git: State<'_, GitManager>,
+    date: String,
+) -> AppResult<Node> {
+    let node = db.get_or_create_periodic_note(PeriodKind::Year, &date).await?;
+    git.record_change();
+    Ok(node)
+}
+
+#[tauri::command]
+pub async fn get_period_rollup(
+    db: State<'_, DatabaseService>,
+    period: PeriodKind,
+    date: String,
+) -> AppResult<PeriodRollup> {
+    db.get_period_rollup(period, &date).await
+}
+
+#[tauri::command]
+pub async fn get_on_this_day(
+    db: State<'_, DatabaseService>,
+    date: String,
+) -> AppResult<Vec<JournalEntry>> {
+    db.get_on_this_day(&date).await
+}
+
+#[tauri::command]
+pub async fn resurface_nodes(
+    db: State<'_, DatabaseService>,
+    count: Option<usize>,
+    min_age_days: Option<i64>,
+    cooldown_days: Option<i64>,
+) -> AppResult<Vec<ResurfacedNode>> {
+    db.resurface_nodes(
+        count.unwrap_or(5),
+        min_age_days.unwrap_or(30),
+        cooldown_days.unwrap_or(7),
+        None,
+    ).await
+}
+
+#[tauri::command]
+pub async fn get_date_locale(
+    db: State<'_, DatabaseService>,
+) -> AppResult<DateLocale> {
+    db.get_date_locale().await
+}
+
+#[tauri::command]
+pub async fn set_date_locale(
+    db: State<'_, DatabaseService>,
+    locale: DateLocale,
+) -> AppResult<()> {
+    db.set_date_locale(locale).await
+}
+
+/// Resolve a date expression such as "next friday" to a `YYYY-MM-DD` date
+#[tauri::command]
+pub async fn parse_date(
+    db: State<'_, DatabaseService>,
+    text: String,
+) -> AppResult<Option<String>> {
+    let locale = db.get_date_locale().await?;
+    Ok(parse_date_expression(&text, Local::now().date_naive(), locale).map(format_iso_date))
+}
@@ -1,4 +1,11 @@
 pub mod nodes;
 pub mod search;
 pub mod stats;
-pub mod export; 
\ No newline at end of file
+pub mod export;
+pub mod journals;
+pub mod templates;
+pub mod tasks;
+pub mod clock;
+pub mod cards;
+pub mod habits;
+pub mod git;
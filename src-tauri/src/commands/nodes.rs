@@ -1,16 +1,19 @@
 use tauri::State;
 use crate::models::{Node, CreateNodeRequest, UpdateNodeRequest, NodeWithChildren};
-use crate::services::{DatabaseService, LinkService};
+use crate::services::{DatabaseService, GitManager, LinkService};
 use crate::errors::AppResult;
 
 #[tauri::command]
 pub async fn create_node(
     db: State<'_, DatabaseService>,
     link_service: State<'_, LinkService>,
+    git: State<'_, GitManager>,
     data: CreateNodeRequest,
 ) -> AppResult<Node> {
     let node = db.inner().create_node(data).await?;
+    let node = db.inner().index_node_content(&node).await?;
     link_service.update_links_for_node(&node).await?;
+    git.record_change();
     Ok(node)
 }
 
@@ -34,46 +37,39 @@ pub async fn get_node_with_children(
 pub async fn update_node(
     db: State<'_, DatabaseService>,
     link_service: State<'_, LinkService>,
+    git: State<'_, GitManager>,
     node_id: String,
     data: UpdateNodeRequest,
 ) -> AppResult<Node> {
     let node = db.inner().update_node(&node_id, data).await?;
+    let node = db.inner().index_node_content(&node).await?;
     link_service.update_links_for_node(&node).await?;
+    git.record_change();
     Ok(node)
 }
 
 #[tauri::command]
 pub async fn delete_node(
     db: State<'_, DatabaseService>,
+    git: State<'_, GitManager>,
     node_id: String,
 ) -> AppResult<()> {
-    db.inner().delete_node(&node_id).await
+    db.inner().delete_node(&node_id).await?;
+    git.record_change();
+    Ok(())
 }
 
 #[tauri::command]
 pub async fn move_node(
     db: State<'_, DatabaseService>,
+    git: State<'_, GitManager>,
     node_id: String,
     new_parent_id: Option<String>,
     new_order: i32,
 ) -> AppResult<Node> {
-    db.inner().move_node(&node_id, new_parent_id, new_order).await
-}
-
-#[tauri::command]
-pub async fn get_daily_note(
-    db: State<'_, DatabaseService>,
-    date: String,
-) -> AppResult<Node> {
-    db.inner().get_daily_note(&date).await
-}
-
-#[tauri::command]
-pub async fn get_or_create_daily_note(
-    db: State<'_, DatabaseService>,
-    date: String,
-) -> AppResult<Node> {
-    db.inner().get_or_create_daily_note(&date).await
+    let node = db.inner().move_node(&node_id, new_parent_id, new_order).await?;
+    git.record_change();
+    Ok(node)
 }
 
 #[tauri::command]
@@ -92,4 +88,12 @@ pub async fn get_unlinked_references(
 ) -> AppResult<Vec<Node>> {
     let _node = db.inner().get_node(&node_id).await?;
     link_service.get_outgoing_links(&node_id).await
-} 
\ No newline at end of file
+}
+
+#[tauri::command]
+pub async fn record_node_view(
+    db: State<'_, DatabaseService>,
+    node_id: String,
+) -> AppResult<()> {
+    db.inner().record_node_view(&node_id).await
+}
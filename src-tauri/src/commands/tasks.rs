@@ -0,0 +1,68 @@
+use tauri::State;
+use crate::models::{Agenda, Node, Task, TaskCompletion, TaskFilter, TaskGroup, TaskState, TaskTransition};
+use crate::services::{DatabaseService, GitManager};
+use crate::errors::AppResult;
+
+#[tauri::command]
+pub async fn cycle_task_state(
+    db: State<'_, DatabaseService>,
+    git: State<'_, GitManager>,
+    node_id: String,
+) -> AppResult<Node> {
+    let node = db.cycle_task_state(&node_id).await?;
+    git.record_change();
+    Ok(node)
+}
+
+#[tauri::command]
+pub async fn set_task_state(
+    db: State<'_, DatabaseService>,
+    git: State<'_, GitManager>,
+    node_id: String,
+    state: Option<TaskState>,
+) -> AppResult<Node> {
+    let node = db.set_task_state(&node_id, state).await?;
+    git.record_change();
+    Ok(node)
+}
+
+#[tauri::command]
+pub async fn list_tasks(
+    db: State<'_, DatabaseService>,
+    filter: Option<TaskFilter>,
+) -> AppResult<Vec<Task>> {
+    db.list_tasks(&filter.unwrap_or_default()).await
+}
+
+#[tauri::command]
+pub async fn get_task_plan(
+    db: State<'_, DatabaseService>,
+    filter: Option<TaskFilter>,
+) -> AppResult<Vec<TaskGroup>> {
+    db.get_task_plan(&filter.unwrap_or_default()).await
+}
+
+#[tauri::command]
+pub async fn get_task_history(
+    db: State<'_, DatabaseService>,
+    node_id: String,
+) -> AppResult<Vec<TaskTransition>> {
+    db.get_task_history(&node_id).await
+}
+
+#[tauri::command]
+pub async fn get_task_completions(
+    db: State<'_, DatabaseService>,
+    node_id: String,
+) -> AppResult<Vec<TaskCompletion>> {
+    db.get_task_completions(&node_id).await
+}
+
+#[tauri::command]
+pub async fn get_agenda(
+    db: State<'_, DatabaseService>,
+    date: String,
+    days: Option<u32>,
+) -> AppResult<Agenda> {
+    db.get_agenda(&date, days.unwrap_or(7)).await
+}
@@ -0,0 +1,23 @@
+use tauri::State;
+use crate::models::Node;
+use crate::services::{DatabaseService, GitManager};
+use crate::errors::AppResult;
+
+#[tauri::command]
+pub async fn list_templates(
+    db: State<'_, DatabaseService>,
+) -> AppResult<Vec<Node>> {
+    db.list_templates().await
+}
+
+#[tauri::command]
+pub async fn apply_template(
+    db: State<'_, DatabaseService>,
+    git: State<'_, GitManager>,
+    template_id: String,
+    target_id: String,
+) -> AppResult<Vec<Node>> {
+    let nodes = db.apply_template(&template_id, &target_id).await?;
+    git.record_change();
+    Ok(nodes)
+}
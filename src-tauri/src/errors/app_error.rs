@@ -23,6 +23,15 @@ pub enum AppError {
     #[error("Missing required field: {0}")]
     MissingRequiredField(String),
     
+    #[error("Invalid date: {0}")]
+    InvalidDate(String),
+    
+    #[error("Not found: {0}")]
+    NotFound(String),
+    
+    #[error("Clock conflict: {0}")]
+    ClockConflict(String),
+    
     // File system errors
     #[error("File not found: {0}")]
     FileNotFound(String),
@@ -36,13 +45,16 @@ pub enum AppError {
     #[error("Configuration error: {0}")]
     ConfigurationError(String),
     
-    // Git errors (for future phases)
+    // Git errors
     #[error("Git initialization failed: {0}")]
     GitInitFailed(String),
     
     #[error("Git commit failed: {0}")]
     GitCommitFailed(String),
     
+    #[error("Git history lookup failed: {0}")]
+    GitHistoryFailed(String),
+    
     #[error("Git push failed: {0}")]
     GitPushFailed(String),
     
@@ -8,11 +8,18 @@ pub mod commands;
 // Re-exports for easier access
 pub use errors::{AppError, AppResult};
 pub use models::*;
-pub use services::{DatabaseService, LinkService};
+pub use services::{DatabaseService, GitManager, LinkService};
 pub use commands::nodes::*;
 pub use commands::search::*;
 pub use commands::stats::*;
 pub use commands::export::*;
+pub use commands::journals::*;
+pub use commands::templates::*;
+pub use commands::tasks::*;
+pub use commands::clock::*;
+pub use commands::cards::*;
+pub use commands::habits::*;
+pub use commands::git::*;
 
 // Basic commands
 #[tauri::command]
@@ -41,22 +48,38 @@ pub fn run() {
     let runtime = tokio::runtime::Runtime::new().expect("Failed to create runtime");
     
     // Initialize services asynchronously
-    let (db_service, link_service) = runtime.block_on(async {
+    let (db_service, link_service, git_manager) = runtime.block_on(async {
         let db_service = DatabaseService::new()
             .await
             .expect("Failed to initialize database service");
         let link_service = LinkService::new(db_service.clone());
-        (db_service, link_service)
+        let git_manager = GitManager::new(db_service.clone());
+        (db_service, link_service, git_manager)
     });
     
     tracing::info!("Services initialized successfully");
 
+    let backup_service = db_service.clone();
+    let git_mirror = git_manager.clone();
+
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
         .manage(db_service)
         .manage(link_service)
+        .manage(git_manager)
+        .setup(move |_app| {
+            // Snapshot the database in the background while the app runs
+            tauri::async_runtime::spawn(async move {
+                backup_service.run_backup_scheduler().await;
+            });
+            // Commit edits to the Git mirror once they settle
+            tauri::async_runtime::spawn(async move {
+                git_mirror.run_git_mirror().await;
+            });
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             // Basic commands
             greet,
@@ -68,9 +91,50 @@ pub fn run() {
             update_node,
             delete_node,
             move_node,
+            record_node_view,
             // Journal commands
             get_daily_note,
             get_or_create_daily_note,
+            list_journals,
+            get_journal_title_format,
+            set_journal_title_format,
+            get_or_create_weekly_note,
+            get_or_create_monthly_note,
+            get_or_create_quarterly_note,
+            get_or_create_yearly_note,
+            get_period_rollup,
+            get_on_this_day,
+            resurface_nodes,
+            get_date_locale,
+            set_date_locale,
+            parse_date,
+            // Template commands
+            list_templates,
+            apply_template,
+            // Task commands
+            cycle_task_state,
+            set_task_state,
+            list_tasks,
+            get_task_plan,
+            get_task_history,
+            get_task_completions,
+            get_agenda,
+            // Time tracking commands
+            clock_in,
+            clock_out,
+            get_running_clock,
+            get_clock_entries,
+            get_time_report,
+            export_time_report_csv,
+            // Flashcard commands
+            get_due_cards,
+            review_card,
+            get_review_stats,
+            // Habit commands
+            add_habit,
+            remove_habit,
+            list_habits,
+            get_habit_report,
             // Linking commands
             get_linked_references,
             get_unlinked_references,
@@ -86,8 +150,32 @@ pub fn run() {
             // Export commands
             export_to_json,
             import_from_json,
+            create_backup,
+            restore_backup,
+            get_backup_settings,
+            set_backup_settings,
+            list_snapshots,
+            create_snapshot,
+            restore_snapshot,
+            import_markdown_dir,
+            import_roam_json,
+            import_notion_zip,
+            import_enex,
+            export_markdown_dir,
+            publish_site,
+            import_opml,
+            export_node_to_opml,
+            import_org,
+            export_node_to_org,
             export_node_to_markdown,
             export_all_to_markdown,
+            // Git mirror commands
+            get_git_settings,
+            set_git_settings,
+            commit_git_mirror,
+            get_page_history,
+            blame_page,
+            restore_page_from_commit,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,54 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use super::node::Node;
+use super::task::TaskState;
+
+/// Why a node shows up on a given agenda day
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AgendaItemKind {
+    Scheduled,
+    Deadline,
+    Due,
+    /// An open task written on that day's journal page
+    Journal,
+}
+
+impl AgendaItemKind {
+    /// Date key in `node_dates` that produces this kind of item
+    pub fn from_date_key(key: &str) -> Option<AgendaItemKind> {
+        match key {
+            "scheduled" => Some(AgendaItemKind::Scheduled),
+            "deadline" => Some(AgendaItemKind::Deadline),
+            "due" => Some(AgendaItemKind::Due),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgendaItem {
+    pub node: Node,
+    pub kind: AgendaItemKind,
+    pub date: NaiveDate,
+    /// Task state when the node is a task
+    pub state: Option<TaskState>,
+}
+
+/// Everything on the agenda for one calendar day
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgendaDay {
+    pub date: NaiveDate,
+    /// That day's journal page, if it exists
+    pub journal: Option<Node>,
+    pub items: Vec<AgendaItem>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Agenda {
+    /// Open tasks whose date passed before the window started
+    pub overdue: Vec<AgendaItem>,
+    pub today: AgendaDay,
+    pub upcoming: Vec<AgendaDay>,
+}
@@ -0,0 +1,38 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// How the app snapshots its database while it runs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackupSettings {
+    pub enabled: bool,
+    /// Minutes between snapshots
+    pub interval_minutes: u32,
+    /// Folder the snapshots go to; unset means `backups` next to the database
+    pub folder: Option<String>,
+    /// Snapshots kept for the most recent hours, days and weeks
+    pub keep_hourly: usize,
+    pub keep_daily: usize,
+    pub keep_weekly: usize,
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        BackupSettings {
+            enabled: true,
+            interval_minutes: 60,
+            folder: None,
+            keep_hourly: 24,
+            keep_daily: 7,
+            keep_weekly: 8,
+        }
+    }
+}
+
+/// A copy of the database in the backup folder
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub file_name: String,
+    pub created_at: DateTime<Utc>,
+    pub size: u64,
+}
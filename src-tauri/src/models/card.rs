@@ -0,0 +1,42 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+/// SM-2 scheduling state of a flashcard
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CardSchedule {
+    pub ease: f64,
+    pub interval_days: i64,
+    pub repetitions: i64,
+    pub lapses: i64,
+}
+
+impl Default for CardSchedule {
+    fn default() -> Self {
+        CardSchedule { ease: 2.5, interval_days: 0, repetitions: 0, lapses: 0 }
+    }
+}
+
+/// A node studied as a flashcard
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Flashcard {
+    pub node_id: String,
+    pub question: String,
+    /// Cloze answer, or the card's child blocks one per line
+    pub answer: String,
+    pub schedule: CardSchedule,
+    pub due_date: NaiveDate,
+    pub last_reviewed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewStats {
+    pub total_cards: i64,
+    /// Cards never reviewed
+    pub new_cards: i64,
+    pub due_cards: i64,
+    /// Cards with an interval of three weeks or more
+    pub mature_cards: i64,
+    pub reviews_today: i64,
+    /// Share of reviews in the last 30 days graded 3 or better
+    pub retention_30d: Option<f64>,
+}
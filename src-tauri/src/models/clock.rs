@@ -0,0 +1,38 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+/// One clocked stretch of time on a node; `ended_at` is unset while running
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClockEntry {
+    pub id: i64,
+    pub node_id: String,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
+    pub duration_seconds: i64,
+}
+
+/// Time spent on one node (or, in `by_subtree`, on it and its descendants)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeTime {
+    pub node_id: String,
+    /// First line of the node's content
+    pub title: String,
+    pub seconds: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagTime {
+    pub tag: String,
+    pub seconds: i64,
+}
+
+/// Clocked time between two dates (inclusive, UTC days), largest totals first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeReport {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub total_seconds: i64,
+    pub by_node: Vec<NodeTime>,
+    pub by_subtree: Vec<NodeTime>,
+    pub by_tag: Vec<TagTime>,
+}
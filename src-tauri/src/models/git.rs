@@ -0,0 +1,44 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// How the graph is mirrored into a Git repository
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GitSettings {
+    /// Whether edits are committed automatically
+    pub enabled: bool,
+    /// Working tree of the mirror; unset means `repository` next to the database
+    pub folder: Option<String>,
+    /// Seconds without further edits before changes are committed
+    pub debounce_seconds: u64,
+    pub author_name: String,
+    pub author_email: String,
+}
+
+impl Default for GitSettings {
+    fn default() -> Self {
+        GitSettings {
+            enabled: true,
+            folder: None,
+            debounce_seconds: 30,
+            author_name: "Note".to_string(),
+            author_email: "note@localhost".to_string(),
+        }
+    }
+}
+
+/// A commit in the mirror repository
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GitCommit {
+    pub id: String,
+    pub message: String,
+    pub author: String,
+    pub committed_at: DateTime<Utc>,
+}
+
+/// The commit that last changed a block of a mirrored page
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockBlame {
+    pub node_id: String,
+    pub commit: GitCommit,
+}
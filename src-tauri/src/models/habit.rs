@@ -0,0 +1,33 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A habit recorded in journal pages as a `key:: value` line
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Habit {
+    pub key: String,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// One habit's record over a report range
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HabitSummary {
+    pub habit: Habit,
+    /// One entry per report date: done, skipped, or nothing recorded
+    pub days: Vec<Option<bool>>,
+    pub completed: i64,
+    /// Share of the range's days on which the habit was done
+    pub completion_rate: f64,
+    /// Run of done days ending at the end of the range
+    pub current_streak: i64,
+    pub longest_streak: i64,
+}
+
+/// Habits between two dates (inclusive) as a habit-by-day matrix
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HabitReport {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub dates: Vec<NaiveDate>,
+    pub habits: Vec<HabitSummary>,
+}
@@ -0,0 +1,56 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A block read from another tool, before it becomes a node
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ImportedBlock {
+    /// Node ID to keep, e.g. from a Logseq `id::` line
+    pub id: Option<String>,
+    /// Creation and last edit time in the source tool, when it records them
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub content: String,
+    pub properties: HashMap<String, serde_json::Value>,
+    pub tags: Vec<String>,
+    pub children: Vec<ImportedBlock>,
+}
+
+/// A page read from another tool. Journal pages are merged into the
+/// journal page of `journal_date` instead of creating a new page.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ImportedPage {
+    /// Node ID to keep when the page is created rather than merged
+    pub id: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub title: String,
+    pub journal_date: Option<NaiveDate>,
+    /// Other titles `[[links]]` may use for this page
+    pub aliases: Vec<String>,
+    pub properties: HashMap<String, serde_json::Value>,
+    pub tags: Vec<String>,
+    pub blocks: Vec<ImportedBlock>,
+}
+
+/// Progress of a running import, sent once per source file or page
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportProgress {
+    pub processed: usize,
+    pub total: usize,
+    pub current: String,
+}
+
+/// What an import created and what it had to leave out
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportSummary {
+    pub pages_created: usize,
+    /// Existing pages (including journal pages) the import added blocks to
+    pub pages_merged: usize,
+    pub blocks_created: usize,
+    pub links_created: usize,
+    /// `[[link]]` targets that matched no page or block
+    pub unresolved_links: Vec<String>,
+    /// Source files that could not be read, with the reason
+    pub skipped: Vec<String>,
+}
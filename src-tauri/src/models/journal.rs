@@ -0,0 +1,64 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use super::node::Node;
+
+/// A journal page together with the calendar date it belongs to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub date: NaiveDate,
+    pub node: Node,
+}
+
+/// One page of journal entries for the infinite-scroll journal view
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalList {
+    pub journals: Vec<JournalEntry>,
+    pub total: i64,
+    pub has_more: bool,
+}
+
+/// Calendar period that a periodic (rollup) page covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PeriodKind {
+    Week,
+    Month,
+    Quarter,
+    Year,
+}
+
+impl PeriodKind {
+    pub const ALL: [PeriodKind; 4] = [PeriodKind::Week, PeriodKind::Month, PeriodKind::Quarter, PeriodKind::Year];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PeriodKind::Week => "week",
+            PeriodKind::Month => "month",
+            PeriodKind::Quarter => "quarter",
+            PeriodKind::Year => "year",
+        }
+    }
+}
+
+/// Everything written or referenced during one calendar period
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeriodRollup {
+    pub period: PeriodKind,
+    pub key: String,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub page: Option<Node>,
+    pub journals: Vec<JournalEntry>,
+    pub referenced: Vec<Node>,
+    pub tagged: Vec<Node>,
+    pub tag_counts: Vec<(String, i64)>,
+}
+
+/// An old node brought back to the user's attention
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResurfacedNode {
+    pub node: Node,
+    pub journal_date: Option<NaiveDate>,
+    pub days_since_touched: f64,
+}
@@ -1,8 +1,26 @@
 pub mod node;
+pub mod journal;
+pub mod task;
+pub mod agenda;
+pub mod clock;
+pub mod card;
+pub mod habit;
+pub mod import;
+pub mod backup;
+pub mod git;
 
 pub use node::{
     Node,
     CreateNodeRequest,
     UpdateNodeRequest,
     NodeWithChildren
-}; 
\ No newline at end of file
+};
+pub use journal::{JournalEntry, JournalList, PeriodKind, PeriodRollup, ResurfacedNode};
+pub use task::{Priority, Task, TaskCompletion, TaskFilter, TaskGroup, TaskState, TaskTransition};
+pub use agenda::{Agenda, AgendaDay, AgendaItem, AgendaItemKind};
+pub use clock::{ClockEntry, NodeTime, TagTime, TimeReport};
+pub use card::{CardSchedule, Flashcard, ReviewStats};
+pub use habit::{Habit, HabitReport, HabitSummary};
+pub use import::{ImportProgress, ImportSummary, ImportedBlock, ImportedPage};
+pub use backup::{BackupSettings, Snapshot};
+pub use git::{BlockBlame, GitCommit, GitSettings};
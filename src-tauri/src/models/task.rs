@@ -0,0 +1,127 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::node::Node;
+
+/// Workflow state of a task, written as a marker at the start of the content
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum TaskState {
+    Todo,
+    Doing,
+    Done,
+    Canceled,
+}
+
+impl TaskState {
+    pub const ALL: [TaskState; 4] = [TaskState::Todo, TaskState::Doing, TaskState::Done, TaskState::Canceled];
+
+    /// Marker as written in content and stored in the database
+    pub fn marker(&self) -> &'static str {
+        match self {
+            TaskState::Todo => "TODO",
+            TaskState::Doing => "DOING",
+            TaskState::Done => "DONE",
+            TaskState::Canceled => "CANCELED",
+        }
+    }
+
+    pub fn from_marker(marker: &str) -> Option<TaskState> {
+        match marker {
+            "TODO" => Some(TaskState::Todo),
+            "DOING" => Some(TaskState::Doing),
+            "DONE" => Some(TaskState::Done),
+            "CANCELED" | "CANCELLED" => Some(TaskState::Canceled),
+            _ => None,
+        }
+    }
+
+    /// State after a click on the task marker: TODO -> DOING -> DONE -> TODO
+    pub fn next(&self) -> TaskState {
+        match self {
+            TaskState::Todo => TaskState::Doing,
+            TaskState::Doing => TaskState::Done,
+            TaskState::Done | TaskState::Canceled => TaskState::Todo,
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        matches!(self, TaskState::Todo | TaskState::Doing)
+    }
+}
+
+/// `[#A]` / `[#B]` / `[#C]` priority cookie; A sorts first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Priority {
+    A,
+    B,
+    C,
+}
+
+impl Priority {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Priority::A => "A",
+            Priority::B => "B",
+            Priority::C => "C",
+        }
+    }
+
+    pub fn from_letter(letter: &str) -> Option<Priority> {
+        match letter {
+            "A" => Some(Priority::A),
+            "B" => Some(Priority::B),
+            "C" => Some(Priority::C),
+            _ => None,
+        }
+    }
+}
+
+/// A node carrying a task marker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Task {
+    pub node: Node,
+    pub state: TaskState,
+    /// Top-level page the task lives on
+    pub page_id: String,
+    pub due: Option<NaiveDate>,
+    pub deadline: Option<NaiveDate>,
+    pub priority: Option<Priority>,
+    /// `effort::` estimate in minutes
+    pub effort_minutes: Option<i64>,
+    pub state_changed_at: DateTime<Utc>,
+}
+
+/// Tasks of one page in a generated plan
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskGroup {
+    pub page: Node,
+    pub tasks: Vec<Task>,
+}
+
+/// One recorded change of a task's state; `None` means "not a task"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskTransition {
+    pub from_state: Option<TaskState>,
+    pub to_state: Option<TaskState>,
+    pub changed_at: DateTime<Utc>,
+}
+
+/// One logged completion; `scheduled_for` is the date that was due
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskCompletion {
+    pub scheduled_for: Option<NaiveDate>,
+    pub completed_at: DateTime<Utc>,
+}
+
+/// Filters for `list_tasks`; unset fields match everything
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TaskFilter {
+    pub states: Option<Vec<TaskState>>,
+    pub page_id: Option<String>,
+    pub tag: Option<String>,
+    pub due_before: Option<NaiveDate>,
+    pub due_after: Option<NaiveDate>,
+    pub limit: Option<i64>,
+}
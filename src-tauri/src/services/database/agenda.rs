@@ -0,0 +1,134 @@
+use crate::errors::{AppError, AppResult};
+use super::connection::DatabaseService;
+use crate::models::{Agenda, AgendaDay, AgendaItem, AgendaItemKind, TaskState};
+use crate::utils::{format_iso_date, parse_iso_date};
+use chrono::Duration;
+use sqlx::Row;
+use std::collections::HashSet;
+
+/// Longest agenda window, in days after the first day
+pub const MAX_AGENDA_DAYS: u32 = 366;
+
+impl DatabaseService {
+    /// Agenda for `date` and the `days` days after it.
+    ///
+    /// Scheduled, deadline and due dates are listed on their day, open tasks
+    /// written on a journal page show up on that page's day, and open tasks
+    /// whose date already passed are collected as overdue.
+    pub async fn get_agenda(&self, date: &str, days: u32) -> AppResult<Agenda> {
+        let today = parse_iso_date(date)?;
+        if days > MAX_AGENDA_DAYS {
+            return Err(AppError::ConfigurationError(format!(
+                "Agenda window is limited to {} days",
+                MAX_AGENDA_DAYS
+            )));
+        }
+        let end = today
+            .checked_add_signed(Duration::days(days as i64))
+            .ok_or_else(|| AppError::InvalidDate(format!("Agenda window after {} is out of range", date)))?;
+        let (start_iso, end_iso) = (format_iso_date(today), format_iso_date(end));
+
+        let dated = sqlx::query(
+            r#"
+            SELECT d.node_id, d.key, d.date, t.state
+            FROM node_dates d
+            LEFT JOIN tasks t ON t.node_id = d.node_id
+            WHERE d.key IN ('scheduled', 'deadline', 'due') AND d.date BETWEEN ? AND ?
+            ORDER BY d.date, d.key, d.node_id
+            "#
+        )
+        .bind(&start_iso)
+        .bind(&end_iso)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+
+        let journal_tasks = sqlx::query(
+            r#"
+            WITH RECURSIVE tree(id, journal_date) AS (
+                SELECT node_id, journal_date FROM journals WHERE journal_date BETWEEN ? AND ?
+                UNION ALL
+                SELECT n.id, tree.journal_date FROM nodes n
+                INNER JOIN tree ON n.parent_id = tree.id
+            )
+            SELECT tree.id AS node_id, tree.journal_date AS date, t.state
+            FROM tree
+            JOIN tasks t ON t.node_id = tree.id
+            JOIN nodes n ON n.id = tree.id
+            WHERE t.state IN ('TODO', 'DOING')
+            ORDER BY tree.journal_date, n.created_at
+            "#
+        )
+        .bind(&start_iso)
+        .bind(&end_iso)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+
+        let overdue_rows = sqlx::query(
+            r#"
+            SELECT d.node_id, d.key, d.date, t.state
+            FROM node_dates d
+            JOIN tasks t ON t.node_id = d.node_id
+            WHERE d.key IN ('scheduled', 'deadline', 'due') AND d.date < ?
+              AND t.state IN ('TODO', 'DOING')
+            ORDER BY d.date, d.key, d.node_id
+            "#
+        )
+        .bind(&start_iso)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+
+        let mut days_in_window = Vec::new();
+        for offset in 0..=days {
+            let day = today + Duration::days(offset as i64);
+            days_in_window.push(AgendaDay {
+                date: day,
+                journal: match self.find_journal_node_id(day).await? {
+                    Some(node_id) => Some(self.get_node(&node_id).await?),
+                    None => None,
+                },
+                items: Vec::new(),
+            });
+        }
+
+        let mut seen = HashSet::new();
+        for row in dated {
+            let key: String = row.get("key");
+            let item = self.agenda_item(&row, AgendaItemKind::from_date_key(&key)).await?;
+            seen.insert((item.node.id.clone(), item.date));
+            days_in_window[(item.date - today).num_days() as usize].items.push(item);
+        }
+        for row in journal_tasks {
+            let item = self.agenda_item(&row, Some(AgendaItemKind::Journal)).await?;
+            if seen.insert((item.node.id.clone(), item.date)) {
+                days_in_window[(item.date - today).num_days() as usize].items.push(item);
+            }
+        }
+
+        let mut overdue = Vec::new();
+        let mut overdue_nodes = HashSet::new();
+        for row in overdue_rows {
+            let node_id: String = row.get("node_id");
+            if overdue_nodes.insert(node_id) {
+                let key: String = row.get("key");
+                overdue.push(self.agenda_item(&row, AgendaItemKind::from_date_key(&key)).await?);
+            }
+        }
+
+        let mut days_in_window = days_in_window.into_iter();
+        let today = days_in_window.next().expect("agenda window contains today");
+        Ok(Agenda { overdue, today, upcoming: days_in_window.collect() })
+    }
+
+    async fn agenda_item(&self, row: &sqlx::sqlite::SqliteRow, kind: Option<AgendaItemKind>) -> AppResult<AgendaItem> {
+        let kind = kind.ok_or_else(|| AppError::Internal("Unexpected agenda date key".to_string()))?;
+        Ok(AgendaItem {
+            node: self.get_node(&row.get::<String, _>("node_id")).await?,
+            kind,
+            date: parse_iso_date(&row.get::<String, _>("date"))?,
+            state: row.get::<Option<String>, _>("state").as_deref().and_then(TaskState::from_marker),
+        })
+    }
+}
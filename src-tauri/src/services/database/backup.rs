@@ -0,0 +1,508 @@
+use crate::errors::{AppError, AppResult};
+use super::connection::DatabaseService;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{Row, SqliteConnection};
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Component, Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// Layout version of the archives `create_backup` writes
+pub const BACKUP_FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_PATH: &str = "manifest.json";
+
+/// Rows read from the database at a time while writing a backup
+const BACKUP_PAGE_SIZE: i64 = 500;
+
+/// Records read ahead of the database while restoring
+const RESTORE_QUEUE_SIZE: usize = 1024;
+
+/// Pages of rows read ahead of the archive writer while backing up
+const BACKUP_QUEUE_SIZE: usize = 8;
+
+/// Description of a backup archive, stored in it as `manifest.json`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub format_version: u32,
+    pub app_version: String,
+    pub created_at: DateTime<Utc>,
+    pub tables: Vec<BackupTable>,
+    /// Every other file in the archive with its size and SHA-256
+    pub files: Vec<BackupFile>,
+}
+
+/// A table stored as `tables/<name>.jsonl`, one JSON object per row
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupTable {
+    pub name: String,
+    pub columns: Vec<String>,
+    pub rows: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackupFile {
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+/// What a backup holds, or a restore brought back
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct BackupSummary {
+    pub nodes: usize,
+    /// Rows across all tables, nodes included
+    pub rows: usize,
+    pub attachments: usize,
+}
+
+impl DatabaseService {
+    /// Write the whole graph to a zip archive at `path`: every table as
+    /// newline-delimited JSON, the attachments folder, and a manifest with
+    /// the checksum of each file.
+    ///
+    /// Tables are read a page of rows at a time inside one read
+    /// transaction, and everything is compressed as it is written, so a
+    /// large graph is never held in memory and the archive is a consistent
+    /// snapshot. The archive only appears at `path` once it is complete.
+    pub async fn create_backup(&self, path: &Path) -> AppResult<BackupSummary> {
+        let partial = partial_path(path);
+        let result = self.write_backup(&partial).await;
+        match result {
+            Ok(summary) => {
+                fs::rename(&partial, path)
+                    .map_err(|e| AppError::ExportFailed(format!("Failed to write backup: {}", e)))?;
+                Ok(summary)
+            }
+            Err(e) => {
+                let _ = fs::remove_file(&partial);
+                Err(e)
+            }
+        }
+    }
+
+    async fn write_backup(&self, path: &Path) -> AppResult<BackupSummary> {
+        // The archive is written on a blocking thread while the tables are
+        // read here, a page of rows at a time
+        let (sender, receiver) = tokio::sync::mpsc::channel::<BackupRecord>(BACKUP_QUEUE_SIZE);
+        let archive_path = path.to_path_buf();
+        let attachments = self.attachments_dir();
+        let writer = tokio::task::spawn_blocking(move || write_archive(&archive_path, &attachments, receiver));
+
+        let result = self.read_backup_tables(&sender).await;
+        if result.is_ok() {
+            let _ = sender.send(BackupRecord::Done).await;
+        }
+        drop(sender);
+        let written = writer.await.map_err(|e| AppError::Internal(e.to_string()))?;
+        // A failed read is the cause when both sides stopped
+        result?;
+        written
+    }
+
+    /// Send every table's rows to the archive writer from one read
+    /// transaction, so the backup is a consistent snapshot
+    async fn read_backup_tables(&self, sender: &tokio::sync::mpsc::Sender<BackupRecord>) -> AppResult<()> {
+        let query_failed = |e: sqlx::Error| AppError::DatabaseQueryFailed(e.to_string());
+        let mut tx = self.pool.begin().await
+            .map_err(|e| AppError::DatabaseConnectionFailed(e.to_string()))?;
+        for table in backup_tables(&mut tx, "main").await? {
+            let columns = table_columns(&mut tx, "main", &table).await?;
+            let fields: Vec<String> = columns.iter().map(|column| format!("'{0}', \"{0}\"", column)).collect();
+            let select = format!(
+                "SELECT rowid, json_object({}) FROM \"{}\" WHERE rowid > ? ORDER BY rowid LIMIT ?",
+                fields.join(", "),
+                table
+            );
+
+            // A closed queue means the writer failed; its error is reported
+            if sender.send(BackupRecord::Table(table, columns)).await.is_err() {
+                return Ok(());
+            }
+            let mut last_rowid = i64::MIN;
+            loop {
+                let page = sqlx::query(&select)
+                    .bind(last_rowid)
+                    .bind(BACKUP_PAGE_SIZE)
+                    .fetch_all(&mut *tx)
+                    .await
+                    .map_err(query_failed)?;
+                let Some(last) = page.last() else {
+                    break;
+                };
+                last_rowid = last.get(0);
+                let rows = page.iter().map(|row| row.get::<String, _>(1)).collect();
+                if sender.send(BackupRecord::Rows(rows)).await.is_err() {
+                    return Ok(());
+                }
+            }
+        }
+        tx.commit().await.map_err(query_failed)
+    }
+
+    /// Replace the graph with the contents of a backup archive.
+    ///
+    /// Every file is checked against the manifest before anything changes,
+    /// so a damaged archive is refused as a whole. Tables are then emptied
+    /// and refilled in one transaction, streaming the records from the
+    /// archive; columns the backup does not have get their defaults, and
+    /// tables or columns this version no longer has are left out.
+    /// Attachments are written back into the attachments folder.
+    pub async fn restore_backup(&self, path: &Path) -> AppResult<BackupSummary> {
+        let manifest = verify_backup(path)?;
+
+        // A foreign key the restored rows break fails the commit, which
+        // rolls the whole restore back
+        let archive_path = path.to_path_buf();
+        let tables = manifest.tables.clone();
+        let mut summary = self.with_immediate_transaction(|conn| Box::pin(async move {
+            restore_tables(conn, &archive_path, &tables).await
+        })).await?;
+
+        let mut archive = open_backup(path)?;
+        let attachments = self.attachments_dir();
+        for file in manifest.files.iter().filter(|file| file.path.starts_with("attachments/")) {
+            let Some(relative) = safe_relative_path(&file.path["attachments/".len()..]) else {
+                continue;
+            };
+            let target = attachments.join(relative);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let mut entry = archive.by_name(&file.path).map_err(|e| invalid_backup(&e))?;
+            io::copy(&mut entry, &mut File::create(&target)?)?;
+            summary.attachments += 1;
+        }
+
+        self.refresh_search_index().await?;
+        Ok(summary)
+    }
+}
+
+/// Empty the tables and refill them from the backup's records
+async fn restore_tables(
+    conn: &mut SqliteConnection,
+    path: &Path,
+    tables: &[BackupTable],
+) -> AppResult<BackupSummary> {
+    let query_failed = |e: sqlx::Error| AppError::DatabaseQueryFailed(e.to_string());
+    // Rows go back in any order; references are checked at commit
+    sqlx::query("PRAGMA defer_foreign_keys = ON")
+        .execute(&mut *conn)
+        .await
+        .map_err(query_failed)?;
+
+    let current = backup_tables(conn, "main").await?;
+    for table in &current {
+        sqlx::query(&format!("DELETE FROM \"{}\"", table))
+            .execute(&mut *conn)
+            .await
+            .map_err(query_failed)?;
+    }
+
+    // The insert for each restored table, reading columns from the record
+    let mut inserts = Vec::new();
+    for table in tables.iter().filter(|table| current.contains(&table.name)) {
+        let existing = table_columns(conn, "main", &table.name).await?;
+        let columns: Vec<&String> = table.columns.iter().filter(|column| existing.contains(column)).collect();
+        if columns.is_empty() {
+            continue;
+        }
+        let names: Vec<String> = columns.iter().map(|column| format!("\"{}\"", column)).collect();
+        let values: Vec<String> = columns.iter().map(|column| format!("json_extract(?1, '$.\"{}\"')", column)).collect();
+        let insert = format!("INSERT INTO \"{}\" ({}) VALUES ({})", table.name, names.join(", "), values.join(", "));
+        inserts.push((table.name.clone(), insert));
+    }
+
+    // Records are read off the archive on a blocking thread and handed
+    // over a bounded queue
+    let (sender, mut receiver) = tokio::sync::mpsc::channel::<AppResult<(usize, String)>>(RESTORE_QUEUE_SIZE);
+    let entries: Vec<String> = inserts.iter().map(|(table, _)| format!("tables/{}.jsonl", table)).collect();
+    let archive_path = path.to_path_buf();
+    let reader = tokio::task::spawn_blocking(move || {
+        let send_lines = || -> AppResult<()> {
+            let mut archive = open_backup(&archive_path)?;
+            for (index, entry) in entries.iter().enumerate() {
+                let file = archive.by_name(entry).map_err(|e| invalid_backup(&e))?;
+                for line in BufReader::new(file).lines() {
+                    let line = line?;
+                    if !line.trim().is_empty() && sender.blocking_send(Ok((index, line))).is_err() {
+                        return Ok(());
+                    }
+                }
+            }
+            Ok(())
+        };
+        if let Err(e) = send_lines() {
+            let _ = sender.blocking_send(Err(e));
+        }
+    });
+
+    let mut summary = BackupSummary::default();
+    let mut outcome = Ok(());
+    while let Some(record) = receiver.recv().await {
+        let (index, line) = match record {
+            Ok(record) => record,
+            Err(e) => {
+                outcome = Err(e);
+                break;
+            }
+        };
+        let (table, insert) = &inserts[index];
+        if let Err(e) = sqlx::query(insert).bind(&line).execute(&mut *conn).await {
+            outcome = Err(AppError::DatabaseQueryFailed(format!("Failed to restore a row of {}: {}", table, e)));
+            break;
+        }
+        if table == "nodes" {
+            summary.nodes += 1;
+        }
+        summary.rows += 1;
+    }
+    drop(receiver);
+    reader.await.map_err(|e| AppError::Internal(e.to_string()))?;
+    outcome?;
+    Ok(summary)
+}
+
+/// What the database side of `write_backup` hands the archive writer
+enum BackupRecord {
+    /// The rows that follow belong to this table, with these columns
+    Table(String, Vec<String>),
+    /// A page of rows as JSON objects
+    Rows(Vec<String>),
+    /// Every table was read; the archive can be completed
+    Done,
+}
+
+/// Write the archive at `path` from the tables `records` brings, then the
+/// attachments under `attachments` and the manifest. Without `Done` the
+/// tables were not all read, and the archive is left incomplete.
+fn write_archive(
+    path: &Path,
+    attachments: &Path,
+    mut records: tokio::sync::mpsc::Receiver<BackupRecord>,
+) -> AppResult<BackupSummary> {
+    let failed = |e: &dyn std::fmt::Display| AppError::ExportFailed(format!("Failed to write backup: {}", e));
+    let file = File::create(path).map_err(|e| failed(&e))?;
+    let mut writer = HashingWriter::new(ZipWriter::new(BufWriter::new(file)));
+    let options = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .large_file(true);
+
+    let mut manifest = BackupManifest {
+        format_version: BACKUP_FORMAT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: Utc::now(),
+        tables: Vec::new(),
+        files: Vec::new(),
+    };
+    let mut summary = BackupSummary::default();
+    let mut current: Option<BackupTable> = None;
+    let mut complete = false;
+
+    while let Some(record) = records.blocking_recv() {
+        let table_done = matches!(record, BackupRecord::Table(..) | BackupRecord::Done);
+        if let Some(table) = current.take_if(|_| table_done) {
+            manifest.files.push(writer.take_file(format!("tables/{}.jsonl", table.name)));
+            if table.name == "nodes" {
+                summary.nodes = table.rows;
+            }
+            summary.rows += table.rows;
+            manifest.tables.push(table);
+        }
+        match record {
+            BackupRecord::Table(name, columns) => {
+                let entry = format!("tables/{}.jsonl", name);
+                writer.inner.start_file(entry.as_str(), options).map_err(|e| failed(&e))?;
+                current = Some(BackupTable { name, columns, rows: 0 });
+            }
+            BackupRecord::Rows(rows) => {
+                for row in &rows {
+                    writeln!(writer, "{}", row).map_err(|e| failed(&e))?;
+                }
+                if let Some(table) = current.as_mut() {
+                    table.rows += rows.len();
+                }
+            }
+            BackupRecord::Done => {
+                complete = true;
+                break;
+            }
+        }
+    }
+    if !complete {
+        return Err(failed(&"the database could not be read to the end"));
+    }
+
+    for (file, name) in attachment_files(attachments).map_err(|e| failed(&e))? {
+        let entry = format!("attachments/{}", name);
+        writer.inner.start_file(entry.as_str(), options).map_err(|e| failed(&e))?;
+        io::copy(&mut File::open(&file).map_err(|e| failed(&e))?, &mut writer).map_err(|e| failed(&e))?;
+        manifest.files.push(writer.take_file(entry));
+        summary.attachments += 1;
+    }
+
+    let mut zip = writer.inner;
+    zip.start_file(MANIFEST_PATH, options).map_err(|e| failed(&e))?;
+    serde_json::to_writer_pretty(&mut zip, &manifest).map_err(|e| failed(&e))?;
+    zip.finish()
+        .map_err(|e| failed(&e))?
+        .flush()
+        .map_err(|e| failed(&e))?;
+    Ok(summary)
+}
+
+/// Open a backup archive and check every file against its manifest
+pub fn verify_backup(path: &Path) -> AppResult<BackupManifest> {
+    let mut archive = open_backup(path)?;
+    let manifest: BackupManifest = {
+        let entry = archive.by_name(MANIFEST_PATH).map_err(|e| invalid_backup(&e))?;
+        serde_json::from_reader(entry).map_err(|e| invalid_backup(&e))?
+    };
+    if manifest.format_version > BACKUP_FORMAT_VERSION {
+        return Err(invalid_backup(&format!(
+            "format version {} is newer than this app supports",
+            manifest.format_version
+        )));
+    }
+
+    for table in &manifest.tables {
+        let entry = format!("tables/{}.jsonl", table.name);
+        if !manifest.files.iter().any(|file| file.path == entry) {
+            return Err(invalid_backup(&format!("{} is missing from the manifest", entry)));
+        }
+    }
+    for file in &manifest.files {
+        let entry = archive.by_name(&file.path).map_err(|e| invalid_backup(&format!("{}: {}", file.path, e)))?;
+        let mut writer = HashingWriter::new(io::sink());
+        io::copy(&mut BufReader::new(entry), &mut writer).map_err(|e| invalid_backup(&e))?;
+        let found = writer.finish(file.path.clone());
+        if found.size != file.size || found.sha256 != file.sha256 {
+            return Err(invalid_backup(&format!("{} does not match its checksum", file.path)));
+        }
+    }
+    Ok(manifest)
+}
+
+fn open_backup(path: &Path) -> AppResult<ZipArchive<BufReader<File>>> {
+    let file = File::open(path)
+        .map_err(|e| AppError::FileNotFound(format!("Failed to open backup: {}", e)))?;
+    ZipArchive::new(BufReader::new(file)).map_err(|e| invalid_backup(&e))
+}
+
+fn invalid_backup(e: &dyn std::fmt::Display) -> AppError {
+    AppError::InvalidPageData(format!("Invalid backup: {}", e))
+}
+
+/// Where a backup is written until it is complete
+pub(super) fn partial_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".partial");
+    path.with_file_name(name)
+}
+
+/// Tables of the `schema` database a backup holds: all but SQLite's own,
+/// full-text indexes (rebuilt from `nodes`) and their storage
+pub(super) async fn backup_tables(conn: &mut SqliteConnection, schema: &str) -> AppResult<Vec<String>> {
+    let query = format!(
+        "SELECT name, sql FROM \"{}\".sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name",
+        schema
+    );
+    let rows = sqlx::query(&query)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+    let tables: Vec<(String, String)> = rows
+        .iter()
+        .map(|row| (row.get("name"), row.get::<Option<String>, _>("sql").unwrap_or_default()))
+        .collect();
+    let virtual_tables: Vec<&str> = tables
+        .iter()
+        .filter(|(_, sql)| sql.to_uppercase().starts_with("CREATE VIRTUAL TABLE"))
+        .map(|(name, _)| name.as_str())
+        .collect();
+    Ok(tables
+        .iter()
+        .map(|(name, _)| name)
+        .filter(|name| !virtual_tables.iter().any(|table| *name == table || name.starts_with(&format!("{}_", table))))
+        .cloned()
+        .collect())
+}
+
+pub(super) async fn table_columns(conn: &mut SqliteConnection, schema: &str, table: &str) -> AppResult<Vec<String>> {
+    sqlx::query_scalar("SELECT name FROM pragma_table_info(?, ?) ORDER BY cid")
+        .bind(table)
+        .bind(schema)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))
+}
+
+/// Files under `dir` with their path relative to it, `/`-separated
+fn attachment_files(dir: &Path) -> io::Result<Vec<(PathBuf, String)>> {
+    let mut files = Vec::new();
+    if !dir.is_dir() {
+        return Ok(files);
+    }
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(folder) = pending.pop() {
+        let mut entries: Vec<PathBuf> = fs::read_dir(&folder)?.map(|entry| entry.map(|entry| entry.path())).collect::<Result<_, _>>()?;
+        entries.sort();
+        for path in entries {
+            if path.is_dir() {
+                pending.push(path);
+            } else if let Ok(relative) = path.strip_prefix(dir) {
+                let name: Vec<String> = relative.components().map(|part| part.as_os_str().to_string_lossy().into_owned()).collect();
+                files.push((path.clone(), name.join("/")));
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// `name` as a path that stays inside the folder it is joined to
+fn safe_relative_path(name: &str) -> Option<PathBuf> {
+    let path = Path::new(name);
+    let normal = path.components().all(|part| matches!(part, Component::Normal(_)));
+    (normal && !name.is_empty()).then(|| path.to_path_buf())
+}
+
+/// Passes writes through while counting and hashing them
+struct HashingWriter<W> {
+    inner: W,
+    hasher: Sha256,
+    size: u64,
+}
+
+impl<W: Write> HashingWriter<W> {
+    fn new(inner: W) -> Self {
+        HashingWriter { inner, hasher: Sha256::new(), size: 0 }
+    }
+
+    fn finish(mut self, path: String) -> BackupFile {
+        self.take_file(path)
+    }
+
+    /// The file written since the last call, keeping the writer for the next
+    fn take_file(&mut self, path: String) -> BackupFile {
+        let hasher = std::mem::take(&mut self.hasher);
+        let size = std::mem::take(&mut self.size);
+        BackupFile { path, size, sha256: format!("{:x}", hasher.finalize()) }
+    }
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
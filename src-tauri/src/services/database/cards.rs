@@ -0,0 +1,182 @@
+use crate::errors::{AppError, AppResult};
+use super::connection::DatabaseService;
+use crate::models::{CardSchedule, Flashcard, Node, ReviewStats};
+use crate::utils::{format_iso_date, has_card_tag, parse_iso_date, sm2_review, split_card_text, CARD_TAG};
+use chrono::{Duration, Local, NaiveDate, Utc};
+use sqlx::sqlite::SqliteRow;
+use sqlx::Row;
+
+/// Interval from which a card counts as mature
+const MATURE_INTERVAL_DAYS: i64 = 21;
+
+impl DatabaseService {
+    /// Track `node` as a flashcard while it is tagged `#card`; new cards are
+    /// due on `today`
+    pub async fn sync_card(&self, node: &Node, today: NaiveDate) -> AppResult<()> {
+        let is_card = node.tags.iter().any(|tag| tag == CARD_TAG) || has_card_tag(&node.content);
+        if is_card {
+            sqlx::query("INSERT OR IGNORE INTO cards (node_id, due_date) VALUES (?, ?)")
+                .bind(&node.id)
+                .bind(format_iso_date(today))
+                .execute(&self.pool)
+                .await
+                .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+        } else {
+            sqlx::query("DELETE FROM cards WHERE node_id = ?")
+                .bind(&node.id)
+                .execute(&self.pool)
+                .await
+                .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    /// Cards due on or before `date`, most overdue first
+    pub async fn get_due_cards(&self, date: &str, limit: i64) -> AppResult<Vec<Flashcard>> {
+        let date = parse_iso_date(date)?;
+        let rows = sqlx::query("SELECT * FROM cards WHERE due_date <= ? ORDER BY due_date, node_id LIMIT ?")
+            .bind(format_iso_date(date))
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+
+        let mut cards = Vec::new();
+        for row in rows {
+            cards.push(self.flashcard_from_row(&row).await?);
+        }
+        Ok(cards)
+    }
+
+    /// Record a review graded 0-5 today and reschedule the card
+    pub async fn review_card(&self, node_id: &str, grade: u8) -> AppResult<Flashcard> {
+        self.review_card_at(node_id, grade, Local::now().date_naive()).await
+    }
+
+    pub async fn review_card_at(&self, node_id: &str, grade: u8, today: NaiveDate) -> AppResult<Flashcard> {
+        let card_id = node_id.to_string();
+
+        self.with_immediate_transaction(|conn| Box::pin(async move {
+            let node_id = card_id;
+            let row = sqlx::query("SELECT * FROM cards WHERE node_id = ?")
+                .bind(&node_id)
+                .fetch_optional(&mut *conn)
+                .await
+                .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?
+                .ok_or_else(|| AppError::NotFound(format!("Node {} is not a flashcard", node_id)))?;
+
+            let next = sm2_review(&schedule_from_row(&row), grade)?;
+            let due_date = today
+                .checked_add_signed(Duration::days(next.interval_days))
+                .ok_or_else(|| AppError::InvalidDate("Next review is out of range".to_string()))?;
+            let now = Utc::now();
+
+            sqlx::query(
+                r#"
+                UPDATE cards SET ease = ?, interval_days = ?, repetitions = ?, lapses = ?,
+                                 due_date = ?, last_reviewed_at = ?
+                WHERE node_id = ?
+                "#
+            )
+            .bind(next.ease)
+            .bind(next.interval_days)
+            .bind(next.repetitions)
+            .bind(next.lapses)
+            .bind(format_iso_date(due_date))
+            .bind(now)
+            .bind(&node_id)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+
+            sqlx::query(
+                r#"
+                INSERT INTO card_reviews (node_id, grade, interval_days, ease, review_date, reviewed_at)
+                VALUES (?, ?, ?, ?, ?, ?)
+                "#
+            )
+            .bind(&node_id)
+            .bind(grade as i64)
+            .bind(next.interval_days)
+            .bind(next.ease)
+            .bind(format_iso_date(today))
+            .bind(now)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+            Ok(())
+        })).await?;
+
+        let row = sqlx::query("SELECT * FROM cards WHERE node_id = ?")
+            .bind(node_id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+        self.flashcard_from_row(&row).await
+    }
+
+    /// Collection and review statistics as of `date`
+    pub async fn get_review_stats(&self, date: &str) -> AppResult<ReviewStats> {
+        let date = parse_iso_date(date)?;
+        let iso_date = format_iso_date(date);
+        let window_start = format_iso_date(date - Duration::days(29));
+
+        let row = sqlx::query(
+            r#"
+            SELECT COUNT(*) AS total_cards,
+                   COALESCE(SUM(last_reviewed_at IS NULL), 0) AS new_cards,
+                   COALESCE(SUM(due_date <= ?1), 0) AS due_cards,
+                   COALESCE(SUM(interval_days >= ?2), 0) AS mature_cards,
+                   (SELECT COUNT(*) FROM card_reviews WHERE review_date = ?1) AS reviews_today,
+                   (SELECT AVG(grade >= 3) FROM card_reviews WHERE review_date BETWEEN ?3 AND ?1) AS retention_30d
+            FROM cards
+            "#
+        )
+        .bind(&iso_date)
+        .bind(MATURE_INTERVAL_DAYS)
+        .bind(&window_start)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+
+        Ok(ReviewStats {
+            total_cards: row.get("total_cards"),
+            new_cards: row.get("new_cards"),
+            due_cards: row.get("due_cards"),
+            mature_cards: row.get("mature_cards"),
+            reviews_today: row.get("reviews_today"),
+            retention_30d: row.get("retention_30d"),
+        })
+    }
+
+    async fn flashcard_from_row(&self, row: &SqliteRow) -> AppResult<Flashcard> {
+        let node_id: String = row.get("node_id");
+        let tree = self.get_node_with_children(&node_id).await?;
+        let (question, cloze_answer) = split_card_text(&tree.node.content);
+        let answer = cloze_answer.unwrap_or_else(|| {
+            tree.child_nodes
+                .iter()
+                .map(|child| child.node.content.as_str())
+                .collect::<Vec<_>>()
+                .join("\n")
+        });
+
+        Ok(Flashcard {
+            node_id,
+            question,
+            answer,
+            schedule: schedule_from_row(row),
+            due_date: parse_iso_date(&row.get::<String, _>("due_date"))?,
+            last_reviewed_at: row.get("last_reviewed_at"),
+        })
+    }
+}
+
+fn schedule_from_row(row: &SqliteRow) -> CardSchedule {
+    CardSchedule {
+        ease: row.get("ease"),
+        interval_days: row.get("interval_days"),
+        repetitions: row.get("repetitions"),
+        lapses: row.get("lapses"),
+    }
+}
@@ -0,0 +1,294 @@
+use crate::errors::{AppError, AppResult};
+use super::connection::DatabaseService;
+use crate::models::{ClockEntry, NodeTime, TagTime, TimeReport};
+use crate::utils::{csv_record, parse_iso_date};
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::sqlite::SqliteRow;
+use sqlx::Row;
+use std::collections::{BTreeSet, HashMap};
+
+/// A clock entry cut to the report window
+struct ClippedEntry {
+    entry: ClockEntry,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+}
+
+/// Node data needed to roll time up to ancestors and tags
+struct ReportNode {
+    parent_id: Option<String>,
+    title: String,
+    tags: Vec<String>,
+}
+
+impl DatabaseService {
+    /// Start the clock on a node
+    pub async fn clock_in(&self, node_id: &str) -> AppResult<ClockEntry> {
+        self.clock_in_at(node_id, Utc::now()).await
+    }
+
+    /// Start the clock on a node at `at`; fails while another clock runs
+    pub async fn clock_in_at(&self, node_id: &str, at: DateTime<Utc>) -> AppResult<ClockEntry> {
+        self.get_node(node_id).await?;
+        let node_id = node_id.to_string();
+
+        let id = self.with_immediate_transaction(|conn| Box::pin(async move {
+            let running: Option<String> = sqlx::query_scalar("SELECT node_id FROM node_clock_entries WHERE ended_at IS NULL")
+                .fetch_optional(&mut *conn)
+                .await
+                .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+            if let Some(running) = running {
+                return Err(AppError::ClockConflict(format!("A clock is already running on node {}", running)));
+            }
+
+            let result = sqlx::query("INSERT INTO node_clock_entries (node_id, started_at) VALUES (?, ?)")
+                .bind(&node_id)
+                .bind(at)
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+            Ok(result.last_insert_rowid())
+        })).await?;
+
+        self.get_clock_entry(id).await
+    }
+
+    /// Stop the running clock; with `node_id`, only if it runs on that node
+    pub async fn clock_out(&self, node_id: Option<&str>) -> AppResult<ClockEntry> {
+        self.clock_out_at(node_id, Utc::now()).await
+    }
+
+    pub async fn clock_out_at(&self, node_id: Option<&str>, at: DateTime<Utc>) -> AppResult<ClockEntry> {
+        let expected = node_id.map(str::to_string);
+
+        let id = self.with_immediate_transaction(|conn| Box::pin(async move {
+            let running = sqlx::query("SELECT id, node_id, started_at FROM node_clock_entries WHERE ended_at IS NULL")
+                .fetch_optional(&mut *conn)
+                .await
+                .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?
+                .ok_or_else(|| AppError::NotFound("No clock is running".to_string()))?;
+
+            let running_node: String = running.get("node_id");
+            if expected.as_ref().is_some_and(|expected| *expected != running_node) {
+                return Err(AppError::ClockConflict(format!("The running clock belongs to node {}", running_node)));
+            }
+            if at < running.get::<DateTime<Utc>, _>("started_at") {
+                return Err(AppError::ClockConflict("A clock cannot stop before it started".to_string()));
+            }
+
+            let id: i64 = running.get("id");
+            sqlx::query("UPDATE node_clock_entries SET ended_at = ? WHERE id = ?")
+                .bind(at)
+                .bind(id)
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+            Ok(id)
+        })).await?;
+
+        self.get_clock_entry(id).await
+    }
+
+    /// The clock that is currently running, if any
+    pub async fn get_running_clock(&self) -> AppResult<Option<ClockEntry>> {
+        let row = sqlx::query("SELECT * FROM node_clock_entries WHERE ended_at IS NULL")
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+        Ok(row.map(|row| clock_entry_from_row(&row, Utc::now())))
+    }
+
+    /// Every clock entry of a node, newest first
+    pub async fn get_clock_entries(&self, node_id: &str) -> AppResult<Vec<ClockEntry>> {
+        let rows = sqlx::query("SELECT * FROM node_clock_entries WHERE node_id = ? ORDER BY started_at DESC, id DESC")
+            .bind(node_id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+        let now = Utc::now();
+        Ok(rows.iter().map(|row| clock_entry_from_row(row, now)).collect())
+    }
+
+    /// Time clocked between `start` and `end` (inclusive UTC days).
+    ///
+    /// Subtree totals include every descendant's time, and a node's time
+    /// counts once towards each tag on it or any of its ancestors.
+    pub async fn get_time_report(&self, start: &str, end: &str) -> AppResult<TimeReport> {
+        let (start, end) = (parse_iso_date(start)?, parse_iso_date(end)?);
+        let entries = self.clipped_clock_entries(start, end).await?;
+        let nodes = self.report_nodes(&entries).await?;
+
+        let mut by_node: HashMap<&str, i64> = HashMap::new();
+        let mut by_subtree: HashMap<&str, i64> = HashMap::new();
+        let mut by_tag: HashMap<&str, i64> = HashMap::new();
+        let mut total_seconds = 0;
+
+        for clipped in &entries {
+            let seconds = (clipped.to - clipped.from).num_seconds();
+            total_seconds += seconds;
+            *by_node.entry(clipped.entry.node_id.as_str()).or_insert(0) += seconds;
+
+            let mut tags = BTreeSet::new();
+            let mut current = Some(clipped.entry.node_id.as_str());
+            while let Some(id) = current {
+                *by_subtree.entry(id).or_insert(0) += seconds;
+                let node = nodes.get(id);
+                tags.extend(node.into_iter().flat_map(|node| node.tags.iter().map(String::as_str)));
+                current = node.and_then(|node| node.parent_id.as_deref());
+            }
+            for tag in tags {
+                *by_tag.entry(tag).or_insert(0) += seconds;
+            }
+        }
+
+        let to_node_times = |totals: HashMap<&str, i64>| {
+            let mut times: Vec<NodeTime> = totals
+                .into_iter()
+                .map(|(id, seconds)| NodeTime {
+                    node_id: id.to_string(),
+                    title: nodes.get(id).map(|node| node.title.clone()).unwrap_or_default(),
+                    seconds,
+                })
+                .collect();
+            times.sort_by(|a, b| b.seconds.cmp(&a.seconds).then_with(|| a.node_id.cmp(&b.node_id)));
+            times
+        };
+        let mut by_tag: Vec<TagTime> = by_tag
+            .into_iter()
+            .map(|(tag, seconds)| TagTime { tag: tag.to_string(), seconds })
+            .collect();
+        by_tag.sort_by(|a, b| b.seconds.cmp(&a.seconds).then_with(|| a.tag.cmp(&b.tag)));
+
+        Ok(TimeReport {
+            start,
+            end,
+            total_seconds,
+            by_node: to_node_times(by_node),
+            by_subtree: to_node_times(by_subtree),
+            by_tag,
+        })
+    }
+
+    /// Clock entries between `start` and `end` as CSV, one row per entry
+    pub async fn export_time_report_csv(&self, start: &str, end: &str) -> AppResult<String> {
+        let (start, end) = (parse_iso_date(start)?, parse_iso_date(end)?);
+        let entries = self.clipped_clock_entries(start, end).await?;
+        let nodes = self.report_nodes(&entries).await?;
+
+        let mut csv = csv_record(&["node_id", "title", "page", "tags", "started_at", "ended_at", "seconds"]);
+        for clipped in &entries {
+            let node = nodes.get(clipped.entry.node_id.as_str());
+            let mut page = node;
+            while let Some(parent) = page.and_then(|node| node.parent_id.as_deref()).and_then(|id| nodes.get(id)) {
+                page = Some(parent);
+            }
+            csv.push_str(&csv_record(&[
+                clipped.entry.node_id.clone(),
+                node.map(|node| node.title.clone()).unwrap_or_default(),
+                page.map(|page| page.title.clone()).unwrap_or_default(),
+                node.map(|node| node.tags.join(";")).unwrap_or_default(),
+                clipped.from.to_rfc3339(),
+                clipped.to.to_rfc3339(),
+                (clipped.to - clipped.from).num_seconds().to_string(),
+            ]));
+        }
+        Ok(csv)
+    }
+
+    async fn get_clock_entry(&self, id: i64) -> AppResult<ClockEntry> {
+        let row = sqlx::query("SELECT * FROM node_clock_entries WHERE id = ?")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+        Ok(clock_entry_from_row(&row, Utc::now()))
+    }
+
+    /// Entries overlapping the UTC days `start..=end`, cut to that window;
+    /// a running clock counts up to now
+    async fn clipped_clock_entries(&self, start: NaiveDate, end: NaiveDate) -> AppResult<Vec<ClippedEntry>> {
+        let window_start = start.and_hms_opt(0, 0, 0).unwrap().and_utc();
+        let window_end = end
+            .succ_opt()
+            .ok_or_else(|| AppError::InvalidDate(format!("{} is out of range", end)))?
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+            .and_utc();
+        if window_end <= window_start {
+            return Err(AppError::InvalidDate(format!("Report ends ({}) before it starts ({})", end, start)));
+        }
+
+        let rows = sqlx::query(
+            r#"
+            SELECT * FROM node_clock_entries
+            WHERE julianday(started_at) < julianday(?)
+              AND (ended_at IS NULL OR julianday(ended_at) > julianday(?))
+            ORDER BY started_at, id
+            "#
+        )
+        .bind(window_end)
+        .bind(window_start)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+
+        let now = Utc::now();
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let entry = clock_entry_from_row(row, now);
+                let from = entry.started_at.max(window_start);
+                let to = entry.ended_at.unwrap_or(now).min(window_end);
+                ClippedEntry { entry, from, to }
+            })
+            .filter(|clipped| clipped.to > clipped.from)
+            .collect())
+    }
+
+    /// Clocked nodes and all their ancestors
+    async fn report_nodes(&self, entries: &[ClippedEntry]) -> AppResult<HashMap<String, ReportNode>> {
+        let ids: BTreeSet<&str> = entries.iter().map(|clipped| clipped.entry.node_id.as_str()).collect();
+        let rows = sqlx::query(
+            r#"
+            WITH RECURSIVE chain(id) AS (
+                SELECT value FROM json_each(?)
+                UNION
+                SELECT n.parent_id FROM nodes n
+                INNER JOIN chain c ON n.id = c.id
+                WHERE n.parent_id IS NOT NULL
+            )
+            SELECT n.id, n.parent_id, n.content, n.tags FROM nodes n
+            INNER JOIN chain c ON n.id = c.id
+            "#
+        )
+        .bind(serde_json::to_string(&ids)?)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let content: String = row.get("content");
+                let node = ReportNode {
+                    parent_id: row.get("parent_id"),
+                    title: content.lines().next().unwrap_or_default().to_string(),
+                    tags: serde_json::from_str(&row.get::<String, _>("tags")).unwrap_or_default(),
+                };
+                (row.get("id"), node)
+            })
+            .collect())
+    }
+}
+
+fn clock_entry_from_row(row: &SqliteRow, now: DateTime<Utc>) -> ClockEntry {
+    let started_at: DateTime<Utc> = row.get("started_at");
+    let ended_at: Option<DateTime<Utc>> = row.get("ended_at");
+    ClockEntry {
+        id: row.get("id"),
+        node_id: row.get("node_id"),
+        started_at,
+        ended_at,
+        duration_seconds: (ended_at.unwrap_or(now) - started_at).num_seconds().max(0),
+    }
+}
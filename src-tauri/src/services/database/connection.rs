@@ -1,6 +1,8 @@
 use sqlx::{SqlitePool, SqliteConnection, Sqlite, Transaction, Row};
 use sqlx::sqlite::SqlitePoolOptions;
+use std::future::Future;
 use std::path::PathBuf;
+use std::pin::Pin;
 use tracing::info;
 use dirs;
 
@@ -45,7 +47,7 @@ impl DatabaseService {
     
     #[cfg(test)]
     pub async fn new_test(db_path: &str) -> AppResult<Self> {
-        let database_url = format!("sqlite:{}", db_path);
+        let database_url = format!("sqlite:{}?mode=rwc", db_path);
         
         let pool = SqlitePoolOptions::new()
             .max_connections(5)
@@ -74,6 +76,12 @@ impl DatabaseService {
         &self.pool
     }
 
+    /// Folder next to the database where imported files such as images
+    /// are kept
+    pub fn attachments_dir(&self) -> PathBuf {
+        self.db_path.with_file_name("attachments")
+    }
+
     /// Execute a database operation with proper connection management
     pub async fn with_connection<F, T, Fut>(&self, operation: F) -> AppResult<T>
     where
@@ -83,7 +91,7 @@ impl DatabaseService {
         let mut conn = self.pool.acquire().await
             .map_err(|e| AppError::DatabaseConnectionFailed(e.to_string()))?;
         
-        let result = operation(&mut *conn).await;
+        let result = operation(&mut conn).await;
         
         result.map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))
     }
@@ -102,6 +110,20 @@ impl DatabaseService {
         result.map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))
     }
 
+    /// Execute a read-then-write operation inside `BEGIN IMMEDIATE`.
+    ///
+    /// Deferred transactions that read before writing fail with "database is
+    /// locked" when another writer gets in first; taking the write lock up
+    /// front makes concurrent callers wait their turn instead.
+    pub async fn with_immediate_transaction<F, T>(&self, operation: F) -> AppResult<T>
+    where
+        F: for<'c> FnOnce(&'c mut SqliteConnection) -> Pin<Box<dyn Future<Output = AppResult<T>> + Send + 'c>>,
+    {
+        let mut conn = self.pool.acquire().await
+            .map_err(|e| AppError::DatabaseConnectionFailed(e.to_string()))?;
+        immediate_transaction(&mut conn, operation).await
+    }
+
     /// Get the default user ID for Phase 1
     pub async fn get_default_user_id(&self) -> AppResult<String> {
         let row = sqlx::query("SELECT id FROM users LIMIT 1")
@@ -120,4 +142,27 @@ impl Clone for DatabaseService {
             pool: self.pool.clone(),
         }
     }
-}
\ No newline at end of file
+}
+
+/// Run `operation` inside `BEGIN IMMEDIATE` on a connection the caller
+/// already holds, for work that needs per-connection setup around the
+/// transaction, such as an attached database
+pub(crate) async fn immediate_transaction<F, T>(conn: &mut SqliteConnection, operation: F) -> AppResult<T>
+where
+    F: for<'c> FnOnce(&'c mut SqliteConnection) -> Pin<Box<dyn Future<Output = AppResult<T>> + Send + 'c>>,
+{
+    sqlx::query("BEGIN IMMEDIATE")
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+
+    let result = operation(&mut *conn).await;
+    let finish = if result.is_ok() { "COMMIT" } else { "ROLLBACK" };
+    if let Err(e) = sqlx::query(finish).execute(&mut *conn).await {
+        // Never hand a connection with an open transaction back to the pool
+        let _ = sqlx::query("ROLLBACK").execute(&mut *conn).await;
+        return Err(AppError::DatabaseQueryFailed(e.to_string()));
+    }
+
+    result
+}
@@ -0,0 +1,195 @@
+use crate::errors::{AppError, AppResult};
+use super::connection::DatabaseService;
+use crate::models::{Node, TaskState};
+use crate::utils::{
+    advance_recurring_task, format_iso_date, parse_content_dates, parse_date_reference, parse_iso_date,
+    parse_task_marker, property_list, DateLocale, DATE_PROPERTY_KEYS,
+};
+use chrono::{Local, NaiveDate};
+use regex::Regex;
+use std::collections::{BTreeMap, HashSet};
+
+const DATE_LOCALE_PREFERENCE: &str = "date_locale";
+
+impl DatabaseService {
+    /// Locale used to read ambiguous numeric dates
+    pub async fn get_date_locale(&self) -> AppResult<DateLocale> {
+        let locale = self.get_user_preference(DATE_LOCALE_PREFERENCE).await?;
+        Ok(locale
+            .and_then(|value| serde_json::from_value(value).ok())
+            .unwrap_or_default())
+    }
+
+    /// Change the locale used to read ambiguous numeric dates
+    pub async fn set_date_locale(&self, locale: DateLocale) -> AppResult<()> {
+        self.set_user_preference(DATE_LOCALE_PREFERENCE, serde_json::to_value(locale)?)
+            .await
+    }
+
+    /// Lowercased `[[references]]` in `content` that read as dates but are
+    /// the title or an alias of an existing page, so they stay links
+    async fn pages_named_like_dates(
+        &self,
+        content: &str,
+        today: NaiveDate,
+        locale: DateLocale,
+    ) -> AppResult<HashSet<String>> {
+        let reference = Regex::new(r"\[\[(.*?)\]\]").unwrap();
+        let candidates: HashSet<String> = reference
+            .captures_iter(content)
+            .filter(|caps| parse_date_reference(&caps[1], today, locale).is_some())
+            .map(|caps| caps[1].to_lowercase())
+            .collect();
+        if candidates.is_empty() {
+            return Ok(candidates);
+        }
+
+        let mut names: HashSet<String> = HashSet::new();
+        let aliased: Vec<String> = sqlx::query_scalar(
+            "SELECT properties FROM nodes WHERE parent_id IS NULL AND json_extract(properties, '$.alias') IS NOT NULL",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+        for properties in aliased {
+            let properties: serde_json::Value = serde_json::from_str(&properties)?;
+            if let Some(aliases) = properties.get("alias") {
+                names.extend(property_list(aliases).iter().map(|alias| alias.to_lowercase()));
+            }
+        }
+
+        let mut pages = HashSet::new();
+        for candidate in candidates {
+            let titled: bool = sqlx::query_scalar(
+                "SELECT EXISTS(SELECT 1 FROM nodes WHERE parent_id IS NULL AND content = ? COLLATE NOCASE)",
+            )
+            .bind(&candidate)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+            if titled || names.contains(&candidate) {
+                pages.insert(candidate);
+            }
+        }
+        Ok(pages)
+    }
+
+    /// Normalize natural-language dates in a node, relative to the local date
+    pub async fn index_node_content(&self, node: &Node) -> AppResult<Node> {
+        self.index_node_content_at(node, Local::now().date_naive()).await
+    }
+
+    /// Normalize natural-language dates in a node relative to `today`.
+    ///
+    /// `[[date]]` references that do not name an existing page are rewritten
+    /// to journal titles (creating the journal pages), `key:: value` date
+    /// lines become typed properties, and `node_dates`, the task state and
+    /// flashcard membership are rebuilt.
+    /// Marking a repeating task DONE logs the completion and moves its dates
+    /// to the next occurrence. The rewrite is bookkeeping, so neither the
+    /// version nor `updated_at` changes.
+    pub async fn index_node_content_at(&self, node: &Node, today: NaiveDate) -> AppResult<Node> {
+        self.index_node_content_with(node, today, true).await
+    }
+
+    /// `index_node_content_at`, optionally leaving the journal pages the
+    /// content refers to uncreated
+    pub(super) async fn index_node_content_with(
+        &self,
+        node: &Node,
+        today: NaiveDate,
+        create_journals: bool,
+    ) -> AppResult<Node> {
+        let locale = self.get_date_locale().await?;
+        let title_format = self.get_journal_title_format().await?;
+        let pages = self.pages_named_like_dates(&node.content, today, locale).await?;
+        let mut parsed = parse_content_dates(&node.content, today, locale, &title_format, &pages)?;
+
+        // Completing a repeating task logs it and moves its dates forward instead
+        if matches!(parse_task_marker(&parsed.content), Some((TaskState::Done, _)))
+            && self.get_task_state(&node.id).await? != Some(TaskState::Done)
+        {
+            if let Some(advance) = advance_recurring_task(&parsed.content, today) {
+                self.complete_recurring_task(&node.id, &advance).await?;
+                parsed = parse_content_dates(&advance.content, today, locale, &title_format, &pages)?;
+            }
+        }
+
+        if create_journals {
+            for date in &parsed.journal_refs {
+                self.get_or_create_daily_note(&format_iso_date(*date)).await?;
+            }
+        }
+
+        let previous_keys: Vec<String> =
+            sqlx::query_scalar("SELECT key FROM node_dates WHERE node_id = ? AND source = 'content'")
+                .bind(&node.id)
+                .fetch_all(&self.pool)
+                .await
+                .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+
+        // Content wins over stale values it wrote earlier; other keys are the user's
+        let mut properties = node.properties.clone();
+        for key in previous_keys {
+            if !parsed.date_properties.contains_key(&key) {
+                properties.remove(&key);
+            }
+        }
+        for (key, date) in &parsed.date_properties {
+            properties.insert(key.clone(), serde_json::Value::String(format_iso_date(*date)));
+        }
+
+        let mut dates: BTreeMap<String, (String, &str)> = BTreeMap::new();
+        for key in DATE_PROPERTY_KEYS {
+            let value = properties.get(*key).and_then(|value| value.as_str());
+            if let Some(date) = value.and_then(|value| parse_iso_date(value).ok()) {
+                dates.insert(key.to_string(), (format_iso_date(date), "property"));
+            }
+        }
+        for key in parsed.date_properties.keys() {
+            if let Some(entry) = dates.get_mut(key) {
+                entry.1 = "content";
+            }
+        }
+
+        let changed = parsed.content != node.content || properties != node.properties;
+        let node_id = node.id.clone();
+        let content = parsed.content;
+        let properties_json = serde_json::to_string(&properties)?;
+
+        self.with_immediate_transaction(|conn| Box::pin(async move {
+            if changed {
+                sqlx::query("UPDATE nodes SET content = ?, properties = ? WHERE id = ?")
+                    .bind(&content)
+                    .bind(&properties_json)
+                    .bind(&node_id)
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+            }
+
+            sqlx::query("DELETE FROM node_dates WHERE node_id = ?")
+                .bind(&node_id)
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+
+            for (key, (date, source)) in &dates {
+                sqlx::query("INSERT INTO node_dates (node_id, key, date, source) VALUES (?, ?, ?, ?)")
+                    .bind(&node_id)
+                    .bind(key)
+                    .bind(date)
+                    .bind(*source)
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+            }
+            Ok(())
+        })).await?;
+
+        let node = if changed { self.get_node(&node.id).await? } else { node.clone() };
+        self.sync_task_state(&node).await?;
+        self.sync_card(&node, today).await?;
+        Ok(node)
+    }
+}
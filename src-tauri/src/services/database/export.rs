@@ -1,10 +1,17 @@
 use crate::errors::{AppResult, AppError};
 use super::connection::DatabaseService;
 use crate::models::{Node, NodeWithChildren};
+use crate::utils::{
+    content_tags, file_stem_for_title, format_property_value, generate_id, page_title_from_stem, parse_property_lines,
+    render_markdown_page, render_opml, render_org, rewrite_image_links,
+};
+use chrono::{DateTime, Utc};
+use regex::{Captures, Regex};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::Path;
-use sqlx::Row;
+use std::path::{Path, PathBuf};
+use sqlx::{Row, SqliteConnection};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ExportData {
@@ -14,6 +21,45 @@ pub struct ExportData {
     pub links: Vec<NodeLink>,
 }
 
+/// How `import_from_json` treats imported nodes whose IDs already exist
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JsonImportMode {
+    /// The imported node overwrites the existing one
+    Replace,
+    /// Whichever copy was edited last is kept
+    #[default]
+    MergeNewerWins,
+    /// Every imported node gets a fresh ID, with parents, links and block
+    /// references moved along, so nothing existing is touched
+    Copy,
+}
+
+/// What a JSON import changed, or on a dry run would change
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct JsonImportReport {
+    pub dry_run: bool,
+    /// IDs of the nodes added
+    pub created: Vec<String>,
+    /// IDs of the existing nodes overwritten
+    pub updated: Vec<String>,
+    /// IDs of the existing nodes kept because they are as new or newer
+    pub unchanged: Vec<String>,
+    pub links_created: usize,
+    /// Links to nodes in neither the export nor this graph
+    pub links_skipped: usize,
+    /// Whether the search index was out of step and had to be rebuilt
+    pub search_index_rebuilt: bool,
+}
+
+/// Files written by a Markdown folder export
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct MarkdownExportSummary {
+    pub pages: usize,
+    pub journals: usize,
+    pub assets: usize,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct NodeLink {
     pub source_node_id: String,
@@ -42,64 +88,44 @@ impl DatabaseService {
         Ok(())
     }
     
-    /// Import nodes and links from JSON
-    pub async fn import_from_json(&self, path: &Path) -> AppResult<()> {
+    /// Import nodes and links from a JSON export.
+    ///
+    /// Exports from a newer version of the app are refused, and so is a tree
+    /// that is not one: repeated IDs, parents found neither in the export
+    /// nor in this graph, or parent chains that would loop. `mode` decides
+    /// what happens to nodes whose IDs already exist here, and links to
+    /// nodes that exist nowhere are dropped. With `dry_run` the import runs
+    /// inside a transaction that is rolled back, so the report is exactly
+    /// what a real import would change.
+    pub async fn import_from_json(&self, path: &Path, mode: JsonImportMode, dry_run: bool) -> AppResult<JsonImportReport> {
         let content = fs::read_to_string(path)
             .map_err(|e| AppError::FileNotFound(format!("Failed to read import file: {}", e)))?;
         
         let export_data: ExportData = serde_json::from_str(&content)
             .map_err(|e| AppError::Internal(format!("Failed to parse import data: {}", e)))?;
-        
-        let mut tx = self.pool.begin().await
-            .map_err(|e| AppError::DatabaseConnectionFailed(e.to_string()))?;
-        
-        // Import nodes
-        for node in &export_data.nodes {
-            let properties_json = serde_json::to_string(&node.properties)
-                .map_err(|e| AppError::Internal(format!("Failed to serialize properties: {}", e)))?;
-            let tags_json = serde_json::to_string(&node.tags)
-                .map_err(|e| AppError::Internal(format!("Failed to serialize tags: {}", e)))?;
-            
-            sqlx::query(
-                r#"
-                INSERT OR REPLACE INTO nodes (id, content, parent_id, order_index, properties, tags, created_at, updated_at, created_by, version)
-                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-                "#
-            )
-            .bind(&node.id)
-            .bind(&node.content)
-            .bind(&node.parent_id)
-            .bind(node.order)
-            .bind(&properties_json)
-            .bind(&tags_json)
-            .bind(&node.created_at)
-            .bind(&node.updated_at)
-            .bind(&node.created_by)
-            .bind(node.version)
-            .execute(&mut *tx)
-            .await
-            .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+        check_export_version(&export_data.version)?;
+
+        let mut seen = HashSet::new();
+        if let Some(node) = export_data.nodes.iter().find(|node| !seen.insert(node.id.as_str())) {
+            return Err(AppError::InvalidPageData(format!("Node {} appears more than once in the export", node.id)));
         }
-        
-        // Import links
-        for link in &export_data.links {
-            sqlx::query(
-                r#"
-                INSERT OR REPLACE INTO node_links (source_node_id, target_node_id)
-                VALUES (?, ?)
-                "#
-            )
-            .bind(&link.source_node_id)
-            .bind(&link.target_node_id)
-            .execute(&mut *tx)
-            .await
-            .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+
+        // Read and written under one write lock, so the checks hold for
+        // what is written
+        let (mut report, written) = self.with_immediate_transaction(|conn| Box::pin(async move {
+            write_json_import(conn, export_data, mode, dry_run).await
+        })).await?;
+        if dry_run {
+            return Ok(report);
         }
-        
-        tx.commit().await
-            .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
-        
-        Ok(())
+
+        // Tasks, dates and the search index follow the new content
+        for node in &written {
+            self.sync_task_state(node).await?;
+            self.index_node_content(node).await?;
+        }
+        report.search_index_rebuilt = self.refresh_search_index().await?;
+        Ok(report)
     }
     
     /// Export a specific node and its descendants to markdown
@@ -108,6 +134,18 @@ impl DatabaseService {
         Ok(self.node_to_markdown(&node_with_children, 0))
     }
     
+    /// Export a node and its descendants as an OPML outline
+    pub async fn export_node_to_opml(&self, node_id: &str) -> AppResult<String> {
+        let tree = self.get_node_with_children(node_id).await?;
+        Ok(render_opml(&tree))
+    }
+
+    /// Export a node and its descendants as an Org document
+    pub async fn export_node_to_org(&self, node_id: &str) -> AppResult<String> {
+        let tree = self.get_node_with_children(node_id).await?;
+        Ok(render_org(&tree))
+    }
+    
     fn node_to_markdown(&self, node: &NodeWithChildren, level: usize) -> String {
         let mut markdown = String::new();
         
@@ -142,6 +180,112 @@ impl DatabaseService {
         Ok(markdown)
     }
     
+    /// Export the graph as a folder `import_markdown_dir` reads back: one
+    /// file per page in `pages/`, journal pages in `journals/`, and local
+    /// images copied to `assets/`. Properties and tags the content does not
+    /// spell out are written as `key:: value` lines, and blocks that other
+    /// nodes link to keep their ID in an `id::` line.
+    pub async fn export_markdown_dir(&self, dir: &Path) -> AppResult<MarkdownExportSummary> {
+        let journal_dates: HashMap<String, String> =
+            sqlx::query_as("SELECT node_id, journal_date FROM journals")
+                .fetch_all(&self.pool)
+                .await
+                .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?
+                .into_iter()
+                .collect();
+        let referenced: HashSet<String> = sqlx::query_scalar(
+            r#"
+            SELECT DISTINCT l.target_node_id FROM node_links l
+            JOIN nodes n ON n.id = l.target_node_id
+            WHERE n.parent_id IS NOT NULL
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?
+        .into_iter()
+        .collect();
+        let derived = self.content_derived_dates().await?;
+
+        let mut summary = MarkdownExportSummary::default();
+        let mut assets = AssetCopier::new(dir.join("assets"));
+        let mut used_stems = HashSet::new();
+
+        for root in self.get_root_nodes().await? {
+            let mut tree = self.get_node_with_children(&root.id).await?;
+            let mut pending: Vec<&mut NodeWithChildren> = tree.child_nodes.iter_mut().collect();
+            while let Some(block) = pending.pop() {
+                let mut content = rewrite_image_links(&block.node.content, |target| assets.copy(target));
+                let extra = unwritten_metadata(&block.node, &content, derived.get(&block.node.id));
+                for line in extra {
+                    content.push('\n');
+                    content.push_str(&line);
+                }
+                if referenced.contains(&block.node.id) {
+                    content.push_str(&format!("\nid:: {}", block.node.id));
+                }
+                block.node.content = content;
+                pending.extend(block.child_nodes.iter_mut());
+            }
+
+            let title = root.content.lines().next().unwrap_or_default();
+            let mut properties = Vec::new();
+            let path = match journal_dates.get(&root.id) {
+                Some(date) => {
+                    summary.journals += 1;
+                    dir.join("journals").join(format!("{}.md", date.replace('-', "_")))
+                }
+                None => {
+                    summary.pages += 1;
+                    let mut stem = file_stem_for_title(title);
+                    let mut suffix = 1;
+                    while !used_stems.insert(stem.to_lowercase()) {
+                        suffix += 1;
+                        stem = format!("{} ({})", file_stem_for_title(title), suffix);
+                    }
+                    if page_title_from_stem(&stem) != title {
+                        properties.push(("title".to_string(), title.to_string()));
+                    }
+                    properties.push(("id".to_string(), root.id.clone()));
+                    dir.join("pages").join(format!("{}.md", stem))
+                }
+            };
+            if !root.tags.is_empty() {
+                properties.push(("tags".to_string(), root.tags.join(", ")));
+            }
+            let mut keys: Vec<&String> = root.properties.keys().collect();
+            keys.sort();
+            for key in keys {
+                let internal = journal_dates.contains_key(&root.id) && (key == "type" || key == "date");
+                if !internal && !["id", "title", "tags"].contains(&key.as_str()) {
+                    properties.push((key.clone(), format_property_value(&root.properties[key])));
+                }
+            }
+
+            fs::create_dir_all(path.parent().unwrap())
+                .map_err(|e| AppError::ExportFailed(format!("Failed to create {}: {}", dir.display(), e)))?;
+            fs::write(&path, render_markdown_page(&properties, &tree.child_nodes))
+                .map_err(|e| AppError::ExportFailed(format!("Failed to write {}: {}", path.display(), e)))?;
+        }
+
+        summary.assets = assets.copied.len();
+        Ok(summary)
+    }
+
+    /// Date properties that content indexing derived from each node's own
+    /// text, by node ID
+    pub(super) async fn content_derived_dates(&self) -> AppResult<HashMap<String, HashSet<String>>> {
+        let mut derived: HashMap<String, HashSet<String>> = HashMap::new();
+        let rows: Vec<(String, String)> = sqlx::query_as("SELECT node_id, key FROM node_dates WHERE source = 'content'")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+        for (node_id, key) in rows {
+            derived.entry(node_id).or_default().insert(key);
+        }
+        Ok(derived)
+    }
+
     // Helper methods
     async fn get_all_links(&self) -> AppResult<Vec<NodeLink>> {
         let rows = sqlx::query(
@@ -161,4 +305,268 @@ impl DatabaseService {
         
         Ok(links)
     }
-}
\ No newline at end of file
+}
+
+/// `key:: value` lines for the properties and tags of `node` that its
+/// content does not already carry
+pub(super) fn unwritten_metadata(node: &Node, content: &str, derived: Option<&HashSet<String>>) -> Vec<String> {
+    let written = parse_property_lines(content);
+    let mut keys: Vec<&String> = node
+        .properties
+        .keys()
+        .filter(|key| {
+            let key = key.to_lowercase();
+            !written.contains_key(&key) && !derived.is_some_and(|derived| derived.contains(&key))
+        })
+        .collect();
+    keys.sort();
+    let mut lines: Vec<String> = keys
+        .into_iter()
+        .map(|key| format!("{}:: {}", key, format_property_value(&node.properties[key])))
+        .collect();
+
+    let inline = content_tags(content);
+    let missing: Vec<&str> = node.tags.iter().filter(|tag| !inline.contains(tag)).map(String::as_str).collect();
+    if !missing.is_empty() {
+        lines.push(format!("tags:: {}", missing.join(", ")));
+    }
+    lines
+}
+
+/// Copies local image files into an export's `assets/` folder once each
+struct AssetCopier {
+    dir: PathBuf,
+    copied: HashMap<PathBuf, String>,
+}
+
+impl AssetCopier {
+    fn new(dir: PathBuf) -> Self {
+        AssetCopier { dir, copied: HashMap::new() }
+    }
+
+    /// Copy the file `target` points to and return the exported link to
+    /// it; remote and missing files are left alone
+    fn copy(&mut self, target: &str) -> Option<String> {
+        let source = PathBuf::from(target.strip_prefix("file://").unwrap_or(target));
+        let remote = target.contains("://") && !target.starts_with("file://");
+        if remote || !source.is_absolute() || !source.is_file() {
+            return None;
+        }
+        if let Some(name) = self.copied.get(&source) {
+            return Some(format!("../assets/{}", name));
+        }
+
+        let file_name = source.file_name()?.to_string_lossy().into_owned();
+        let taken: HashSet<&String> = self.copied.values().collect();
+        let mut name = file_name.clone();
+        let mut suffix = 1;
+        while taken.contains(&name) {
+            suffix += 1;
+            name = match file_name.rsplit_once('.') {
+                Some((stem, extension)) => format!("{}-{}.{}", stem, suffix, extension),
+                None => format!("{}-{}", file_name, suffix),
+            };
+        }
+
+        fs::create_dir_all(&self.dir).ok()?;
+        fs::copy(&source, self.dir.join(&name)).ok()?;
+        self.copied.insert(source, name.clone());
+        Some(format!("../assets/{}", name))
+    }
+}
+
+/// Check and write the nodes and links of a JSON export on `conn`, which
+/// holds the write lock. A dry run is rolled back to a savepoint once the
+/// report is complete. Returns the report and the nodes written.
+async fn write_json_import(
+    conn: &mut SqliteConnection,
+    export_data: ExportData,
+    mode: JsonImportMode,
+    dry_run: bool,
+) -> AppResult<(JsonImportReport, Vec<Node>)> {
+    if dry_run {
+        sqlx::query("SAVEPOINT json_dry_run")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+    }
+
+    // Every node here, with its parent and last edit
+    let rows = sqlx::query("SELECT id, parent_id, updated_at FROM nodes")
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+    let mut existing: HashMap<String, (Option<String>, DateTime<Utc>)> = HashMap::new();
+    for row in rows {
+        existing.insert(row.get("id"), (row.get("parent_id"), row.get("updated_at")));
+    }
+
+    // IDs the imported nodes get here
+    let ids: HashMap<&str, String> = export_data
+        .nodes
+        .iter()
+        .map(|node| {
+            let id = if mode == JsonImportMode::Copy { generate_id() } else { node.id.clone() };
+            (node.id.as_str(), id)
+        })
+        .collect();
+
+    let mut report = JsonImportReport { dry_run, ..Default::default() };
+    let mut written = Vec::new();
+    for node in &export_data.nodes {
+        let id = ids[node.id.as_str()].clone();
+        let mut node = node.clone();
+        node.parent_id = match node.parent_id.take() {
+            Some(parent_id) => match ids.get(parent_id.as_str()) {
+                Some(id) => Some(id.clone()),
+                None if mode != JsonImportMode::Copy && existing.contains_key(&parent_id) => Some(parent_id),
+                None => {
+                    return Err(AppError::InvalidPageData(format!("Node {} has unknown parent {}", node.id, parent_id)));
+                }
+            },
+            None => None,
+        };
+        node.id = id;
+
+        match (existing.get(&node.id), mode) {
+            (None, _) => report.created.push(node.id.clone()),
+            (Some((_, updated_at)), JsonImportMode::MergeNewerWins) if *updated_at >= node.updated_at => {
+                report.unchanged.push(node.id.clone());
+                continue;
+            }
+            (Some(_), _) => report.updated.push(node.id.clone()),
+        }
+        written.push(node);
+    }
+
+    // The tree as it would be after the import must not loop
+    let mut parents: HashMap<&str, Option<&str>> =
+        existing.iter().map(|(id, (parent_id, _))| (id.as_str(), parent_id.as_deref())).collect();
+    parents.extend(written.iter().map(|node| (node.id.as_str(), node.parent_id.as_deref())));
+    let mut acyclic: HashSet<&str> = HashSet::new();
+    for node in &written {
+        let mut chain = HashSet::new();
+        let mut current = Some(node.id.as_str());
+        while let Some(id) = current.filter(|id| !acyclic.contains(id)) {
+            if !chain.insert(id) {
+                return Err(AppError::InvalidPageData(format!("Node {} would be its own ancestor", id)));
+            }
+            current = parents.get(id).copied().flatten();
+        }
+        acyclic.extend(chain);
+    }
+
+    // Parents go in before their children
+    let mut depths: HashMap<&str, usize> = HashMap::new();
+    for node in &written {
+        let mut chain = Vec::new();
+        let mut current = Some(node.id.as_str());
+        while let Some(id) = current.filter(|id| !depths.contains_key(id)) {
+            chain.push(id);
+            current = parents.get(id).copied().flatten();
+        }
+        let base = current.map_or(0, |id| depths[id] + 1);
+        for (offset, id) in chain.into_iter().rev().enumerate() {
+            depths.insert(id, base + offset);
+        }
+    }
+    let mut order: Vec<usize> = (0..written.len()).collect();
+    order.sort_by_key(|index| depths[written[*index].id.as_str()]);
+
+    // Block references follow their nodes to new IDs
+    let block_ref = Regex::new(r"\(\(([^()\s]+)\)\)").unwrap();
+    if mode == JsonImportMode::Copy {
+        for node in &mut written {
+            node.content = block_ref
+                .replace_all(&node.content, |caps: &Captures| match ids.get(&caps[1]) {
+                    Some(id) => format!("(({}))", id),
+                    None => caps[0].to_string(),
+                })
+                .into_owned();
+        }
+    }
+
+    for node in order.iter().map(|index| &written[*index]) {
+        let properties_json = serde_json::to_string(&node.properties)
+            .map_err(|e| AppError::Internal(format!("Failed to serialize properties: {}", e)))?;
+        let tags_json = serde_json::to_string(&node.tags)
+            .map_err(|e| AppError::Internal(format!("Failed to serialize tags: {}", e)))?;
+        
+        // An update keeps the row, so its children and search entry stay
+        let query = if existing.contains_key(&node.id) {
+            r#"
+            UPDATE nodes SET content = ?2, parent_id = ?3, order_index = ?4, properties = ?5, tags = ?6,
+                created_at = ?7, updated_at = ?8, created_by = ?9, version = ?10
+            WHERE id = ?1
+            "#
+        } else {
+            r#"
+            INSERT INTO nodes (id, content, parent_id, order_index, properties, tags, created_at, updated_at, created_by, version)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+            "#
+        };
+        sqlx::query(query)
+        .bind(&node.id)
+        .bind(&node.content)
+        .bind(&node.parent_id)
+        .bind(node.order)
+        .bind(&properties_json)
+        .bind(&tags_json)
+        .bind(node.created_at)
+        .bind(node.updated_at)
+        .bind(&node.created_by)
+        .bind(node.version)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+    }
+    
+    for link in &export_data.links {
+        let endpoint = |id: &String| match ids.get(id.as_str()) {
+            Some(id) => Some(id.clone()),
+            None => (mode != JsonImportMode::Copy && existing.contains_key(id)).then(|| id.clone()),
+        };
+        let (Some(source), Some(target)) = (endpoint(&link.source_node_id), endpoint(&link.target_node_id)) else {
+            report.links_skipped += 1;
+            continue;
+        };
+        let inserted = sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO node_links (source_node_id, target_node_id)
+            VALUES (?, ?)
+            "#
+        )
+        .bind(&source)
+        .bind(&target)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+        report.links_created += inserted.rows_affected() as usize;
+    }
+
+    if dry_run {
+        sqlx::query("ROLLBACK TO json_dry_run")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+    }
+    Ok((report, written))
+}
+
+/// Refuse exports written by a newer version of the app, which may hold
+/// data this one does not understand
+fn check_export_version(version: &str) -> AppResult<()> {
+    let parse = |version: &str| -> Option<Vec<u64>> {
+        let release = version.trim().split(['-', '+']).next()?;
+        release.split('.').map(|part| part.parse().ok()).collect()
+    };
+    let current = env!("CARGO_PKG_VERSION");
+    match parse(version) {
+        Some(export) if export <= parse(current).unwrap_or_default() => Ok(()),
+        Some(_) => Err(AppError::InvalidPageData(format!(
+            "The export is from version {}, newer than this app ({})",
+            version, current
+        ))),
+        None => Err(AppError::InvalidPageData(format!("Unknown export version {:?}", version))),
+    }
+}
@@ -0,0 +1,163 @@
+use crate::errors::{AppError, AppResult};
+use super::connection::DatabaseService;
+use crate::models::{Habit, HabitReport, HabitSummary};
+use crate::utils::{
+    current_streak, format_iso_date, longest_streak, parse_habit_value, parse_iso_date, parse_property_lines,
+    validate_habit_key,
+};
+use chrono::{NaiveDate, Utc};
+use serde_json::Value;
+use sqlx::sqlite::SqliteRow;
+use sqlx::Row;
+use std::collections::HashMap;
+
+/// Longest habit report, in days after the first day
+pub const MAX_HABIT_REPORT_DAYS: i64 = 366;
+
+impl DatabaseService {
+    /// Start tracking `key:: value` lines as a habit, or rename one
+    pub async fn add_habit(&self, key: &str, name: Option<&str>) -> AppResult<Habit> {
+        validate_habit_key(key)?;
+        let key = key.to_lowercase();
+        let name = name.map(str::trim).filter(|name| !name.is_empty()).unwrap_or(&key);
+
+        sqlx::query(
+            r#"
+            INSERT INTO habits (key, name, created_at) VALUES (?, ?, ?)
+            ON CONFLICT(key) DO UPDATE SET name = excluded.name
+            "#
+        )
+        .bind(&key)
+        .bind(name)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+
+        let row = sqlx::query("SELECT key, name, created_at FROM habits WHERE key = ?")
+            .bind(&key)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+        Ok(habit_from_row(&row))
+    }
+
+    /// Stop tracking a habit; journal content is left untouched
+    pub async fn remove_habit(&self, key: &str) -> AppResult<()> {
+        let result = sqlx::query("DELETE FROM habits WHERE key = ?")
+            .bind(key.to_lowercase())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Habit {} not found", key)));
+        }
+        Ok(())
+    }
+
+    pub async fn list_habits(&self) -> AppResult<Vec<Habit>> {
+        let rows = sqlx::query("SELECT key, name, created_at FROM habits ORDER BY created_at, key")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+        Ok(rows.iter().map(habit_from_row).collect())
+    }
+
+    /// Streaks, completion rates and a per-day matrix of every habit between
+    /// `start` and `end` (inclusive). A day's value comes from the journal
+    /// page's properties and the `key:: value` lines anywhere inside it; it
+    /// counts as done if any of them says so.
+    pub async fn get_habit_report(&self, start: &str, end: &str) -> AppResult<HabitReport> {
+        let (start, end) = (parse_iso_date(start)?, parse_iso_date(end)?);
+        if end < start {
+            return Err(AppError::InvalidDate(format!("Report ends ({}) before it starts ({})", end, start)));
+        }
+        if (end - start).num_days() > MAX_HABIT_REPORT_DAYS {
+            return Err(AppError::InvalidDate(format!(
+                "Habit reports are limited to {} days",
+                MAX_HABIT_REPORT_DAYS
+            )));
+        }
+
+        let rows = sqlx::query(
+            r#"
+            WITH RECURSIVE tree(journal_date, id) AS (
+                SELECT journal_date, node_id FROM journals WHERE journal_date BETWEEN ? AND ?
+                UNION ALL
+                SELECT t.journal_date, n.id FROM nodes n
+                INNER JOIN tree t ON n.parent_id = t.id
+            )
+            SELECT t.journal_date, n.content, n.properties
+            FROM tree t
+            JOIN nodes n ON n.id = t.id
+            "#
+        )
+        .bind(format_iso_date(start))
+        .bind(format_iso_date(end))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+
+        // (date, key) -> done, merged across every block of the day's page
+        let mut recorded: HashMap<(NaiveDate, String), bool> = HashMap::new();
+        for row in rows {
+            let date = parse_iso_date(&row.get::<String, _>("journal_date"))?;
+            let mut values: Vec<(String, Option<bool>)> = parse_property_lines(&row.get::<String, _>("content"))
+                .into_iter()
+                .map(|(key, value)| (key, parse_habit_value(&value)))
+                .collect();
+
+            let properties: HashMap<String, Value> =
+                serde_json::from_str(&row.get::<String, _>("properties")).unwrap_or_default();
+            values.extend(properties.into_iter().map(|(key, value)| {
+                let done = match value {
+                    Value::Bool(done) => Some(done),
+                    Value::Number(number) => number.as_f64().map(|number| number > 0.0),
+                    Value::String(text) => parse_habit_value(&text),
+                    _ => None,
+                };
+                (key.to_lowercase(), done)
+            }));
+
+            for (key, done) in values {
+                if let Some(done) = done {
+                    let entry = recorded.entry((date, key)).or_insert(false);
+                    *entry |= done;
+                }
+            }
+        }
+
+        let dates: Vec<NaiveDate> = start.iter_days().take_while(|date| *date <= end).collect();
+        let habits = self
+            .list_habits()
+            .await?
+            .into_iter()
+            .map(|habit| {
+                let days: Vec<Option<bool>> = dates
+                    .iter()
+                    .map(|date| recorded.get(&(*date, habit.key.clone())).copied())
+                    .collect();
+                let completed = days.iter().filter(|day| **day == Some(true)).count() as i64;
+                HabitSummary {
+                    completed,
+                    completion_rate: completed as f64 / dates.len() as f64,
+                    current_streak: current_streak(&days),
+                    longest_streak: longest_streak(&days),
+                    habit,
+                    days,
+                }
+            })
+            .collect();
+
+        Ok(HabitReport { start, end, dates, habits })
+    }
+}
+
+fn habit_from_row(row: &SqliteRow) -> Habit {
+    Habit {
+        key: row.get("key"),
+        name: row.get("name"),
+        created_at: row.get("created_at"),
+    }
+}
@@ -0,0 +1,586 @@
+use crate::errors::{AppError, AppResult};
+use super::connection::DatabaseService;
+use crate::models::{ImportProgress, ImportSummary, ImportedBlock, ImportedPage};
+use crate::utils::{
+    enml_to_blocks, escape_like, format_iso_date, generate_id, parse_enex, parse_markdown_page, parse_notion_export, parse_opml,
+    markdown_link_target, mime_extension, parse_org, parse_roam_export, rewrite_image_links, EnexResource,
+};
+use chrono::Utc;
+use regex::{Captures, Regex};
+use sqlx::SqliteConnection;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+impl DatabaseService {
+    /// Import a Logseq or Obsidian vault: every `.md` file under `dir`
+    /// becomes a page, and files named by date become journal pages. The
+    /// vault's own settings and backups (hidden folders, `logseq/`) are left
+    /// out. Images the pages embed from the vault are copied into the
+    /// attachments folder and linked from there.
+    pub async fn import_markdown_dir(
+        &self,
+        dir: &Path,
+        on_progress: impl FnMut(ImportProgress),
+    ) -> AppResult<ImportSummary> {
+        if !dir.is_dir() {
+            return Err(AppError::FileNotFound(format!("{} is not a directory", dir.display())));
+        }
+
+        let mut files = Vec::new();
+        collect_markdown_files(dir, true, &mut files)?;
+        files.sort();
+
+        let attachments = self.attachments_dir();
+        let mut stored: HashMap<PathBuf, String> = HashMap::new();
+        let mut pages = Vec::new();
+        let mut skipped = Vec::new();
+        for file in files {
+            let stem = file.file_stem().and_then(|stem| stem.to_str()).unwrap_or_default();
+            match fs::read_to_string(&file) {
+                Ok(text) => {
+                    let mut page = parse_markdown_page(stem, &text);
+                    let base = file.parent().unwrap_or(dir);
+                    let mut failed = None;
+                    let mut pending: Vec<&mut ImportedBlock> = page.blocks.iter_mut().collect();
+                    while let Some(block) = pending.pop() {
+                        block.content = rewrite_image_links(&block.content, |target| {
+                            let source = resolve_local_file(base, target)?;
+                            if let Some(target) = stored.get(&source) {
+                                return Some(target.clone());
+                            }
+                            let name = source.file_name()?.to_string_lossy().into_owned();
+                            let target = File::open(&source)
+                                .map_err(AppError::from)
+                                .and_then(|file| store_attachment(&attachments, &name, file));
+                            match target {
+                                Ok(target) => {
+                                    stored.insert(source, target.clone());
+                                    Some(target)
+                                }
+                                Err(e) => {
+                                    failed.get_or_insert(e);
+                                    None
+                                }
+                            }
+                        });
+                        pending.extend(block.children.iter_mut());
+                    }
+                    if let Some(e) = failed {
+                        return Err(e);
+                    }
+                    pages.push(page);
+                }
+                Err(e) => skipped.push(format!("{}: {}", file.display(), e)),
+            }
+        }
+
+        let mut summary = self.import_pages(pages, on_progress).await?;
+        summary.skipped.extend(skipped);
+        Ok(summary)
+    }
+
+    /// Import an OPML file as one page, titled by the document or, failing
+    /// that, the file name
+    pub async fn import_opml(&self, path: &Path) -> AppResult<ImportSummary> {
+        let text = fs::read_to_string(path)?;
+        let stem = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or_default();
+        let page = parse_opml(&text, stem)?;
+        self.import_pages(vec![page], |_| {}).await
+    }
+
+    /// Import an Org file as a page, titled by its `#+TITLE:` or else its
+    /// file name
+    pub async fn import_org(&self, path: &Path) -> AppResult<ImportSummary> {
+        let text = fs::read_to_string(path)?;
+        let stem = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or_default();
+        self.import_pages(vec![parse_org(&text, stem)], |_| {}).await
+    }
+
+    /// Import a Roam Research JSON export, keeping block UIDs as node IDs
+    /// where they are free
+    pub async fn import_roam_json(
+        &self,
+        path: &Path,
+        on_progress: impl FnMut(ImportProgress),
+    ) -> AppResult<ImportSummary> {
+        let text = fs::read_to_string(path)?;
+        let pages = parse_roam_export(&text)?;
+        self.import_pages(pages, on_progress).await
+    }
+
+    /// Import a Notion export zip. Pages and databases are read from the
+    /// Markdown and CSV files; images and other files they link to are
+    /// copied into the attachments folder and linked from there.
+    pub async fn import_notion_zip(
+        &self,
+        path: &Path,
+        on_progress: impl FnMut(ImportProgress),
+    ) -> AppResult<ImportSummary> {
+        let invalid = |e: zip::result::ZipError| AppError::InvalidPageData(format!("Invalid Notion export: {}", e));
+        let mut archive = zip::ZipArchive::new(File::open(path)?).map_err(invalid)?;
+
+        let mut files = BTreeMap::new();
+        let mut skipped = Vec::new();
+        for index in 0..archive.len() {
+            let mut entry = archive.by_index(index).map_err(invalid)?;
+            let name = entry.name().to_string();
+            let text_file = name.ends_with(".md") || name.ends_with(".csv");
+            if !entry.is_file() || !text_file || name.starts_with("__MACOSX/") {
+                continue;
+            }
+            let mut text = String::new();
+            match entry.read_to_string(&mut text) {
+                Ok(_) => {
+                    files.insert(name, text);
+                }
+                Err(e) => skipped.push(format!("{}: {}", name, e)),
+            }
+        }
+
+        let attachments = self.attachments_dir();
+        let mut stored: HashMap<String, Option<String>> = HashMap::new();
+        let mut failed = None;
+        let pages = parse_notion_export(&files, |name| {
+            if let Some(target) = stored.get(name) {
+                return target.clone();
+            }
+            // Files the export links to but does not contain stay as links
+            let target = match archive.by_name(name) {
+                Ok(entry) => match store_attachment(&attachments, name, entry) {
+                    Ok(target) => Some(target),
+                    Err(e) => {
+                        failed.get_or_insert(e);
+                        None
+                    }
+                },
+                Err(_) => None,
+            };
+            stored.insert(name.to_string(), target.clone());
+            target
+        });
+        if let Some(e) = failed {
+            return Err(e);
+        }
+
+        let mut summary = self.import_pages(pages, on_progress).await?;
+        summary.skipped.extend(skipped);
+        Ok(summary)
+    }
+
+    /// Import an Evernote ENEX export as a page named after the file (its
+    /// notebook) with one block per note. Notes keep their tags and
+    /// timestamps, and embedded files are copied into the attachments
+    /// folder. Notes whose content cannot be read are skipped.
+    pub async fn import_enex(
+        &self,
+        path: &Path,
+        on_progress: impl FnMut(ImportProgress),
+    ) -> AppResult<ImportSummary> {
+        let text = fs::read_to_string(path)?;
+        let notes = parse_enex(&text)?;
+        let attachments = self.attachments_dir();
+
+        let mut notebook = ImportedPage {
+            title: path.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default(),
+            ..Default::default()
+        };
+        let mut skipped = Vec::new();
+        for note in notes {
+            // Checked before any files are stored, so a skipped note leaves none behind
+            if let Err(e) = enml_to_blocks(&note.content, |_| None) {
+                skipped.push(format!("{}: {}", note.title, e));
+                continue;
+            }
+            let mut shown: HashMap<&str, String> = HashMap::new();
+            let mut failed = None;
+            let mut children = enml_to_blocks(&note.content, |hash| {
+                if let Some(markdown) = shown.get(hash) {
+                    return Some(markdown.clone());
+                }
+                let resource = note.resources.iter().find(|resource| resource.hash == hash)?;
+                match resource_markdown(&attachments, resource) {
+                    Ok(markdown) => {
+                        shown.insert(resource.hash.as_str(), markdown.clone());
+                        Some(markdown)
+                    }
+                    Err(e) => {
+                        failed.get_or_insert(e);
+                        None
+                    }
+                }
+            })?;
+            if let Some(e) = failed {
+                return Err(e);
+            }
+            // Files the note carries without showing them inline
+            for resource in note.resources.iter().filter(|resource| !shown.contains_key(resource.hash.as_str())) {
+                children.push(ImportedBlock { content: resource_markdown(&attachments, resource)?, ..Default::default() });
+            }
+
+            notebook.blocks.push(ImportedBlock {
+                content: note.title,
+                tags: note.tags,
+                created_at: note.created,
+                updated_at: note.updated,
+                children,
+                ..Default::default()
+            });
+        }
+
+        let mut summary = self.import_pages(vec![notebook], on_progress).await?;
+        summary.skipped.extend(skipped);
+        Ok(summary)
+    }
+
+    /// Write imported pages into the outline.
+    ///
+    /// Journal pages and pages whose title matches an existing top-level page
+    /// are merged: their blocks are appended after the existing children.
+    /// Once everything is written, the new blocks go through content indexing
+    /// (dates, tasks, flashcards) and their `[[links]]` are resolved against
+    /// page titles and aliases. `((block refs))` to source IDs that were
+    /// already taken are pointed at the IDs the blocks got instead.
+    pub async fn import_pages(
+        &self,
+        pages: Vec<ImportedPage>,
+        mut on_progress: impl FnMut(ImportProgress),
+    ) -> AppResult<ImportSummary> {
+        let mut summary = ImportSummary::default();
+        let mut created = Vec::new();
+        let mut aliases: HashMap<String, String> = HashMap::new();
+        // Source IDs that were taken and had to be replaced
+        let mut renamed_ids: HashMap<String, String> = HashMap::new();
+        let total = pages.len();
+
+        for (index, page) in pages.into_iter().enumerate() {
+            on_progress(ImportProgress { processed: index, total, current: page.title.clone() });
+
+            let existing = match page.journal_date {
+                Some(date) => {
+                    if self.find_journal_node_id(date).await?.is_some() {
+                        summary.pages_merged += 1;
+                    } else {
+                        summary.pages_created += 1;
+                    }
+                    Some(self.get_or_create_daily_note(&format_iso_date(date)).await?.id)
+                }
+                None => {
+                    let existing = self.find_page_by_title(&page.title).await?;
+                    if existing.is_some() {
+                        summary.pages_merged += 1;
+                    } else {
+                        summary.pages_created += 1;
+                    }
+                    existing
+                }
+            };
+
+            let page_aliases = page.aliases.clone();
+            let (page_id, block_ids, remapped) = self.with_immediate_transaction(|conn| Box::pin(async move {
+                let page_id = match existing {
+                    Some(page_id) => {
+                        merge_page_metadata(conn, &page_id, &page).await?;
+                        page_id
+                    }
+                    None => {
+                        let id = keep_or_generate_id(conn, page.id.as_deref()).await?;
+                        let node = ImportedBlock {
+                            content: page.title.clone(),
+                            properties: page.properties.clone(),
+                            tags: page.tags.clone(),
+                            created_at: page.created_at,
+                            updated_at: page.updated_at,
+                            ..Default::default()
+                        };
+                        insert_imported_node(conn, &id, None, 0, &node).await?;
+                        id
+                    }
+                };
+
+                let order: i32 = sqlx::query_scalar("SELECT COALESCE(MAX(order_index) + 1, 0) FROM nodes WHERE parent_id = ?")
+                    .bind(&page_id)
+                    .fetch_one(&mut *conn)
+                    .await
+                    .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+
+                let mut block_ids = Vec::new();
+                let mut remapped = HashMap::new();
+                insert_imported_blocks(conn, &page_id, order, &page.blocks, &mut block_ids, &mut remapped).await?;
+                Ok((page_id, block_ids, remapped))
+            })).await?;
+
+            for alias in page_aliases {
+                aliases.insert(alias.to_lowercase(), page_id.clone());
+            }
+            summary.blocks_created += block_ids.len();
+            created.extend(block_ids);
+            renamed_ids.extend(remapped);
+        }
+
+        // Record imported task states first, so a finished repeating task
+        // is not advanced to its next occurrence by indexing
+        let block_ref = Regex::new(r"\(\(([^()\s]+)\)\)").unwrap();
+        let mut indexed = Vec::with_capacity(created.len());
+        for id in &created {
+            let mut node = self.get_node(id).await?;
+            if !renamed_ids.is_empty() {
+                let content = block_ref.replace_all(&node.content, |caps: &Captures| match renamed_ids.get(&caps[1]) {
+                    Some(id) => format!("(({}))", id),
+                    None => caps[0].to_string(),
+                });
+                if content != node.content {
+                    sqlx::query("UPDATE nodes SET content = ? WHERE id = ?")
+                        .bind(content.as_ref())
+                        .bind(id)
+                        .execute(&self.pool)
+                        .await
+                        .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+                    node.content = content.into_owned();
+                }
+            }
+            self.sync_task_state(&node).await?;
+            indexed.push(self.index_node_content(&node).await?);
+        }
+
+        let mut titles = aliases;
+        let pages: Vec<(String, String)> = sqlx::query_as("SELECT id, content FROM nodes WHERE parent_id IS NULL")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+        for (id, title) in pages {
+            titles.entry(title.to_lowercase()).or_insert(id);
+        }
+
+        let link = Regex::new(r"\[\[(.*?)\]\]").unwrap();
+        let mut unresolved = Vec::new();
+        for node in &indexed {
+            for caps in link.captures_iter(&node.content) {
+                // Pages by title or alias first, then blocks the way `update_links_for_node` finds them
+                let target_id = match titles.get(&caps[1].to_lowercase()) {
+                    Some(id) => Some(id.clone()),
+                    None => sqlx::query_scalar("SELECT id FROM nodes WHERE content = ? OR content LIKE ? ESCAPE '\\' LIMIT 1")
+                        .bind(&caps[1])
+                        .bind(format!("{}%", escape_like(&caps[1])))
+                        .fetch_optional(&self.pool)
+                        .await
+                        .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?,
+                };
+                let Some(target_id) = target_id.filter(|id| *id != node.id) else {
+                    unresolved.push(caps[1].to_string());
+                    continue;
+                };
+                let result = sqlx::query("INSERT OR IGNORE INTO node_links (source_node_id, target_node_id) VALUES (?, ?)")
+                    .bind(&node.id)
+                    .bind(&target_id)
+                    .execute(&self.pool)
+                    .await
+                    .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+                summary.links_created += result.rows_affected() as usize;
+            }
+            for caps in block_ref.captures_iter(&node.content) {
+                let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM nodes WHERE id = ?)")
+                    .bind(&caps[1])
+                    .fetch_one(&self.pool)
+                    .await
+                    .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+                if !exists || caps[1] == node.id {
+                    unresolved.push(caps[0].to_string());
+                    continue;
+                }
+                let result = sqlx::query("INSERT OR IGNORE INTO node_links (source_node_id, target_node_id) VALUES (?, ?)")
+                    .bind(&node.id)
+                    .bind(&caps[1])
+                    .execute(&self.pool)
+                    .await
+                    .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+                summary.links_created += result.rows_affected() as usize;
+            }
+        }
+        unresolved.sort();
+        unresolved.dedup();
+        summary.unresolved_links = unresolved;
+
+        on_progress(ImportProgress { processed: total, total, current: String::new() });
+        Ok(summary)
+    }
+
+    /// Top-level page whose title matches `title`, ignoring case
+    async fn find_page_by_title(&self, title: &str) -> AppResult<Option<String>> {
+        sqlx::query_scalar("SELECT id FROM nodes WHERE parent_id IS NULL AND content = ? COLLATE NOCASE ORDER BY created_at LIMIT 1")
+            .bind(title)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))
+    }
+}
+
+/// Markdown files under `dir`; at the vault root the `logseq/` folder holds
+/// config and backups rather than notes
+fn collect_markdown_files(dir: &Path, root: bool, files: &mut Vec<PathBuf>) -> AppResult<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+        if name.starts_with('.') || (root && name == "logseq") {
+            continue;
+        }
+        if path.is_dir() {
+            collect_markdown_files(&path, false, files)?;
+        } else if path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("md")) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Canonical path of a relative image target that exists next to the file
+fn resolve_local_file(base: &Path, target: &str) -> Option<PathBuf> {
+    if target.contains("://") || Path::new(target).is_absolute() {
+        return None;
+    }
+    let path = base.join(target).canonicalize().ok()?;
+    path.is_file().then_some(path)
+}
+
+/// Write `contents` into `dir` as `file_name`, or a numbered variant of it
+/// when that is taken, returning the path
+fn store_attachment(dir: &Path, file_name: &str, mut contents: impl Read) -> AppResult<String> {
+    let file_name = Path::new(file_name)
+        .file_name()
+        .ok_or_else(|| AppError::InvalidPageData(format!("{} is not a file name", file_name)))?
+        .to_string_lossy()
+        .into_owned();
+    let mut target = dir.join(&file_name);
+    let mut suffix = 1;
+    while target.exists() {
+        suffix += 1;
+        target = dir.join(match file_name.rsplit_once('.') {
+            Some((stem, extension)) => format!("{}-{}.{}", stem, suffix, extension),
+            None => format!("{}-{}", file_name, suffix),
+        });
+    }
+
+    fs::create_dir_all(dir)?;
+    let mut file = File::create(&target)?;
+    std::io::copy(&mut contents, &mut file)?;
+    Ok(target.to_string_lossy().into_owned())
+}
+
+/// Store an Evernote resource as an attachment and link to it, as an
+/// image where it is one
+fn resource_markdown(dir: &Path, resource: &EnexResource) -> AppResult<String> {
+    let file_name = resource
+        .file_name
+        .clone()
+        .unwrap_or_else(|| format!("{}.{}", resource.hash, mime_extension(&resource.mime)));
+    let path = store_attachment(dir, &file_name, resource.data.as_slice())?;
+    let image = if resource.mime.starts_with("image/") { "!" } else { "" };
+    Ok(format!("{}[{}]({})", image, file_name, markdown_link_target(&path)))
+}
+
+/// `id` when it is free, so imported nodes keep their IDs; a fresh one otherwise
+async fn keep_or_generate_id(conn: &mut SqliteConnection, id: Option<&str>) -> AppResult<String> {
+    let Some(id) = id.filter(|id| !id.trim().is_empty()) else {
+        return Ok(generate_id());
+    };
+    let taken: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM nodes WHERE id = ?)")
+        .bind(id)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+    Ok(if taken { generate_id() } else { id.to_string() })
+}
+
+/// Add an imported page's properties and tags to an existing page, keeping
+/// the values it already has
+async fn merge_page_metadata(conn: &mut SqliteConnection, page_id: &str, page: &ImportedPage) -> AppResult<()> {
+    if page.properties.is_empty() && page.tags.is_empty() {
+        return Ok(());
+    }
+
+    let (properties_json, tags_json): (String, String) = sqlx::query_as("SELECT properties, tags FROM nodes WHERE id = ?")
+        .bind(page_id)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+
+    let mut properties: HashMap<String, serde_json::Value> = serde_json::from_str(&properties_json).unwrap_or_default();
+    for (key, value) in &page.properties {
+        properties.entry(key.clone()).or_insert_with(|| value.clone());
+    }
+    let mut tags: Vec<String> = serde_json::from_str(&tags_json).unwrap_or_default();
+    for tag in &page.tags {
+        if !tags.contains(tag) {
+            tags.push(tag.clone());
+        }
+    }
+
+    sqlx::query("UPDATE nodes SET properties = ?, tags = ? WHERE id = ?")
+        .bind(serde_json::to_string(&properties)?)
+        .bind(serde_json::to_string(&tags)?)
+        .bind(page_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+    Ok(())
+}
+
+async fn insert_imported_blocks(
+    conn: &mut SqliteConnection,
+    parent_id: &str,
+    first_order: i32,
+    blocks: &[ImportedBlock],
+    ids: &mut Vec<String>,
+    remapped: &mut HashMap<String, String>,
+) -> AppResult<()> {
+    // Walk the tree with an explicit stack; imported outlines can be deep
+    let mut stack: Vec<(String, i32, &ImportedBlock)> = blocks
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(index, block)| (parent_id.to_string(), first_order + index as i32, block))
+        .collect();
+
+    while let Some((parent_id, order, block)) = stack.pop() {
+        let id = keep_or_generate_id(conn, block.id.as_deref()).await?;
+        if let Some(source_id) = block.id.as_ref().filter(|source_id| **source_id != id) {
+            remapped.insert(source_id.clone(), id.clone());
+        }
+        insert_imported_node(conn, &id, Some(&parent_id), order, block).await?;
+        for (index, child) in block.children.iter().enumerate().rev() {
+            stack.push((id.clone(), index as i32, child));
+        }
+        ids.push(id);
+    }
+    Ok(())
+}
+
+pub(super) async fn insert_imported_node(
+    conn: &mut SqliteConnection,
+    id: &str,
+    parent_id: Option<&str>,
+    order: i32,
+    block: &ImportedBlock,
+) -> AppResult<()> {
+    let created_at = block.created_at.or(block.updated_at).unwrap_or_else(Utc::now);
+    sqlx::query(
+        r#"
+        INSERT INTO nodes (id, content, parent_id, order_index, properties, tags, created_at, updated_at, created_by)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#
+    )
+    .bind(id)
+    .bind(&block.content)
+    .bind(parent_id)
+    .bind(order)
+    .bind(serde_json::to_string(&block.properties)?)
+    .bind(serde_json::to_string(&block.tags)?)
+    .bind(created_at)
+    .bind(block.updated_at.unwrap_or(created_at))
+    .bind("default_user")
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+    Ok(())
+}
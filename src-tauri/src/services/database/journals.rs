@@ -0,0 +1,226 @@
+use crate::errors::{AppError, AppResult};
+use super::connection::DatabaseService;
+use super::templates::{copy_template_children, DAILY_TEMPLATE};
+use crate::models::{JournalEntry, JournalList, Node};
+use crate::utils::{
+    format_iso_date, format_journal_title, generate_id, parse_iso_date, validate_title_format,
+    DEFAULT_JOURNAL_TITLE_FORMAT,
+};
+use chrono::{NaiveDate, Utc};
+use sqlx::{Row, SqliteConnection};
+use std::collections::HashMap;
+
+const TITLE_FORMAT_PREFERENCE: &str = "journal_title_format";
+
+impl DatabaseService {
+    /// Get the journal page for a date
+    pub async fn get_daily_note(&self, date: &str) -> AppResult<Node> {
+        let date = parse_iso_date(date)?;
+        match self.find_journal_node_id(date).await? {
+            Some(node_id) => self.get_node(&node_id).await,
+            None => Err(AppError::NotFound(format!("No journal page for {}", format_iso_date(date)))),
+        }
+    }
+
+    /// Get the journal page for a date, creating it on first access
+    pub async fn get_or_create_daily_note(&self, date: &str) -> AppResult<Node> {
+        let date = parse_iso_date(date)?;
+        if let Some(node_id) = self.find_journal_node_id(date).await? {
+            return self.get_node(&node_id).await;
+        }
+
+        let title_format = self.get_journal_title_format().await?;
+        let title = format_journal_title(date, &title_format)?;
+        let iso_date = format_iso_date(date);
+
+        let node_id = generate_id();
+        let mut properties = HashMap::new();
+        properties.insert("type".to_string(), serde_json::Value::String("daily_note".to_string()));
+        properties.insert("date".to_string(), serde_json::Value::String(iso_date.clone()));
+        let properties_json = serde_json::to_string(&properties)?;
+        let tags_json = serde_json::to_string(&["daily", "journal"])?;
+
+        // The template is copied in the same transaction, so a new page never
+        // shows up without its template content
+        let template = match self.find_template(DAILY_TEMPLATE).await? {
+            Some(template) => Some(self.get_node_with_children(&template.id).await?),
+            None => None,
+        };
+        let vars = self.template_variables(date).await?;
+
+        let (page_id, copied) = self.with_immediate_transaction(|conn| Box::pin(async move {
+            // Another caller may have created the page while we waited for the lock
+            let existing: Option<String> = sqlx::query_scalar("SELECT node_id FROM journals WHERE journal_date = ?")
+                .bind(&iso_date)
+                .fetch_optional(&mut *conn)
+                .await
+                .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+            if let Some(existing_id) = existing {
+                return Ok((existing_id, None));
+            }
+
+            insert_page_node(conn, &node_id, &title, &properties_json, &tags_json).await?;
+
+            sqlx::query("INSERT INTO journals (journal_date, node_id) VALUES (?, ?)")
+                .bind(&iso_date)
+                .bind(&node_id)
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+
+            let copied = match &template {
+                Some(template) => copy_template_children(conn, template, &node_id, &vars).await?,
+                None => Vec::new(),
+            };
+
+            Ok((node_id, Some(copied)))
+        })).await?;
+
+        if let Some(copied) = copied {
+            self.link_journal_to_periods(&page_id).await?;
+            Box::pin(self.index_copied_nodes(&copied, false)).await?;
+        }
+        self.get_node(&page_id).await
+    }
+
+    /// List journal pages between two dates (inclusive), newest first
+    pub async fn list_journals(
+        &self,
+        start: Option<&str>,
+        end: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> AppResult<JournalList> {
+        let start = start.map(parse_iso_date).transpose()?.map(format_iso_date);
+        let end = end.map(parse_iso_date).transpose()?.map(format_iso_date);
+
+        let total: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*) FROM journals
+            WHERE (?1 IS NULL OR journal_date >= ?1)
+              AND (?2 IS NULL OR journal_date <= ?2)
+            "#
+        )
+        .bind(&start)
+        .bind(&end)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT journal_date, node_id FROM journals
+            WHERE (?1 IS NULL OR journal_date >= ?1)
+              AND (?2 IS NULL OR journal_date <= ?2)
+            ORDER BY journal_date DESC
+            LIMIT ?3 OFFSET ?4
+            "#
+        )
+        .bind(&start)
+        .bind(&end)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+
+        let mut journals = Vec::new();
+        for row in rows {
+            let date = parse_iso_date(&row.get::<String, _>("journal_date"))?;
+            let node = self.get_node(&row.get::<String, _>("node_id")).await?;
+            journals.push(JournalEntry { date, node });
+        }
+
+        let has_more = offset + (journals.len() as i64) < total;
+        Ok(JournalList { journals, total, has_more })
+    }
+
+    /// Title format used for new journal pages
+    pub async fn get_journal_title_format(&self) -> AppResult<String> {
+        let format = self.get_user_preference(TITLE_FORMAT_PREFERENCE).await?;
+        Ok(format
+            .as_ref()
+            .and_then(|value| value.as_str())
+            .unwrap_or(DEFAULT_JOURNAL_TITLE_FORMAT)
+            .to_string())
+    }
+
+    /// Change the title format used for new journal pages
+    pub async fn set_journal_title_format(&self, format: &str) -> AppResult<()> {
+        validate_title_format(format)?;
+        self.set_user_preference(TITLE_FORMAT_PREFERENCE, serde_json::Value::String(format.to_string()))
+            .await
+    }
+
+    /// Date of the journal page backed by `node_id`, if it is one
+    pub async fn get_journal_date(&self, node_id: &str) -> AppResult<Option<NaiveDate>> {
+        let date: Option<String> = sqlx::query_scalar("SELECT journal_date FROM journals WHERE node_id = ?")
+            .bind(node_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+
+        date.as_deref().map(parse_iso_date).transpose()
+    }
+
+    /// Date of the journal page that contains `node_id`, if any
+    pub async fn get_containing_journal_date(&self, node_id: &str) -> AppResult<Option<NaiveDate>> {
+        let date: Option<String> = sqlx::query_scalar(
+            r#"
+            WITH RECURSIVE ancestors(id, parent_id) AS (
+                SELECT id, parent_id FROM nodes WHERE id = ?
+                UNION ALL
+                SELECT n.id, n.parent_id FROM nodes n
+                INNER JOIN ancestors a ON n.id = a.parent_id
+            )
+            SELECT j.journal_date FROM ancestors a
+            JOIN journals j ON j.node_id = a.id
+            LIMIT 1
+            "#
+        )
+        .bind(node_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+
+        date.as_deref().map(parse_iso_date).transpose()
+    }
+
+    pub(crate) async fn find_journal_node_id(&self, date: NaiveDate) -> AppResult<Option<String>> {
+        sqlx::query_scalar("SELECT node_id FROM journals WHERE journal_date = ?")
+            .bind(format_iso_date(date))
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))
+    }
+}
+
+/// Insert a top-level page node; used for journal and periodic pages whose
+/// creation must happen inside the caller's transaction.
+pub(crate) async fn insert_page_node(
+    conn: &mut SqliteConnection,
+    node_id: &str,
+    title: &str,
+    properties_json: &str,
+    tags_json: &str,
+) -> AppResult<()> {
+    let now = Utc::now();
+    sqlx::query(
+        r#"
+        INSERT INTO nodes (id, content, parent_id, order_index, properties, tags, created_at, updated_at, created_by)
+        VALUES (?, ?, NULL, 0, ?, ?, ?, ?, ?)
+        "#
+    )
+    .bind(node_id)
+    .bind(title)
+    .bind(properties_json)
+    .bind(tags_json)
+    .bind(now)
+    .bind(now)
+    .bind("default_user")
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+
+    Ok(())
+}
@@ -0,0 +1,255 @@
+use crate::errors::{AppError, AppResult};
+use super::connection::DatabaseService;
+use super::export::unwritten_metadata;
+use super::import::insert_imported_node;
+use crate::services::link_service::rebuild_node_links;
+use crate::models::{ImportedBlock, ImportedPage, Node, NodeWithChildren};
+use crate::utils::{format_property_value, generate_id, render_markdown_page};
+use chrono::Utc;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+/// Folder of a Git mirror that holds the page files
+pub const MIRROR_PAGES_DIR: &str = "pages";
+
+/// Page property holding the full root content, JSON-escaped, where it is
+/// more than the `title::` line
+const MIRROR_CONTENT_PROPERTY: &str = "page-content";
+
+/// Path of page `page_id`'s file within a Git mirror
+pub fn mirror_page_path(page_id: &str) -> String {
+    format!("{}/{}.md", MIRROR_PAGES_DIR, page_id)
+}
+
+/// A page as the Git mirror stores it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MirrorPage {
+    /// Path within the repository, `pages/<page id>.md`
+    pub path: String,
+    pub page_id: String,
+    pub title: String,
+    pub contents: String,
+}
+
+impl DatabaseService {
+    /// Every page in the format the Git mirror stores: Markdown that
+    /// `parse_markdown_page` reads back, with the page's title, ID, tags and
+    /// properties as leading `key:: value` lines and every block ending in
+    /// its `id::` line. Root content spanning more than its title line is
+    /// kept whole in a `page-content::` line.
+    ///
+    /// Files are named by page ID, so renaming a page keeps its history in
+    /// one file, and properties are sorted so an unchanged page always
+    /// renders to the same bytes.
+    pub async fn render_mirror_pages(&self) -> AppResult<Vec<MirrorPage>> {
+        let derived = self.content_derived_dates().await?;
+        let mut pages = Vec::new();
+        for root in self.get_root_nodes().await? {
+            let mut tree = self.get_node_with_children(&root.id).await?;
+            let mut pending: Vec<&mut NodeWithChildren> = tree.child_nodes.iter_mut().collect();
+            while let Some(block) = pending.pop() {
+                let mut lines = vec![block.node.content.clone()];
+                lines.extend(unwritten_metadata(&block.node, &block.node.content, derived.get(&block.node.id)));
+                lines.push(format!("id:: {}", block.node.id));
+                block.node.content = lines.join("\n");
+                pending.extend(block.child_nodes.iter_mut());
+            }
+
+            let title = root.content.lines().next().unwrap_or_default().to_string();
+            let mut properties = vec![("title".to_string(), title.clone())];
+            if root.content != title {
+                properties.push((MIRROR_CONTENT_PROPERTY.to_string(), serde_json::to_string(&root.content)?));
+            }
+            properties.push(("id".to_string(), root.id.clone()));
+            if !root.tags.is_empty() {
+                properties.push(("tags".to_string(), root.tags.join(", ")));
+            }
+            let mut keys: Vec<&String> = root
+                .properties
+                .keys()
+                .filter(|key| !["id", "title", "tags", MIRROR_CONTENT_PROPERTY].contains(&key.as_str()))
+                .collect();
+            keys.sort();
+            for key in keys {
+                properties.push((key.clone(), format_property_value(&root.properties[key])));
+            }
+
+            pages.push(MirrorPage {
+                path: mirror_page_path(&root.id),
+                page_id: root.id.clone(),
+                title,
+                contents: render_markdown_page(&properties, &tree.child_nodes),
+            });
+        }
+        Ok(pages)
+    }
+
+    /// Bring page `page.id` back to an earlier version read from the mirror
+    /// with `parse_markdown_page`.
+    ///
+    /// Blocks still on the page are updated in place, so their task history,
+    /// clock entries and flashcard reviews stay; blocks the old version did
+    /// not have are deleted and ones it had are recreated under their old
+    /// IDs where those are free. A deleted page is recreated. Properties
+    /// come back as the `key:: value` text the mirror wrote, except where
+    /// the current value still reads the same.
+    ///
+    /// The page and its blocks are written in one transaction. Task states,
+    /// dates and links are then rebuilt block by block as after an edit; if
+    /// that fails, the restored blocks stay, the error is returned, and
+    /// restoring again finishes the job.
+    pub async fn restore_mirror_page(&self, mut page: ImportedPage) -> AppResult<Node> {
+        let page_id = page.id.clone().ok_or_else(|| AppError::InvalidPageData("The page has no ID".to_string()))?;
+        let content = match page.properties.remove(MIRROR_CONTENT_PROPERTY) {
+            Some(Value::String(escaped)) => serde_json::from_str(&escaped)
+                .map_err(|e| AppError::InvalidPageData(format!("Unreadable page content: {}", e)))?,
+            _ => page.title.clone(),
+        };
+        let restored = self.with_immediate_transaction(|conn| Box::pin(async move {
+            let page_id = page.id.as_deref().unwrap_or_default();
+            let current: Option<String> = sqlx::query_scalar("SELECT properties FROM nodes WHERE id = ?")
+                .bind(page_id)
+                .fetch_optional(&mut *conn)
+                .await
+                .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+            match current {
+                Some(properties) => {
+                    let properties = restored_properties(&properties, &page.properties);
+                    sqlx::query("UPDATE nodes SET content = ?, properties = ?, tags = ?, updated_at = ?, version = version + 1 WHERE id = ?")
+                        .bind(&content)
+                        .bind(serde_json::to_string(&properties)?)
+                        .bind(serde_json::to_string(&page.tags)?)
+                        .bind(Utc::now())
+                        .bind(page_id)
+                        .execute(&mut *conn)
+                        .await
+                        .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+                }
+                None => {
+                    let node = ImportedBlock {
+                        content,
+                        properties: page.properties.clone(),
+                        tags: page.tags.clone(),
+                        ..Default::default()
+                    };
+                    let order: i32 = sqlx::query_scalar("SELECT COALESCE(MAX(order_index) + 1, 0) FROM nodes WHERE parent_id IS NULL")
+                        .fetch_one(&mut *conn)
+                        .await
+                        .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+                    insert_imported_node(conn, page_id, None, order, &node).await?;
+                }
+            }
+
+            let descendants: HashSet<String> = sqlx::query_scalar(
+                r#"
+                WITH RECURSIVE tree(id) AS (
+                    SELECT id FROM nodes WHERE parent_id = ?
+                    UNION ALL
+                    SELECT n.id FROM nodes n JOIN tree t ON n.parent_id = t.id
+                )
+                SELECT id FROM tree
+                "#
+            )
+            .bind(page_id)
+            .fetch_all(&mut *conn)
+            .await
+            .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?
+            .into_iter()
+            .collect();
+
+            let mut restored = vec![page_id.to_string()];
+            let mut kept_ids = HashSet::new();
+            let mut stack: Vec<(String, i32, &ImportedBlock)> = page
+                .blocks
+                .iter()
+                .enumerate()
+                .rev()
+                .map(|(index, block)| (page_id.to_string(), index as i32, block))
+                .collect();
+            while let Some((parent_id, order, block)) = stack.pop() {
+                let kept = block.id.as_ref().filter(|id| descendants.contains(*id) && !kept_ids.contains(*id));
+                let id = match kept {
+                    Some(id) => {
+                        let properties: String = sqlx::query_scalar("SELECT properties FROM nodes WHERE id = ?")
+                            .bind(id)
+                            .fetch_one(&mut *conn)
+                            .await
+                            .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+                        let properties = restored_properties(&properties, &block.properties);
+                        sqlx::query(
+                            r#"
+                            UPDATE nodes SET content = ?, parent_id = ?, order_index = ?, properties = ?, tags = ?,
+                                updated_at = ?, version = version + 1
+                            WHERE id = ?
+                            "#
+                        )
+                        .bind(&block.content)
+                        .bind(&parent_id)
+                        .bind(order)
+                        .bind(serde_json::to_string(&properties)?)
+                        .bind(serde_json::to_string(&block.tags)?)
+                        .bind(Utc::now())
+                        .bind(id)
+                        .execute(&mut *conn)
+                        .await
+                        .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+                        kept_ids.insert(id.clone());
+                        id.clone()
+                    }
+                    None => {
+                        let free = match block.id.as_deref() {
+                            Some(id) if !descendants.contains(id) => !sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM nodes WHERE id = ?)")
+                                .bind(id)
+                                .fetch_one(&mut *conn)
+                                .await
+                                .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?,
+                            _ => false,
+                        };
+                        let id = match block.id.as_ref().filter(|_| free) {
+                            Some(id) => id.clone(),
+                            None => generate_id(),
+                        };
+                        insert_imported_node(conn, &id, Some(&parent_id), order, block).await?;
+                        id
+                    }
+                };
+                for (index, child) in block.children.iter().enumerate().rev() {
+                    stack.push((id.clone(), index as i32, child));
+                }
+                restored.push(id);
+            }
+
+            // Blocks kept above were moved out from under these first
+            for id in descendants.iter().filter(|id| !kept_ids.contains(*id)) {
+                sqlx::query("DELETE FROM nodes WHERE id = ?")
+                    .bind(id)
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+            }
+            Ok(restored)
+        })).await?;
+
+        for id in &restored {
+            let node = self.get_node(id).await?;
+            self.sync_task_state(&node).await?;
+            let node = self.index_node_content(&node).await?;
+            let mut conn = self.pool.acquire().await?;
+            rebuild_node_links(&mut conn, &node.id, &node.content).await?;
+        }
+        self.get_node(&page_id).await
+    }
+}
+
+/// Restored property values: the mirror's text, or the current value where
+/// it still reads the same, so lists and numbers keep their type
+fn restored_properties(current_json: &str, restored: &HashMap<String, Value>) -> HashMap<String, Value> {
+    let current: HashMap<String, Value> = serde_json::from_str(current_json).unwrap_or_default();
+    restored
+        .iter()
+        .map(|(key, value)| match current.get(key) {
+            Some(existing) if Value::String(format_property_value(existing)) == *value => (key.clone(), existing.clone()),
+            _ => (key.clone(), value.clone()),
+        })
+        .collect()
+}
@@ -4,6 +4,22 @@ pub mod schema;
 pub mod search;
 pub mod stats;
 pub mod export;
+pub mod preferences;
+pub mod journals;
+pub mod periodic;
+pub mod templates;
+pub mod time_travel;
+pub mod content_index;
+pub mod tasks;
+pub mod agenda;
+pub mod clock;
+pub mod cards;
+pub mod habits;
+pub mod import;
+pub mod publish;
+pub mod backup;
+pub mod snapshots;
+pub mod mirror;
 
 #[cfg(test)]
 pub mod tests; 
\ No newline at end of file
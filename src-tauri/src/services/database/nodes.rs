@@ -3,8 +3,8 @@ use super::connection::DatabaseService;
 use crate::models::{Node, CreateNodeRequest, UpdateNodeRequest, NodeWithChildren};
 use sqlx::Row;
 use chrono::Utc;
+use std::collections::{HashMap, HashSet};
 use crate::utils::generate_id;
-use std::collections::HashMap;
 
 impl DatabaseService {
     pub async fn create_node(&self, request: CreateNodeRequest) -> AppResult<Node> {
@@ -80,26 +80,82 @@ impl DatabaseService {
     }
 
     pub async fn get_node_with_children(&self, node_id: &str) -> AppResult<NodeWithChildren> {
-        let node = self.get_node(node_id).await?;
-        let children = self.get_child_nodes(node_id).await?;
-        Ok(NodeWithChildren {
-            node,
-            child_nodes: children,
-        })
-    }
+        let root = self.get_node(node_id).await?;
 
-    async fn get_child_nodes(&self, parent_id: &str) -> AppResult<Vec<NodeWithChildren>> {
-        let children = sqlx::query("SELECT id FROM nodes WHERE parent_id = ? ORDER BY order_index")
-            .bind(parent_id)
-            .fetch_all(&self.pool)
-            .await
-            .map_err(|e| crate::errors::AppError::DatabaseQueryFailed(e.to_string()))?;
+        // Load the whole subtree in one query and assemble it bottom-up, so
+        // deep outlines need neither a query per node nor recursion
+        let rows = sqlx::query(
+            r#"
+            WITH RECURSIVE subtree(id) AS (
+                SELECT id FROM nodes WHERE parent_id = ?
+                UNION
+                SELECT n.id FROM nodes n
+                INNER JOIN subtree s ON n.parent_id = s.id
+            )
+            SELECT n.id, n.content, n.parent_id, n.order_index, n.properties, n.tags,
+                   n.created_at, n.updated_at, n.created_by, n.version
+            FROM nodes n
+            INNER JOIN subtree s ON n.id = s.id
+            ORDER BY n.order_index, n.rowid
+            "#
+        )
+        .bind(node_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| crate::errors::AppError::DatabaseQueryFailed(e.to_string()))?;
 
-        let mut result = Vec::new();
-        for child in children {
-            result.push(Box::pin(self.get_node_with_children(&child.get::<String, _>("id"))).await?);
+        let mut children_of: HashMap<String, Vec<String>> = HashMap::new();
+        let mut nodes: HashMap<String, Node> = HashMap::new();
+        for row in rows {
+            let node = Node {
+                id: row.get("id"),
+                content: row.get("content"),
+                parent_id: row.get("parent_id"),
+                order: row.get("order_index"),
+                properties: serde_json::from_str(&row.get::<String, _>("properties")).unwrap_or_default(),
+                tags: serde_json::from_str(&row.get::<String, _>("tags")).unwrap_or_default(),
+                created_at: row.get("created_at"),
+                updated_at: row.get("updated_at"),
+                created_by: row.get("created_by"),
+                version: row.get("version"),
+                children: Vec::new(),
+            };
+            let parent_id = node.parent_id.clone().unwrap_or_default();
+            children_of.entry(parent_id).or_default().push(node.id.clone());
+            nodes.insert(node.id.clone(), node);
+        }
+
+        // Children before parents, so each node's subtree is built when it is reached
+        let mut post_order = Vec::new();
+        let mut seen = HashSet::new();
+        let mut pending = vec![(root.id.clone(), false)];
+        while let Some((id, expanded)) = pending.pop() {
+            if expanded {
+                post_order.push(id);
+                continue;
+            }
+            if !seen.insert(id.clone()) {
+                continue;
+            }
+            pending.push((id.clone(), true));
+            for child in children_of.get(&id).into_iter().flatten().rev() {
+                pending.push((child.clone(), false));
+            }
+        }
+
+        let mut built: HashMap<String, NodeWithChildren> = HashMap::new();
+        let mut root = Some(root);
+        for id in post_order {
+            let child_ids = children_of.remove(&id).unwrap_or_default();
+            let mut node = match nodes.remove(&id) {
+                Some(node) => node,
+                None => root.take().unwrap(),
+            };
+            node.children = child_ids.clone();
+            let child_nodes = child_ids.iter().filter_map(|child| built.remove(child)).collect();
+            built.insert(id, NodeWithChildren { node, child_nodes });
         }
-        Ok(result)
+        Ok(built.remove(node_id).unwrap())
     }
 
     pub async fn update_node(&self, node_id: &str, request: UpdateNodeRequest) -> AppResult<Node> {
@@ -231,23 +287,22 @@ impl DatabaseService {
         let mut tx = self.pool.begin().await
             .map_err(|e| crate::errors::AppError::DatabaseConnectionFailed(e.to_string()))?;
 
-        // Recursively delete children
-        let children = sqlx::query("SELECT id FROM nodes WHERE parent_id = ?")
-            .bind(&node_id)
-            .fetch_all(&mut *tx)
-            .await
-            .map_err(|e| crate::errors::AppError::DatabaseQueryFailed(e.to_string()))?;
-
-        for child in children {
-            Box::pin(self.delete_node(&child.get::<String, _>("id"))).await?;
-        }
-
-        // Delete the node itself
-        sqlx::query("DELETE FROM nodes WHERE id = ?")
-            .bind(&node_id)
-            .execute(&mut *tx)
-            .await
-            .map_err(|e| crate::errors::AppError::DatabaseQueryFailed(e.to_string()))?;
+        // Delete the node and its descendants on this one connection; a
+        // nested transaction per child would wait on this one's lock
+        sqlx::query(
+            r#"
+            WITH RECURSIVE tree(id) AS (
+                SELECT ?
+                UNION ALL
+                SELECT n.id FROM nodes n JOIN tree t ON n.parent_id = t.id
+            )
+            DELETE FROM nodes WHERE id IN (SELECT id FROM tree)
+            "#
+        )
+        .bind(node_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| crate::errors::AppError::DatabaseQueryFailed(e.to_string()))?;
 
         tx.commit().await
             .map_err(|e| crate::errors::AppError::DatabaseQueryFailed(e.to_string()))?;
@@ -358,76 +413,4 @@ impl DatabaseService {
 
         Ok(nodes)
     }
-
-    pub async fn get_daily_note(&self, date: &str) -> AppResult<Node> {
-        let title = format!("Daily Note - {}", date);
-        let row = sqlx::query(
-            r#"
-            SELECT id, content, parent_id, order_index, properties, tags, 
-                   created_at, updated_at, created_by, version 
-            FROM nodes 
-            WHERE content LIKE ?
-            LIMIT 1
-            "#
-        )
-        .bind(format!("{}%", title))
-        .fetch_one(&self.pool)
-        .await
-        .map_err(|e| crate::errors::AppError::DatabaseQueryFailed(e.to_string()))?;
-        
-        let mut node = Node {
-            id: row.get("id"),
-            content: row.get("content"),
-            parent_id: row.get("parent_id"),
-            order: row.get("order_index"),
-            properties: serde_json::from_str(&row.get::<String, _>("properties")).unwrap_or_default(),
-            tags: serde_json::from_str(&row.get::<String, _>("tags")).unwrap_or_default(),
-            created_at: row.get("created_at"),
-            updated_at: row.get("updated_at"),
-            created_by: row.get("created_by"),
-            version: row.get("version"),
-            children: Vec::new(),
-        };
-
-        // Get children for this node
-        let children = sqlx::query("SELECT id FROM nodes WHERE parent_id = ?")
-            .bind(&node.id)
-            .fetch_all(&self.pool)
-            .await
-            .map_err(|e| crate::errors::AppError::DatabaseQueryFailed(e.to_string()))?;
-        
-        node.children = children.into_iter().map(|r| r.get::<String, _>("id")).collect();
-        
-        Ok(node)
-    }
-
-    pub async fn get_or_create_daily_note(&self, date: &str) -> AppResult<Node> {
-        let title = format!("Daily Note - {}", date);
-        
-        // Try to find existing daily note
-        let existing = sqlx::query("SELECT id FROM nodes WHERE content LIKE ? LIMIT 1")
-            .bind(format!("{}%", title))
-            .fetch_optional(&self.pool)
-            .await
-            .map_err(|e| crate::errors::AppError::DatabaseQueryFailed(e.to_string()))?;
-        
-        if let Some(row) = existing {
-            self.get_node(&row.get::<String, _>("id")).await
-        } else {
-            // Create new daily note
-            let request = CreateNodeRequest {
-                content: title,
-                parent_id: None,
-                order: Some(0),
-                properties: Some({
-                    let mut props = HashMap::new();
-                    props.insert("type".to_string(), serde_json::Value::String("daily_note".to_string()));
-                    props.insert("date".to_string(), serde_json::Value::String(date.to_string()));
-                    props
-                }),
-                tags: Some(vec!["daily".to_string(), "journal".to_string()]),
-            };
-            self.create_node(request).await
-        }
-    }
 }
\ No newline at end of file
@@ -0,0 +1,196 @@
+use crate::errors::{AppError, AppResult};
+use super::connection::DatabaseService;
+use super::journals::insert_page_node;
+use crate::models::{Node, PeriodKind, PeriodRollup};
+use crate::utils::{format_iso_date, generate_id, parse_iso_date, period_range, period_title, PeriodRange};
+use std::collections::HashMap;
+
+impl DatabaseService {
+    /// Get the periodic page of `kind` containing `date`, creating it on first access.
+    ///
+    /// Creating the page links every existing daily note of the period to it.
+    pub async fn get_or_create_periodic_note(&self, kind: PeriodKind, date: &str) -> AppResult<Node> {
+        let range = period_range(kind, parse_iso_date(date)?);
+        if let Some(node_id) = self.find_periodic_node_id(&range).await? {
+            return self.get_node(&node_id).await;
+        }
+
+        let title = period_title(&range);
+        let node_id = generate_id();
+        let mut properties = HashMap::new();
+        properties.insert("type".to_string(), serde_json::Value::String("periodic_note".to_string()));
+        properties.insert("period".to_string(), serde_json::Value::String(kind.as_str().to_string()));
+        properties.insert("period_key".to_string(), serde_json::Value::String(range.key.clone()));
+        properties.insert("start".to_string(), serde_json::Value::String(format_iso_date(range.start)));
+        properties.insert("end".to_string(), serde_json::Value::String(format_iso_date(range.end)));
+        let properties_json = serde_json::to_string(&properties)?;
+        let tags_json = serde_json::to_string(&["periodic", kind.as_str()])?;
+
+        let key = range.key.clone();
+        let start = format_iso_date(range.start);
+        let end = format_iso_date(range.end);
+        let created_id = self.with_immediate_transaction(|conn| Box::pin(async move {
+            let existing: Option<String> = sqlx::query_scalar(
+                "SELECT node_id FROM periodic_notes WHERE period_type = ? AND period_key = ?"
+            )
+            .bind(kind.as_str())
+            .bind(&key)
+            .fetch_optional(&mut *conn)
+            .await
+            .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+            if let Some(existing_id) = existing {
+                return Ok(existing_id);
+            }
+
+            insert_page_node(conn, &node_id, &title, &properties_json, &tags_json).await?;
+
+            sqlx::query("INSERT INTO periodic_notes (period_type, period_key, node_id) VALUES (?, ?, ?)")
+                .bind(kind.as_str())
+                .bind(&key)
+                .bind(&node_id)
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+
+            // Link the period's existing daily notes to the new page
+            sqlx::query(
+                r#"
+                INSERT OR IGNORE INTO node_links (source_node_id, target_node_id)
+                SELECT node_id, ? FROM journals WHERE journal_date BETWEEN ? AND ?
+                "#
+            )
+            .bind(&node_id)
+            .bind(&start)
+            .bind(&end)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+
+            Ok(node_id)
+        })).await?;
+
+        self.get_node(&created_id).await
+    }
+
+    /// Link a daily note to the periodic pages that already exist for its date.
+    ///
+    /// Does nothing for nodes that are not journal pages.
+    pub async fn link_journal_to_periods(&self, node_id: &str) -> AppResult<()> {
+        let Some(date) = self.get_journal_date(node_id).await? else {
+            return Ok(());
+        };
+
+        for kind in PeriodKind::ALL {
+            let range = period_range(kind, date);
+            sqlx::query(
+                r#"
+                INSERT OR IGNORE INTO node_links (source_node_id, target_node_id)
+                SELECT ?, node_id FROM periodic_notes WHERE period_type = ? AND period_key = ?
+                "#
+            )
+            .bind(node_id)
+            .bind(kind.as_str())
+            .bind(&range.key)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Collect the journal pages of a period together with every node that
+    /// referenced one of them, and every tagged node touched during it
+    pub async fn get_period_rollup(&self, kind: PeriodKind, date: &str) -> AppResult<PeriodRollup> {
+        let range = period_range(kind, parse_iso_date(date)?);
+        let start = format_iso_date(range.start);
+        let end = format_iso_date(range.end);
+
+        let page = match self.find_periodic_node_id(&range).await? {
+            Some(node_id) => Some(self.get_node(&node_id).await?),
+            None => None,
+        };
+
+        let journals = self.list_journals(Some(&start), Some(&end), -1, 0).await?.journals;
+
+        // Journal and periodic pages are excluded so the automatic
+        // daily -> period links don't show up as references
+        let referenced_ids = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT DISTINCT n.id
+            FROM node_links l
+            JOIN nodes n ON n.id = l.source_node_id
+            WHERE l.target_node_id IN (
+                SELECT node_id FROM journals WHERE journal_date BETWEEN ?1 AND ?2
+                UNION
+                SELECT node_id FROM periodic_notes WHERE period_type = ?3 AND period_key = ?4
+            )
+              AND n.id NOT IN (SELECT node_id FROM journals)
+              AND n.id NOT IN (SELECT node_id FROM periodic_notes)
+            ORDER BY n.updated_at
+            "#
+        )
+        .bind(&start)
+        .bind(&end)
+        .bind(kind.as_str())
+        .bind(&range.key)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+
+        let tagged_ids = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT id FROM nodes
+            WHERE tags IS NOT NULL AND tags != '[]'
+              AND date(updated_at) BETWEEN ?1 AND ?2
+              AND id NOT IN (SELECT node_id FROM journals)
+              AND id NOT IN (SELECT node_id FROM periodic_notes)
+            ORDER BY updated_at
+            "#
+        )
+        .bind(&start)
+        .bind(&end)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+
+        let mut referenced = Vec::new();
+        for id in referenced_ids {
+            referenced.push(self.get_node(&id).await?);
+        }
+
+        let mut tagged = Vec::new();
+        let mut counts: HashMap<String, i64> = HashMap::new();
+        for id in tagged_ids {
+            let node = self.get_node(&id).await?;
+            for tag in &node.tags {
+                *counts.entry(tag.clone()).or_insert(0) += 1;
+            }
+            tagged.push(node);
+        }
+
+        let mut tag_counts: Vec<(String, i64)> = counts.into_iter().collect();
+        tag_counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        Ok(PeriodRollup {
+            period: kind,
+            key: range.key,
+            start: range.start,
+            end: range.end,
+            page,
+            journals,
+            referenced,
+            tagged,
+            tag_counts,
+        })
+    }
+
+    async fn find_periodic_node_id(&self, range: &PeriodRange) -> AppResult<Option<String>> {
+        sqlx::query_scalar("SELECT node_id FROM periodic_notes WHERE period_type = ? AND period_key = ?")
+            .bind(range.kind.as_str())
+            .bind(&range.key)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))
+    }
+}
@@ -0,0 +1,42 @@
+use crate::errors::{AppError, AppResult};
+use super::connection::DatabaseService;
+use serde_json::{Map, Value};
+
+impl DatabaseService {
+    /// Load the default user's preferences object
+    pub async fn get_user_preferences(&self) -> AppResult<Map<String, Value>> {
+        let user_id = self.get_default_user_id().await?;
+        let raw: Option<String> = sqlx::query_scalar("SELECT preferences FROM users WHERE id = ?")
+            .bind(&user_id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+
+        match raw.as_deref().map(serde_json::from_str::<Value>) {
+            Some(Ok(Value::Object(map))) => Ok(map),
+            _ => Ok(Map::new()),
+        }
+    }
+
+    /// Read a single preference value
+    pub async fn get_user_preference(&self, key: &str) -> AppResult<Option<Value>> {
+        Ok(self.get_user_preferences().await?.remove(key))
+    }
+
+    /// Store a single preference value, keeping the others intact
+    pub async fn set_user_preference(&self, key: &str, value: Value) -> AppResult<()> {
+        let user_id = self.get_default_user_id().await?;
+        let mut preferences = self.get_user_preferences().await?;
+        preferences.insert(key.to_string(), value);
+
+        let json = serde_json::to_string(&preferences)?;
+        sqlx::query("UPDATE users SET preferences = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+            .bind(&json)
+            .bind(&user_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,335 @@
+use crate::errors::{AppError, AppResult};
+use super::connection::DatabaseService;
+use crate::models::NodeWithChildren;
+use crate::utils::{content_tags, escape_html, format_property_value, parse_property_lines, render_inline_html, slugify};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+/// Property lines that only steer publishing and are not shown
+const HIDDEN_PROPERTIES: [&str; 2] = ["public", "id"];
+
+const STYLESHEET: &str = "body { font-family: system-ui, sans-serif; max-width: 46rem; margin: 2rem auto; padding: 0 1rem; line-height: 1.5; color: #222; }
+a { color: #2563eb; text-decoration: none; }
+a:hover { text-decoration: underline; }
+nav { margin-bottom: 1.5rem; font-size: 0.9rem; }
+ul.blocks, ul.blocks ul { list-style: disc; padding-left: 1.4rem; }
+.tag { color: #6b7280; }
+code { background: #f3f4f6; padding: 0 0.2rem; border-radius: 3px; }
+section.backlinks { margin-top: 2.5rem; border-top: 1px solid #e5e7eb; }
+";
+
+/// Files written by a static site publish
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PublishSummary {
+    pub pages: usize,
+    pub tags: usize,
+}
+
+/// One page in `search-index.json`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchIndexEntry {
+    pub title: String,
+    pub url: String,
+    pub tags: Vec<String>,
+    pub text: String,
+}
+
+/// A public page on its way to HTML
+struct PublishedPage {
+    title: String,
+    slug: String,
+    tree: NodeWithChildren,
+    tags: Vec<String>,
+}
+
+impl DatabaseService {
+    /// Publish the pages marked `public:: true` as a static site in `dir`:
+    /// `pages/<slug>.html` for every public page with its blocks and
+    /// backlinks, `tags/<slug>.html` for every tag they use, an `index.html`
+    /// listing the pages and a `search-index.json` for client-side search.
+    ///
+    /// Nothing else leaves the graph: blocks marked `public:: false` are
+    /// dropped with their children, and links or backlinks to pages that
+    /// are not public turn into plain text. Publishing again replaces the
+    /// previous `pages/` and `tags/` folders, so pages that stopped being
+    /// public disappear.
+    pub async fn publish_site(&self, dir: &Path) -> AppResult<PublishSummary> {
+        prepare_site_dir(dir)?;
+
+        let mut pages = Vec::new();
+        let mut used_slugs = HashSet::new();
+        for root in self.get_root_nodes().await? {
+            if is_public(&root.properties, &root.content) != Some(true) {
+                continue;
+            }
+            let mut tree = self.get_node_with_children(&root.id).await?;
+            prune_private_blocks(&mut tree);
+
+            let title = root.content.lines().next().unwrap_or_default().to_string();
+            let mut slug = slugify(&title);
+            let mut suffix = 1;
+            while !used_slugs.insert(slug.clone()) {
+                suffix += 1;
+                slug = format!("{}-{}", slugify(&title), suffix);
+            }
+            pages.push(PublishedPage { title, slug, tree, tags: Vec::new() });
+        }
+
+        // Page for every published node, and titles and aliases `[[links]]` may use
+        let mut node_pages: HashMap<String, usize> = HashMap::new();
+        let mut titles: HashMap<String, usize> = HashMap::new();
+        for (index, page) in pages.iter_mut().enumerate() {
+            titles.entry(page.title.to_lowercase()).or_insert(index);
+            if let Some(aliases) = page.tree.node.properties.get("alias") {
+                for alias in format_property_value(aliases).split(',') {
+                    titles.entry(alias.trim().to_lowercase()).or_insert(index);
+                }
+            }
+
+            let mut tags = page.tree.node.tags.clone();
+            let mut pending = vec![&page.tree];
+            while let Some(block) = pending.pop() {
+                node_pages.insert(block.node.id.clone(), index);
+                for tag in block.node.tags.iter().cloned().chain(content_tags(&block.node.content)) {
+                    if !tags.iter().any(|known| known.to_lowercase() == tag.to_lowercase()) {
+                        tags.push(tag);
+                    }
+                }
+                pending.extend(block.child_nodes.iter());
+            }
+            page.tags = tags;
+        }
+
+        let links: Vec<(String, String)> = sqlx::query_as("SELECT source_node_id, target_node_id FROM node_links")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+        let mut targets: HashMap<String, Vec<String>> = HashMap::new();
+        let mut backlinks: Vec<Vec<usize>> = vec![Vec::new(); pages.len()];
+        for (source, target) in links {
+            let (Some(&from), Some(&to)) = (node_pages.get(&source), node_pages.get(&target)) else {
+                continue;
+            };
+            if from != to && !backlinks[to].contains(&from) {
+                backlinks[to].push(from);
+            }
+            targets.entry(source).or_default().push(target);
+        }
+
+        // Tag pages, keyed by lowercased name and listing pages by title
+        let mut tags: BTreeMap<String, (String, Vec<usize>)> = BTreeMap::new();
+        for (index, page) in pages.iter().enumerate() {
+            for tag in &page.tags {
+                tags.entry(tag.to_lowercase()).or_insert_with(|| (tag.clone(), Vec::new())).1.push(index);
+            }
+        }
+        let mut tag_slugs: HashMap<String, String> = HashMap::new();
+        let mut used_tag_slugs = HashSet::new();
+        for key in tags.keys() {
+            let mut slug = slugify(key);
+            let mut suffix = 1;
+            while !used_tag_slugs.insert(slug.clone()) {
+                suffix += 1;
+                slug = format!("{}-{}", slugify(key), suffix);
+            }
+            tag_slugs.insert(key.clone(), slug);
+        }
+
+        let site = Site { pages: &pages, node_pages: &node_pages, titles: &titles, targets: &targets, tag_slugs: &tag_slugs };
+        let mut search_index = Vec::new();
+        for (index, page) in pages.iter().enumerate() {
+            let mut referrers: Vec<&PublishedPage> = backlinks[index].iter().map(|&from| &pages[from]).collect();
+            referrers.sort_by_key(|referrer| referrer.title.to_lowercase());
+            write_file(&dir.join("pages").join(format!("{}.html", page.slug)), &site.render_page(page, &referrers))?;
+
+            let mut text = Vec::new();
+            let mut pending: Vec<&NodeWithChildren> = page.tree.child_nodes.iter().rev().collect();
+            while let Some(block) = pending.pop() {
+                text.push(block.node.content.as_str());
+                pending.extend(block.child_nodes.iter().rev());
+            }
+            search_index.push(SearchIndexEntry {
+                title: page.title.clone(),
+                url: format!("pages/{}.html", page.slug),
+                tags: page.tags.clone(),
+                text: text.join("\n"),
+            });
+        }
+
+        for (key, (name, tagged)) in &tags {
+            let mut tagged: Vec<&PublishedPage> = tagged.iter().map(|&index| &pages[index]).collect();
+            tagged.sort_by_key(|page| page.title.to_lowercase());
+            let list = page_list(&tagged, "../pages/");
+            let body = format!("<h1>#{}</h1>\n{}", escape_html(name), list);
+            write_file(&dir.join("tags").join(format!("{}.html", tag_slugs[key])), &html_document(name, "../", &body))?;
+        }
+
+        let mut listed: Vec<&PublishedPage> = pages.iter().collect();
+        listed.sort_by_key(|page| page.title.to_lowercase());
+        let mut body = format!("<h1>Pages</h1>\n{}", page_list(&listed, "pages/"));
+        if !tags.is_empty() {
+            body.push_str("<h2>Tags</h2>\n<ul class=\"tags\">\n");
+            for (key, (name, _)) in &tags {
+                body.push_str(&format!("<li><a class=\"tag\" href=\"tags/{}.html\">#{}</a></li>\n", tag_slugs[key], escape_html(name)));
+            }
+            body.push_str("</ul>\n");
+        }
+        write_file(&dir.join("index.html"), &html_document("Index", "", &body))?;
+        write_file(&dir.join("style.css"), STYLESHEET)?;
+        write_file(&dir.join("search-index.json"), &serde_json::to_string_pretty(&search_index)?)?;
+
+        Ok(PublishSummary { pages: pages.len(), tags: tags.len() })
+    }
+}
+
+/// What page rendering needs to know about the rest of the site
+struct Site<'a> {
+    pages: &'a [PublishedPage],
+    node_pages: &'a HashMap<String, usize>,
+    titles: &'a HashMap<String, usize>,
+    /// Published link targets of each published node
+    targets: &'a HashMap<String, Vec<String>>,
+    tag_slugs: &'a HashMap<String, String>,
+}
+
+impl Site<'_> {
+    fn render_page(&self, page: &PublishedPage, backlinks: &[&PublishedPage]) -> String {
+        let mut body = format!("<h1>{}</h1>\n", escape_html(&page.title));
+        if !page.tree.node.tags.is_empty() {
+            body.push_str("<p class=\"tags\">");
+            let tags: Vec<String> = page.tree.node.tags.iter().map(|tag| self.tag_link(tag)).collect();
+            body.push_str(&tags.join(" "));
+            body.push_str("</p>\n");
+        }
+
+        // `None` closes the list of children opened for a block
+        body.push_str("<ul class=\"blocks\">\n");
+        let mut pending: Vec<Option<&NodeWithChildren>> = page.tree.child_nodes.iter().rev().map(Some).collect();
+        while let Some(entry) = pending.pop() {
+            let Some(block) = entry else {
+                body.push_str("</ul></li>\n");
+                continue;
+            };
+            let content = render_inline_html(
+                &block.node.content,
+                &HIDDEN_PROPERTIES,
+                |title| self.resolve_link(&block.node.id, title),
+                |tag| format!("../tags/{}.html", self.tag_slug(tag)),
+            );
+            body.push_str(&format!("<li id=\"block-{}\">{}", escape_html(&block.node.id), content));
+            if block.child_nodes.is_empty() {
+                body.push_str("</li>\n");
+            } else {
+                body.push_str("\n<ul>\n");
+                pending.push(None);
+                pending.extend(block.child_nodes.iter().rev().map(Some));
+            }
+        }
+        body.push_str("</ul>\n");
+
+        if !backlinks.is_empty() {
+            body.push_str("<section class=\"backlinks\">\n<h2>Linked references</h2>\n");
+            body.push_str(&page_list(backlinks, ""));
+            body.push_str("</section>\n");
+        }
+        html_document(&page.title, "../", &body)
+    }
+
+    /// Where `[[title]]` in `source_id` points: a public page by title or
+    /// alias, or a published block the link was resolved to
+    fn resolve_link(&self, source_id: &str, title: &str) -> Option<String> {
+        if let Some(&index) = self.titles.get(&title.to_lowercase()) {
+            return Some(format!("{}.html", self.pages[index].slug));
+        }
+        self.targets.get(source_id)?.iter().find_map(|target| {
+            let page = &self.pages[self.node_pages[target]];
+            let mut pending = vec![&page.tree];
+            while let Some(block) = pending.pop() {
+                if block.node.id == *target {
+                    let content = &block.node.content;
+                    return (content == title || content.starts_with(title))
+                        .then(|| format!("{}.html#block-{}", page.slug, target));
+                }
+                pending.extend(block.child_nodes.iter());
+            }
+            None
+        })
+    }
+
+    fn tag_slug(&self, tag: &str) -> String {
+        self.tag_slugs.get(&tag.to_lowercase()).cloned().unwrap_or_else(|| slugify(tag))
+    }
+
+    fn tag_link(&self, tag: &str) -> String {
+        format!("<a class=\"tag\" href=\"../tags/{}.html\">#{}</a>", self.tag_slug(tag), escape_html(tag))
+    }
+}
+
+/// Whether `public` is set, as a property or a `public::` line
+fn is_public(properties: &HashMap<String, serde_json::Value>, content: &str) -> Option<bool> {
+    let value = match properties.get("public") {
+        Some(value) => format_property_value(value),
+        None => parse_property_lines(content).remove("public")?,
+    };
+    match value.trim().to_lowercase().as_str() {
+        "true" | "yes" => Some(true),
+        "false" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+/// Drop blocks marked `public:: false`, with their children
+fn prune_private_blocks(tree: &mut NodeWithChildren) {
+    let mut pending = vec![tree];
+    while let Some(block) = pending.pop() {
+        block.child_nodes.retain(|child| is_public(&child.node.properties, &child.node.content) != Some(false));
+        pending.extend(block.child_nodes.iter_mut());
+    }
+}
+
+/// Make sure `dir` is empty or an earlier published site, and clear the
+/// generated folders of the latter
+fn prepare_site_dir(dir: &Path) -> AppResult<()> {
+    if dir.is_dir() && fs::read_dir(dir)?.next().is_some() {
+        if !dir.join("search-index.json").is_file() {
+            return Err(AppError::ExportFailed(format!("{} is not empty and not a published site", dir.display())));
+        }
+        for generated in ["pages", "tags"] {
+            let path = dir.join(generated);
+            if path.is_dir() {
+                fs::remove_dir_all(&path)
+                    .map_err(|e| AppError::ExportFailed(format!("Failed to clear {}: {}", path.display(), e)))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn page_list(pages: &[&PublishedPage], prefix: &str) -> String {
+    let mut list = String::from("<ul class=\"pages\">\n");
+    for page in pages {
+        list.push_str(&format!("<li><a href=\"{}{}.html\">{}</a></li>\n", prefix, page.slug, escape_html(&page.title)));
+    }
+    list.push_str("</ul>\n");
+    list
+}
+
+/// Wrap `body` in a page; `root` is the relative path to the site root
+fn html_document(title: &str, root: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>{title}</title>\n<link rel=\"stylesheet\" href=\"{root}style.css\">\n</head>\n<body>\n<nav><a href=\"{root}index.html\">Index</a></nav>\n<main>\n{body}</main>\n</body>\n</html>\n",
+        title = escape_html(title),
+        root = root,
+        body = body,
+    )
+}
+
+fn write_file(path: &Path, contents: &str) -> AppResult<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| AppError::ExportFailed(format!("Failed to create {}: {}", parent.display(), e)))?;
+    }
+    fs::write(path, contents).map_err(|e| AppError::ExportFailed(format!("Failed to write {}: {}", path.display(), e)))
+}
@@ -88,7 +88,218 @@ impl DatabaseService {
         sqlx::query("CREATE INDEX IF NOT EXISTS idx_links_target_id ON node_links(target_node_id)")
             .execute(&self.pool)
             .await?;
-        
+
+        // Create journals table (one page per calendar date)
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS journals (
+                journal_date TEXT PRIMARY KEY,
+                node_id TEXT NOT NULL UNIQUE,
+                FOREIGN KEY (node_id) REFERENCES nodes(id) ON DELETE CASCADE
+            )"
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Create periodic notes table (week/month/quarter/year pages)
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS periodic_notes (
+                period_type TEXT NOT NULL,
+                period_key TEXT NOT NULL,
+                node_id TEXT NOT NULL UNIQUE,
+                PRIMARY KEY (period_type, period_key),
+                FOREIGN KEY (node_id) REFERENCES nodes(id) ON DELETE CASCADE
+            )"
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Create node views table (last time each node was opened)
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS node_views (
+                node_id TEXT PRIMARY KEY,
+                last_viewed_at DATETIME NOT NULL,
+                view_count INTEGER NOT NULL DEFAULT 0,
+                FOREIGN KEY (node_id) REFERENCES nodes(id) ON DELETE CASCADE
+            )"
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Typed date properties; source is 'content' when read from `key:: value` lines
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS node_dates (
+                node_id TEXT NOT NULL,
+                key TEXT NOT NULL,
+                date TEXT NOT NULL,
+                source TEXT NOT NULL DEFAULT 'content',
+                PRIMARY KEY (node_id, key),
+                FOREIGN KEY (node_id) REFERENCES nodes(id) ON DELETE CASCADE
+            )"
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_node_dates_key_date ON node_dates(key, date)")
+            .execute(&self.pool)
+            .await?;
+
+        // Task state per node, derived from the marker at the start of its content
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS tasks (
+                node_id TEXT PRIMARY KEY,
+                state TEXT NOT NULL,
+                state_changed_at DATETIME NOT NULL,
+                FOREIGN KEY (node_id) REFERENCES nodes(id) ON DELETE CASCADE
+            )"
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Added after the table first shipped
+        self.add_column_if_missing("tasks", "priority", "TEXT").await?;
+        self.add_column_if_missing("tasks", "effort_minutes", "INTEGER").await?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_tasks_state ON tasks(state)")
+            .execute(&self.pool)
+            .await?;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS task_transitions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                node_id TEXT NOT NULL,
+                from_state TEXT,
+                to_state TEXT,
+                changed_at DATETIME NOT NULL,
+                FOREIGN KEY (node_id) REFERENCES nodes(id) ON DELETE CASCADE
+            )"
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_task_transitions_node ON task_transitions(node_id, changed_at)")
+            .execute(&self.pool)
+            .await?;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS task_completions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                node_id TEXT NOT NULL,
+                scheduled_for TEXT,
+                completed_at DATETIME NOT NULL,
+                FOREIGN KEY (node_id) REFERENCES nodes(id) ON DELETE CASCADE
+            )"
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_task_completions_node ON task_completions(node_id, completed_at)")
+            .execute(&self.pool)
+            .await?;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS node_clock_entries (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                node_id TEXT NOT NULL,
+                started_at DATETIME NOT NULL,
+                ended_at DATETIME,
+                FOREIGN KEY (node_id) REFERENCES nodes(id) ON DELETE CASCADE
+            )"
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_clock_entries_node ON node_clock_entries(node_id, started_at)")
+            .execute(&self.pool)
+            .await?;
+
+        // At most one clock may run at a time
+        sqlx::query(
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_clock_entries_running
+             ON node_clock_entries(ended_at IS NULL) WHERE ended_at IS NULL"
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Spaced-repetition state of nodes studied as flashcards
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS cards (
+                node_id TEXT PRIMARY KEY,
+                ease REAL NOT NULL DEFAULT 2.5,
+                interval_days INTEGER NOT NULL DEFAULT 0,
+                repetitions INTEGER NOT NULL DEFAULT 0,
+                lapses INTEGER NOT NULL DEFAULT 0,
+                due_date TEXT NOT NULL,
+                last_reviewed_at DATETIME,
+                FOREIGN KEY (node_id) REFERENCES nodes(id) ON DELETE CASCADE
+            )"
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_cards_due_date ON cards(due_date)")
+            .execute(&self.pool)
+            .await?;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS card_reviews (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                node_id TEXT NOT NULL,
+                grade INTEGER NOT NULL,
+                interval_days INTEGER NOT NULL,
+                ease REAL NOT NULL,
+                review_date TEXT NOT NULL,
+                reviewed_at DATETIME NOT NULL,
+                FOREIGN KEY (node_id) REFERENCES nodes(id) ON DELETE CASCADE
+            )"
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_card_reviews_date ON card_reviews(review_date)")
+            .execute(&self.pool)
+            .await?;
+
+        // Habits tracked through `key:: value` lines in journal pages
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS habits (
+                key TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                created_at DATETIME NOT NULL
+            )"
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Adopt task markers written before the tasks table existed
+        sqlx::query(
+            "INSERT OR IGNORE INTO tasks (node_id, state, state_changed_at)
+             SELECT id, CASE
+                        WHEN marker = 'CANCELLED' THEN 'CANCELED'
+                        ELSE marker
+                    END, updated_at
+             FROM (
+                 SELECT id, updated_at,
+                        substr(text, 1, instr(text || ' ', ' ') - 1) AS marker
+                 FROM (SELECT id, updated_at, replace(replace(ltrim(content), char(10), ' '), char(9), ' ') AS text FROM nodes)
+             )
+             WHERE marker IN ('TODO', 'DOING', 'DONE', 'CANCELED', 'CANCELLED')"
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Adopt daily notes created before the journals table existed
+        sqlx::query(
+            "INSERT OR IGNORE INTO journals (journal_date, node_id)
+             SELECT json_extract(properties, '$.date'), id
+             FROM nodes
+             WHERE json_valid(properties)
+               AND json_extract(properties, '$.type') = 'daily_note'
+               AND date(json_extract(properties, '$.date')) = json_extract(properties, '$.date')
+             ORDER BY created_at"
+        )
+        .execute(&self.pool)
+        .await?;
+
         // Create users table
         sqlx::query(
             "CREATE TABLE IF NOT EXISTS users (
@@ -106,6 +317,21 @@ impl DatabaseService {
         Ok(())
     }
 
+    /// Add `column` to an existing `table` unless an earlier run already did
+    async fn add_column_if_missing(&self, table: &str, column: &str, definition: &str) -> AppResult<()> {
+        let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM pragma_table_info(?) WHERE name = ?)")
+            .bind(table)
+            .bind(column)
+            .fetch_one(&self.pool)
+            .await?;
+        if !exists {
+            sqlx::query(&format!("ALTER TABLE \"{}\" ADD COLUMN \"{}\" {}", table, column, definition))
+                .execute(&self.pool)
+                .await?;
+        }
+        Ok(())
+    }
+
     pub(crate) async fn ensure_default_user(&self) -> AppResult<()> {
         let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users")
             .fetch_one(&self.pool)
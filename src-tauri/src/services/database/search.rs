@@ -4,6 +4,22 @@ use crate::models::Node;
 use sqlx::Row;
 
 impl DatabaseService {
+    /// Check the full-text index against `nodes` and rebuild it when they
+    /// disagree, returning whether it had to be rebuilt
+    pub async fn refresh_search_index(&self) -> AppResult<bool> {
+        let check = sqlx::query("INSERT INTO nodes_fts(nodes_fts, rank) VALUES('integrity-check', 1)")
+            .execute(&self.pool)
+            .await;
+        if check.is_ok() {
+            return Ok(false);
+        }
+        sqlx::query("INSERT INTO nodes_fts(nodes_fts) VALUES('rebuild')")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| crate::errors::AppError::DatabaseQueryFailed(e.to_string()))?;
+        Ok(true)
+    }
+
     /// Search nodes by content using FTS
     pub async fn search_nodes(&self, query: &str, limit: i64) -> AppResult<Vec<Node>> {
         let rows = sqlx::query(
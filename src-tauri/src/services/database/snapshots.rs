@@ -0,0 +1,214 @@
+use crate::errors::{AppError, AppResult};
+use super::backup::{backup_tables, partial_path, table_columns};
+use super::connection::{immediate_transaction, DatabaseService};
+use crate::models::{BackupSettings, Snapshot};
+use crate::utils::{parse_snapshot_file_name, snapshot_file_name, snapshots_to_keep};
+use chrono::{DateTime, Duration, Utc};
+use sqlx::SqliteConnection;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const BACKUP_SETTINGS_PREFERENCE: &str = "backup_settings";
+
+/// How often `run_backup_scheduler` checks whether a snapshot is due
+const BACKUP_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+impl DatabaseService {
+    pub async fn get_backup_settings(&self) -> AppResult<BackupSettings> {
+        let value = self.get_user_preference(BACKUP_SETTINGS_PREFERENCE).await?;
+        Ok(value.and_then(|value| serde_json::from_value(value).ok()).unwrap_or_default())
+    }
+
+    pub async fn set_backup_settings(&self, settings: &BackupSettings) -> AppResult<()> {
+        if settings.interval_minutes == 0 {
+            return Err(AppError::ConfigurationError("Backups need an interval of at least a minute".to_string()));
+        }
+        self.set_user_preference(BACKUP_SETTINGS_PREFERENCE, serde_json::to_value(settings)?).await
+    }
+
+    /// Folder the snapshots go to under `settings`
+    pub fn backup_folder(&self, settings: &BackupSettings) -> PathBuf {
+        match settings.folder.as_deref().filter(|folder| !folder.trim().is_empty()) {
+            Some(folder) => PathBuf::from(folder),
+            None => self.db_path.with_file_name("backups"),
+        }
+    }
+
+    /// Snapshots in the backup folder, newest first
+    pub async fn list_snapshots(&self) -> AppResult<Vec<Snapshot>> {
+        let settings = self.get_backup_settings().await?;
+        list_snapshot_files(&self.backup_folder(&settings))
+    }
+
+    /// Snapshot the database into the backup folder now
+    pub async fn create_snapshot(&self) -> AppResult<Snapshot> {
+        let settings = self.get_backup_settings().await?;
+        self.create_snapshot_at(&self.backup_folder(&settings), Utc::now()).await
+    }
+
+    /// Copy the live database with `VACUUM INTO`, which reads a consistent
+    /// view without blocking other readers. The copy is renamed into place
+    /// once complete, so a snapshot file is never half written.
+    async fn create_snapshot_at(&self, folder: &Path, time: DateTime<Utc>) -> AppResult<Snapshot> {
+        let failed = |e: &dyn std::fmt::Display| AppError::ExportFailed(format!("Failed to snapshot the database: {}", e));
+        fs::create_dir_all(folder).map_err(|e| failed(&e))?;
+        let file_name = snapshot_file_name(time);
+        let path = folder.join(&file_name);
+        // Taken within the same second as the last one
+        if path.exists() {
+            return snapshot_info(&path, &file_name, time);
+        }
+
+        let partial = partial_path(&path);
+        let _ = fs::remove_file(&partial);
+        let result = sqlx::query("VACUUM INTO ?")
+            .bind(partial.to_string_lossy().into_owned())
+            .execute(&self.pool)
+            .await;
+        if let Err(e) = result {
+            let _ = fs::remove_file(&partial);
+            return Err(failed(&e));
+        }
+        fs::rename(&partial, &path).map_err(|e| failed(&e))?;
+        snapshot_info(&path, &file_name, time)
+    }
+
+    /// Take a snapshot if backups are on and the last one is at least the
+    /// configured interval old, then rotate the folder. Returns the new
+    /// snapshot, if one was taken.
+    pub async fn run_scheduled_backup(&self, now: DateTime<Utc>) -> AppResult<Option<Snapshot>> {
+        let settings = self.get_backup_settings().await?;
+        if !settings.enabled {
+            return Ok(None);
+        }
+        let folder = self.backup_folder(&settings);
+        let snapshots = list_snapshot_files(&folder)?;
+        let interval = Duration::minutes(settings.interval_minutes.max(1) as i64);
+        if snapshots.first().is_some_and(|last| now - last.created_at < interval) {
+            return Ok(None);
+        }
+
+        let snapshot = self.create_snapshot_at(&folder, now).await?;
+        prune_snapshots(&folder, &settings)?;
+        Ok(Some(snapshot))
+    }
+
+    /// Take scheduled snapshots for as long as the app runs
+    pub async fn run_backup_scheduler(&self) {
+        loop {
+            match self.run_scheduled_backup(Utc::now()).await {
+                Ok(Some(snapshot)) => tracing::info!("Saved database snapshot {}", snapshot.file_name),
+                Ok(None) => {}
+                Err(e) => tracing::warn!("Scheduled backup failed: {}", e),
+            }
+            tokio::time::sleep(BACKUP_CHECK_INTERVAL).await;
+        }
+    }
+
+    /// Bring the database back to the snapshot `file_name`.
+    ///
+    /// The current state is snapshotted first, and that snapshot is
+    /// returned so the restore can itself be undone. Tables are copied over
+    /// from the snapshot in one transaction, leaving the database file and
+    /// its open connections in place; backup settings stay as they are now.
+    pub async fn restore_snapshot(&self, file_name: &str) -> AppResult<Snapshot> {
+        let settings = self.get_backup_settings().await?;
+        let folder = self.backup_folder(&settings);
+        let path = folder.join(file_name);
+        if parse_snapshot_file_name(file_name).is_none() || !path.is_file() {
+            return Err(AppError::NotFound(format!("Snapshot {}", file_name)));
+        }
+        let safety = self.create_snapshot_at(&folder, Utc::now()).await?;
+
+        let mut conn = self.pool.acquire().await
+            .map_err(|e| AppError::DatabaseConnectionFailed(e.to_string()))?;
+        sqlx::query("ATTACH DATABASE ? AS snapshot")
+            .bind(path.to_string_lossy().into_owned())
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+        let result = immediate_transaction(&mut conn, |conn| Box::pin(copy_snapshot_tables(conn))).await;
+        if let Err(e) = sqlx::query("DETACH DATABASE snapshot").execute(&mut *conn).await {
+            // Close the connection rather than pool it with the snapshot attached
+            drop(conn.detach());
+            result?;
+            return Err(AppError::DatabaseQueryFailed(e.to_string()));
+        }
+        drop(conn);
+        result?;
+
+        self.set_backup_settings(&settings).await?;
+        self.refresh_search_index().await?;
+        Ok(safety)
+    }
+}
+
+/// Replace the tables of the main database with those of the attached
+/// `snapshot`, keeping to the columns both have
+async fn copy_snapshot_tables(conn: &mut SqliteConnection) -> AppResult<()> {
+    let query_failed = |e: sqlx::Error| AppError::DatabaseQueryFailed(e.to_string());
+    sqlx::query("PRAGMA defer_foreign_keys = ON").execute(&mut *conn).await.map_err(query_failed)?;
+    let tables = backup_tables(conn, "main").await?;
+    let saved = backup_tables(conn, "snapshot").await?;
+    for table in &tables {
+        sqlx::query(&format!("DELETE FROM main.\"{}\"", table))
+            .execute(&mut *conn)
+            .await
+            .map_err(query_failed)?;
+    }
+    for table in tables.iter().filter(|table| saved.contains(table)) {
+        let current = table_columns(conn, "main", table).await?;
+        let columns: Vec<String> = table_columns(conn, "snapshot", table)
+            .await?
+            .into_iter()
+            .filter(|column| current.contains(column))
+            .map(|column| format!("\"{}\"", column))
+            .collect();
+        if columns.is_empty() {
+            continue;
+        }
+        let columns = columns.join(", ");
+        sqlx::query(&format!(
+            "INSERT INTO main.\"{0}\" ({1}) SELECT {1} FROM snapshot.\"{0}\"",
+            table, columns
+        ))
+        .execute(&mut *conn)
+        .await
+        .map_err(query_failed)?;
+    }
+    Ok(())
+}
+
+/// Snapshot files in `folder`, newest first; other files are ignored
+fn list_snapshot_files(folder: &Path) -> AppResult<Vec<Snapshot>> {
+    let mut snapshots = Vec::new();
+    if !folder.is_dir() {
+        return Ok(snapshots);
+    }
+    for entry in fs::read_dir(folder)? {
+        let entry = entry?;
+        let file_name = entry.file_name().to_string_lossy().into_owned();
+        if let Some(created_at) = parse_snapshot_file_name(&file_name).filter(|_| entry.path().is_file()) {
+            snapshots.push(snapshot_info(&entry.path(), &file_name, created_at)?);
+        }
+    }
+    snapshots.sort_by_key(|snapshot| std::cmp::Reverse(snapshot.created_at));
+    Ok(snapshots)
+}
+
+fn snapshot_info(path: &Path, file_name: &str, created_at: DateTime<Utc>) -> AppResult<Snapshot> {
+    Ok(Snapshot { file_name: file_name.to_string(), created_at, size: fs::metadata(path)?.len() })
+}
+
+/// Delete the snapshots the rotation no longer keeps
+fn prune_snapshots(folder: &Path, settings: &BackupSettings) -> AppResult<()> {
+    let snapshots = list_snapshot_files(folder)?;
+    let times: Vec<DateTime<Utc>> = snapshots.iter().map(|snapshot| snapshot.created_at).collect();
+    let keep = snapshots_to_keep(&times, settings.keep_hourly, settings.keep_daily, settings.keep_weekly);
+    for (index, snapshot) in snapshots.iter().enumerate() {
+        if !keep.contains(&index) {
+            fs::remove_file(folder.join(&snapshot.file_name))?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,331 @@
+use crate::errors::{AppError, AppResult};
+use super::connection::DatabaseService;
+use crate::models::{
+    Node, Priority, Task, TaskCompletion, TaskFilter, TaskGroup, TaskState, TaskTransition, UpdateNodeRequest,
+};
+use crate::utils::{
+    format_iso_date, parse_effort_property, parse_iso_date, parse_priority, parse_task_marker, set_task_marker,
+    RecurringAdvance,
+};
+use chrono::{DateTime, Utc};
+use sqlx::{Row, SqliteConnection};
+
+impl DatabaseService {
+    /// Bring the stored task state, priority and effort in line with
+    /// `node`'s content, recording a transition when the state changed
+    pub async fn sync_task_state(&self, node: &Node) -> AppResult<Option<TaskState>> {
+        let state = parse_task_marker(&node.content).map(|(state, _)| state);
+        let priority = parse_priority(&node.content);
+        let effort_minutes = parse_effort_property(&node.content);
+        let node_id = node.id.clone();
+
+        self.with_immediate_transaction(|conn| Box::pin(async move {
+            let previous: Option<String> = sqlx::query_scalar("SELECT state FROM tasks WHERE node_id = ?")
+                .bind(&node_id)
+                .fetch_optional(&mut *conn)
+                .await
+                .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+            let previous = previous.as_deref().and_then(TaskState::from_marker);
+
+            if previous != state {
+                let now = Utc::now();
+                match state {
+                    Some(state) => {
+                        sqlx::query(
+                            r#"
+                            INSERT INTO tasks (node_id, state, state_changed_at) VALUES (?, ?, ?)
+                            ON CONFLICT(node_id) DO UPDATE SET
+                                state = excluded.state,
+                                state_changed_at = excluded.state_changed_at
+                            "#
+                        )
+                        .bind(&node_id)
+                        .bind(state.marker())
+                        .bind(now)
+                        .execute(&mut *conn)
+                        .await
+                        .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+                    }
+                    None => {
+                        sqlx::query("DELETE FROM tasks WHERE node_id = ?")
+                            .bind(&node_id)
+                            .execute(&mut *conn)
+                            .await
+                            .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+                    }
+                }
+
+                insert_transition(conn, &node_id, previous, state, now).await?;
+                if state == Some(TaskState::Done) {
+                    let scheduled_for: Option<String> = sqlx::query_scalar(
+                        r#"
+                        SELECT date FROM node_dates
+                        WHERE node_id = ? AND key IN ('scheduled', 'deadline', 'due')
+                        ORDER BY CASE key WHEN 'scheduled' THEN 0 WHEN 'deadline' THEN 1 ELSE 2 END
+                        LIMIT 1
+                        "#
+                    )
+                    .bind(&node_id)
+                    .fetch_optional(&mut *conn)
+                    .await
+                    .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+                    insert_completion(conn, &node_id, scheduled_for.as_deref(), now).await?;
+                }
+            }
+
+            sqlx::query("UPDATE tasks SET priority = ?, effort_minutes = ? WHERE node_id = ?")
+                .bind(priority.map(|priority| priority.as_str()))
+                .bind(effort_minutes)
+                .bind(&node_id)
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+            Ok(())
+        })).await?;
+
+        Ok(state)
+    }
+
+    /// Current task state of a node, `None` for plain nodes
+    pub async fn get_task_state(&self, node_id: &str) -> AppResult<Option<TaskState>> {
+        let state: Option<String> = sqlx::query_scalar("SELECT state FROM tasks WHERE node_id = ?")
+            .bind(node_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+        Ok(state.as_deref().and_then(TaskState::from_marker))
+    }
+
+    /// Log the completion of a repeating task that goes straight back to TODO
+    pub(crate) async fn complete_recurring_task(&self, node_id: &str, advance: &RecurringAdvance) -> AppResult<()> {
+        let node_id = node_id.to_string();
+        let scheduled_for = advance.completed.first().map(|(_, date)| format_iso_date(*date));
+
+        self.with_immediate_transaction(|conn| Box::pin(async move {
+            let previous: Option<String> = sqlx::query_scalar("SELECT state FROM tasks WHERE node_id = ?")
+                .bind(&node_id)
+                .fetch_optional(&mut *conn)
+                .await
+                .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+            let previous = previous.as_deref().and_then(TaskState::from_marker);
+
+            let now = Utc::now();
+            insert_transition(conn, &node_id, previous, Some(TaskState::Done), now).await?;
+            insert_transition(conn, &node_id, Some(TaskState::Done), Some(TaskState::Todo), now).await?;
+            insert_completion(conn, &node_id, scheduled_for.as_deref(), now).await?;
+
+            sqlx::query(
+                r#"
+                INSERT INTO tasks (node_id, state, state_changed_at) VALUES (?, 'TODO', ?)
+                ON CONFLICT(node_id) DO UPDATE SET
+                    state = excluded.state,
+                    state_changed_at = excluded.state_changed_at
+                "#
+            )
+            .bind(&node_id)
+            .bind(now)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+            Ok(())
+        })).await
+    }
+
+    /// Logged completions of a task, oldest first
+    pub async fn get_task_completions(&self, node_id: &str) -> AppResult<Vec<TaskCompletion>> {
+        let rows = sqlx::query("SELECT scheduled_for, completed_at FROM task_completions WHERE node_id = ? ORDER BY id")
+            .bind(node_id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(TaskCompletion {
+                    scheduled_for: row.get::<Option<String>, _>("scheduled_for").as_deref().map(parse_iso_date).transpose()?,
+                    completed_at: row.get("completed_at"),
+                })
+            })
+            .collect()
+    }
+
+    /// Set (or with `None` clear) a node's task marker
+    pub async fn set_task_state(&self, node_id: &str, state: Option<TaskState>) -> AppResult<Node> {
+        let node = self.get_node(node_id).await?;
+        let content = set_task_marker(&node.content, state);
+        if content == node.content {
+            return Ok(node);
+        }
+
+        let node = self.update_node(node_id, UpdateNodeRequest {
+            content: Some(content),
+            parent_id: None,
+            order: None,
+            properties: None,
+            tags: None,
+        }).await?;
+        self.index_node_content(&node).await
+    }
+
+    /// Advance a node to its next task state; plain nodes become TODO
+    pub async fn cycle_task_state(&self, node_id: &str) -> AppResult<Node> {
+        let node = self.get_node(node_id).await?;
+        let next = match parse_task_marker(&node.content) {
+            Some((state, _)) => state.next(),
+            None => TaskState::Todo,
+        };
+        self.set_task_state(node_id, Some(next)).await
+    }
+
+    /// Tasks matching `filter`, soonest due first, then most recently changed
+    pub async fn list_tasks(&self, filter: &TaskFilter) -> AppResult<Vec<Task>> {
+        let states = filter
+            .states
+            .as_ref()
+            .map(|states| serde_json::to_string(&states.iter().map(|state| state.marker()).collect::<Vec<_>>()))
+            .transpose()?;
+
+        let rows = sqlx::query(
+            r#"
+            WITH RECURSIVE ancestry(task_id, id, parent_id) AS (
+                SELECT t.node_id, n.id, n.parent_id FROM tasks t
+                JOIN nodes n ON n.id = t.node_id
+                UNION ALL
+                SELECT a.task_id, n.id, n.parent_id FROM ancestry a
+                JOIN nodes n ON n.id = a.parent_id
+            )
+            SELECT t.node_id, t.state, t.state_changed_at, t.priority, t.effort_minutes,
+                   a.id AS page_id, d.date AS due, dl.date AS deadline
+            FROM tasks t
+            JOIN nodes n ON n.id = t.node_id
+            JOIN ancestry a ON a.task_id = t.node_id AND a.parent_id IS NULL
+            LEFT JOIN node_dates d ON d.node_id = t.node_id AND d.key = 'due'
+            LEFT JOIN node_dates dl ON dl.node_id = t.node_id AND dl.key = 'deadline'
+            WHERE (?1 IS NULL OR t.state IN (SELECT value FROM json_each(?1)))
+              AND (?2 IS NULL OR a.id = ?2)
+              AND (?3 IS NULL OR (json_valid(n.tags) AND EXISTS (SELECT 1 FROM json_each(n.tags) WHERE value = ?3)))
+              AND (?4 IS NULL OR d.date <= ?4)
+              AND (?5 IS NULL OR d.date >= ?5)
+            ORDER BY d.date IS NULL, d.date, t.state_changed_at DESC
+            LIMIT ?6
+            "#
+        )
+        .bind(&states)
+        .bind(&filter.page_id)
+        .bind(&filter.tag)
+        .bind(filter.due_before.map(format_iso_date))
+        .bind(filter.due_after.map(format_iso_date))
+        .bind(filter.limit.unwrap_or(-1))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+
+        let mut tasks = Vec::new();
+        for row in rows {
+            let state = row.get::<String, _>("state");
+            let state = TaskState::from_marker(&state)
+                .ok_or_else(|| AppError::Internal(format!("Unknown task state: {}", state)))?;
+            tasks.push(Task {
+                node: self.get_node(&row.get::<String, _>("node_id")).await?,
+                state,
+                page_id: row.get("page_id"),
+                due: row.get::<Option<String>, _>("due").as_deref().map(parse_iso_date).transpose()?,
+                deadline: row.get::<Option<String>, _>("deadline").as_deref().map(parse_iso_date).transpose()?,
+                priority: row.get::<Option<String>, _>("priority").as_deref().and_then(Priority::from_letter),
+                effort_minutes: row.get("effort_minutes"),
+                state_changed_at: row.get::<DateTime<Utc>, _>("state_changed_at"),
+            });
+        }
+        Ok(tasks)
+    }
+
+    /// Tasks for a daily plan, grouped by page.
+    ///
+    /// Within a page tasks run by priority (A first), then deadline (or due
+    /// date, earliest first), then effort (quickest first); pages are ordered
+    /// by their most pressing task. Without a state filter only open tasks
+    /// are planned.
+    pub async fn get_task_plan(&self, filter: &TaskFilter) -> AppResult<Vec<TaskGroup>> {
+        let mut filter = filter.clone();
+        filter.states.get_or_insert_with(|| vec![TaskState::Todo, TaskState::Doing]);
+        let mut tasks = self.list_tasks(&filter).await?;
+
+        // `None` sorts after every value in each key
+        tasks.sort_by_key(|task| {
+            (
+                task.priority.is_none(),
+                task.priority,
+                task.deadline.or(task.due).is_none(),
+                task.deadline.or(task.due),
+                task.effort_minutes.is_none(),
+                task.effort_minutes,
+            )
+        });
+
+        let mut groups: Vec<TaskGroup> = Vec::new();
+        for task in tasks {
+            match groups.iter_mut().find(|group| group.page.id == task.page_id) {
+                Some(group) => group.tasks.push(task),
+                None => groups.push(TaskGroup {
+                    page: self.get_node(&task.page_id).await?,
+                    tasks: vec![task],
+                }),
+            }
+        }
+        Ok(groups)
+    }
+
+    /// Every recorded state change of a task, oldest first
+    pub async fn get_task_history(&self, node_id: &str) -> AppResult<Vec<TaskTransition>> {
+        let rows = sqlx::query(
+            "SELECT from_state, to_state, changed_at FROM task_transitions WHERE node_id = ? ORDER BY id"
+        )
+        .bind(node_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| TaskTransition {
+                from_state: row.get::<Option<String>, _>("from_state").as_deref().and_then(TaskState::from_marker),
+                to_state: row.get::<Option<String>, _>("to_state").as_deref().and_then(TaskState::from_marker),
+                changed_at: row.get("changed_at"),
+            })
+            .collect())
+    }
+}
+
+async fn insert_transition(
+    conn: &mut SqliteConnection,
+    node_id: &str,
+    from: Option<TaskState>,
+    to: Option<TaskState>,
+    at: DateTime<Utc>,
+) -> AppResult<()> {
+    sqlx::query("INSERT INTO task_transitions (node_id, from_state, to_state, changed_at) VALUES (?, ?, ?, ?)")
+        .bind(node_id)
+        .bind(from.map(|state| state.marker()))
+        .bind(to.map(|state| state.marker()))
+        .bind(at)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+    Ok(())
+}
+
+async fn insert_completion(
+    conn: &mut SqliteConnection,
+    node_id: &str,
+    scheduled_for: Option<&str>,
+    at: DateTime<Utc>,
+) -> AppResult<()> {
+    sqlx::query("INSERT INTO task_completions (node_id, scheduled_for, completed_at) VALUES (?, ?, ?)")
+        .bind(node_id)
+        .bind(scheduled_for)
+        .bind(at)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+    Ok(())
+}
@@ -0,0 +1,222 @@
+use crate::errors::{AppError, AppResult};
+use super::connection::DatabaseService;
+use crate::models::{Node, NodeWithChildren};
+use crate::utils::{
+    format_iso_date, format_journal_title, generate_id, parse_property_lines, render_template, render_template_value,
+};
+use crate::services::link_service::rebuild_node_links;
+use chrono::{Local, NaiveDate, Utc};
+use sqlx::SqliteConnection;
+use std::collections::HashMap;
+
+/// Property that marks a node as a template, e.g. `template:: daily`
+pub const TEMPLATE_PROPERTY: &str = "template";
+
+/// Template name applied to newly created journal pages
+pub const DAILY_TEMPLATE: &str = "daily";
+
+impl DatabaseService {
+    /// Find the most recently edited template registered under `name`
+    pub async fn find_template(&self, name: &str) -> AppResult<Option<Node>> {
+        let templates = self.template_candidates().await?;
+        Ok(templates.into_iter().find(|node| template_name(node).as_deref() == Some(name)))
+    }
+
+    /// List every node marked as a template
+    pub async fn list_templates(&self) -> AppResult<Vec<Node>> {
+        let mut templates: Vec<Node> = self
+            .template_candidates()
+            .await?
+            .into_iter()
+            .filter(|node| template_name(node).is_some())
+            .collect();
+        templates.sort_by(|a, b| a.content.cmp(&b.content));
+        Ok(templates)
+    }
+
+    /// Nodes that may carry the template marker, as a property or as a
+    /// `template::` line in their content, most recently edited first
+    async fn template_candidates(&self) -> AppResult<Vec<Node>> {
+        let ids = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT id FROM nodes
+            WHERE (json_valid(properties) AND json_extract(properties, '$.template') IS NOT NULL)
+               OR content LIKE '%template::%'
+            ORDER BY updated_at DESC
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+
+        let mut nodes = Vec::new();
+        for id in ids {
+            nodes.push(self.get_node(&id).await?);
+        }
+        Ok(nodes)
+    }
+
+    /// Deep-copy the children of a template under `target_id`.
+    ///
+    /// Variables resolve against the target's journal date, or today for
+    /// ordinary pages. Returns the created nodes in insertion order.
+    pub async fn apply_template(&self, template_id: &str, target_id: &str) -> AppResult<Vec<Node>> {
+        let date = match self.get_journal_date(target_id).await? {
+            Some(date) => date,
+            None => Local::now().date_naive(),
+        };
+        self.apply_template_for_date(template_id, target_id, date).await
+    }
+
+    pub(crate) async fn apply_template_for_date(
+        &self,
+        template_id: &str,
+        target_id: &str,
+        date: NaiveDate,
+    ) -> AppResult<Vec<Node>> {
+        if template_id == target_id {
+            return Err(AppError::InvalidBlockData("A template cannot be applied to itself".to_string()));
+        }
+
+        // Snapshot the template first so copying into its own subtree terminates
+        let template = self.get_node_with_children(template_id).await?;
+        self.get_node(target_id).await?;
+        let vars = self.template_variables(date).await?;
+
+        let target_id = target_id.to_string();
+        let created_ids = self.with_immediate_transaction(|conn| Box::pin(async move {
+            copy_template_children(conn, &template, &target_id, &vars).await
+        })).await?;
+        self.index_copied_nodes(&created_ids, true).await
+    }
+
+    /// Sync the tasks, dates and links of blocks copied from a template, as
+    /// for blocks created one by one. Copies into a new journal page leave
+    /// the journal pages they refer to uncreated: its `{{tomorrow}}` link
+    /// would create the next page, whose template would create the one after.
+    pub(super) async fn index_copied_nodes(&self, ids: &[String], create_journals: bool) -> AppResult<Vec<Node>> {
+        let today = Local::now().date_naive();
+        let mut nodes = Vec::new();
+        for id in ids {
+            let node = self.get_node(id).await?;
+            self.sync_task_state(&node).await?;
+            let node = self.index_node_content_with(&node, today, create_journals).await?;
+            let mut conn = self.pool.acquire().await?;
+            rebuild_node_links(&mut conn, &node.id, &node.content).await?;
+            nodes.push(node);
+        }
+        Ok(nodes)
+    }
+
+    /// Values for `{{date}}`, `{{title}}`, `{{weekday}}`, `{{today}}`,
+    /// `{{yesterday}}` and `{{tomorrow}}`; the last three render as page links
+    pub(crate) async fn template_variables(&self, date: NaiveDate) -> AppResult<HashMap<String, String>> {
+        let title_format = self.get_journal_title_format().await?;
+        let page_link = |day: NaiveDate| -> AppResult<String> {
+            Ok(format!("[[{}]]", format_journal_title(day, &title_format)?))
+        };
+
+        let mut vars = HashMap::new();
+        vars.insert("date".to_string(), format_iso_date(date));
+        vars.insert("title".to_string(), format_journal_title(date, &title_format)?);
+        vars.insert("weekday".to_string(), date.format("%A").to_string());
+        vars.insert("today".to_string(), page_link(date)?);
+        vars.insert("yesterday".to_string(), page_link(date.pred_opt().unwrap_or(date))?);
+        vars.insert("tomorrow".to_string(), page_link(date.succ_opt().unwrap_or(date))?);
+        Ok(vars)
+    }
+}
+
+/// Name a node is registered under as a template, from its `template`
+/// property or a `template:: name` line, the way habits read their values
+fn template_name(node: &Node) -> Option<String> {
+    let name = match node.properties.get(TEMPLATE_PROPERTY) {
+        Some(serde_json::Value::String(name)) => Some(name.trim().to_string()),
+        _ => parse_property_lines(&node.content).remove(TEMPLATE_PROPERTY),
+    };
+    name.filter(|name| !name.is_empty())
+}
+
+/// `content` without its `template::` lines, so copies are not templates
+fn without_template_line(content: &str) -> String {
+    if !parse_property_lines(content).contains_key(TEMPLATE_PROPERTY) {
+        return content.to_string();
+    }
+    content
+        .lines()
+        .filter(|line| !parse_property_lines(line).contains_key(TEMPLATE_PROPERTY))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Copy the children of a template snapshot under `target_id`, after any
+/// existing children, and link them. Returns the new ids in insertion order.
+pub(crate) async fn copy_template_children(
+    conn: &mut SqliteConnection,
+    template: &NodeWithChildren,
+    target_id: &str,
+    vars: &HashMap<String, String>,
+) -> AppResult<Vec<String>> {
+    let next_order: i32 = sqlx::query_scalar(
+        "SELECT COALESCE(MAX(order_index) + 1, 0) FROM nodes WHERE parent_id = ?"
+    )
+    .bind(target_id)
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+
+    let mut pending: Vec<(&NodeWithChildren, String, i32)> = template
+        .child_nodes
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(i, child)| (child, target_id.to_string(), next_order + i as i32))
+        .collect();
+
+    let now = Utc::now();
+    let mut created = Vec::new();
+
+    while let Some((source, parent_id, order)) = pending.pop() {
+        let node_id = generate_id();
+        let content = render_template(&without_template_line(&source.node.content), vars);
+        let properties: HashMap<String, serde_json::Value> = source
+            .node
+            .properties
+            .iter()
+            .filter(|(key, _)| key.as_str() != TEMPLATE_PROPERTY)
+            .map(|(key, value)| (key.clone(), render_template_value(value, vars)))
+            .collect();
+        let properties_json = serde_json::to_string(&properties)?;
+        let tags_json = serde_json::to_string(&source.node.tags)?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO nodes (id, content, parent_id, order_index, properties, tags, created_at, updated_at, created_by)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#
+        )
+        .bind(&node_id)
+        .bind(&content)
+        .bind(&parent_id)
+        .bind(order)
+        .bind(&properties_json)
+        .bind(&tags_json)
+        .bind(now)
+        .bind(now)
+        .bind("default_user")
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+
+        for child in source.child_nodes.iter().rev() {
+            pending.push((child, node_id.clone(), child.node.order));
+        }
+        created.push((node_id, content));
+    }
+
+    // Links resolve once the whole copy exists, so siblings can point at each other
+    for (node_id, content) in &created {
+        rebuild_node_links(conn, node_id, content).await?;
+    }
+    Ok(created.into_iter().map(|(node_id, _)| node_id).collect())
+}
@@ -0,0 +1,82 @@
+use crate::models::{AgendaItemKind, Node, TaskState};
+use crate::services::database::connection::DatabaseService;
+use super::{create_node, setup};
+use chrono::NaiveDate;
+
+async fn create(db: &DatabaseService, content: &str, parent_id: Option<&str>) -> Node {
+    let node = create_node(db, content, parent_id, 0, &[]).await;
+    db.index_node_content_at(&node, NaiveDate::from_ymd_opt(2026, 10, 17).unwrap()).await.unwrap()
+}
+
+#[tokio::test]
+async fn test_planning_lines_are_typed_dates() {
+    let (_temp_dir, db) = setup().await;
+
+    let node = create(&db, "TODO ship\nSCHEDULED: tomorrow DEADLINE: <2026-10-23 Fri>", None).await;
+    assert_eq!(node.content, "TODO ship\nSCHEDULED: <2026-10-18 Sun> DEADLINE: <2026-10-23 Fri>");
+
+    let dates: Vec<(String, String)> = sqlx::query_as("SELECT key, date FROM node_dates WHERE node_id = ? ORDER BY key")
+        .bind(&node.id)
+        .fetch_all(db.pool())
+        .await
+        .unwrap();
+    assert_eq!(
+        dates,
+        vec![
+            ("deadline".to_string(), "2026-10-23".to_string()),
+            ("scheduled".to_string(), "2026-10-18".to_string()),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_agenda_groups_items_by_day() {
+    let (_temp_dir, db) = setup().await;
+
+    let journal = db.get_or_create_daily_note("2026-10-17").await.unwrap();
+    let written_today = create(&db, "TODO water plants", Some(&journal.id)).await;
+    let due_today = create(&db, "TODO pay rent\ndue:: 2026-10-17", None).await;
+    let done_today = create(&db, "DONE call mum\nSCHEDULED: <2026-10-17>", None).await;
+    let deadline = create(&db, "DOING report\nDEADLINE: <2026-10-20>", None).await;
+    let late = create(&db, "TODO taxes\nSCHEDULED: <2026-10-01> DEADLINE: <2026-10-10>", None).await;
+    create(&db, "DONE old chore\ndue:: 2026-10-02", None).await;
+    create(&db, "TODO far away\ndue:: 2026-12-01", None).await;
+
+    let agenda = db.get_agenda("2026-10-17", 7).await.unwrap();
+
+    assert_eq!(agenda.today.journal.as_ref().map(|page| page.id.as_str()), Some(journal.id.as_str()));
+    let today: Vec<(&str, AgendaItemKind, Option<TaskState>)> = agenda
+        .today
+        .items
+        .iter()
+        .map(|item| (item.node.id.as_str(), item.kind, item.state))
+        .collect();
+    assert_eq!(
+        today,
+        vec![
+            (due_today.id.as_str(), AgendaItemKind::Due, Some(TaskState::Todo)),
+            (done_today.id.as_str(), AgendaItemKind::Scheduled, Some(TaskState::Done)),
+            (written_today.id.as_str(), AgendaItemKind::Journal, Some(TaskState::Todo)),
+        ]
+    );
+
+    assert_eq!(agenda.upcoming.len(), 7);
+    assert_eq!(agenda.upcoming[2].date, NaiveDate::from_ymd_opt(2026, 10, 20).unwrap());
+    assert_eq!(agenda.upcoming[2].items.len(), 1);
+    assert_eq!(agenda.upcoming[2].items[0].node.id, deadline.id);
+    assert_eq!(agenda.upcoming[2].items[0].kind, AgendaItemKind::Deadline);
+    assert!(agenda.upcoming[2].journal.is_none());
+
+    assert_eq!(agenda.overdue.len(), 1);
+    assert_eq!(agenda.overdue[0].node.id, late.id);
+    assert_eq!(agenda.overdue[0].date, NaiveDate::from_ymd_opt(2026, 10, 1).unwrap());
+}
+
+#[tokio::test]
+async fn test_agenda_rejects_bad_windows() {
+    let (_temp_dir, db) = setup().await;
+
+    assert!(db.get_agenda("2026-13-01", 7).await.is_err());
+    assert!(db.get_agenda("2026-10-17", 10_000).await.is_err());
+    assert!(db.get_agenda("2026-10-17", 0).await.unwrap().upcoming.is_empty());
+}
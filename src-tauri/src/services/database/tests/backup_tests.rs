@@ -0,0 +1,110 @@
+use crate::services::database::backup::verify_backup;
+use crate::services::database::connection::DatabaseService;
+use super::{create_node, setup};
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use zip::write::SimpleFileOptions;
+
+async fn count(db: &DatabaseService, table: &str) -> i64 {
+    sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", table)).fetch_one(db.pool()).await.unwrap()
+}
+
+#[tokio::test]
+async fn test_backup_round_trip() {
+    let (temp_dir, db) = setup().await;
+    let page = create_node(&db, "Garden", None, 0, &[]).await;
+    let task = create_node(&db, "TODO Water the tomatoes\nSCHEDULED: <2026-10-20 Tue>", Some(&page.id), 0, &[]).await;
+    db.index_node_content(&task).await.unwrap();
+    sqlx::query("INSERT INTO node_links (source_node_id, target_node_id) VALUES (?, ?)")
+        .bind(&task.id)
+        .bind(&page.id)
+        .execute(db.pool())
+        .await
+        .unwrap();
+    fs::create_dir_all(db.attachments_dir().join("photos")).unwrap();
+    fs::write(db.attachments_dir().join("photos/bed.png"), b"\x89PNG bed").unwrap();
+
+    let path = temp_dir.path().join("graph.zip");
+    let summary = db.create_backup(&path).await.unwrap();
+    assert_eq!((summary.nodes, summary.attachments), (2, 1));
+    assert!(!temp_dir.path().join("graph.zip.partial").exists());
+    let manifest = verify_backup(&path).unwrap();
+    assert!(manifest.files.iter().any(|file| file.path == "attachments/photos/bed.png" && file.size == 8));
+    assert!(manifest.tables.iter().any(|table| table.name == "tasks" && table.rows == 1));
+    assert!(!manifest.tables.iter().any(|table| table.name.starts_with("nodes_fts")));
+
+    // Restoring replaces whatever the graph held
+    let (_target_dir, target) = setup().await;
+    create_node(&target, "Scratch", None, 0, &[]).await;
+    let restored = target.restore_backup(&path).await.unwrap();
+    assert_eq!((restored.nodes, restored.rows, restored.attachments), (2, summary.rows, 1));
+
+    let tree = target.get_node_with_children(&page.id).await.unwrap();
+    assert_eq!(tree.child_nodes[0].node.content, task.content);
+    assert_eq!(tree.node.created_at, page.created_at);
+    assert_eq!(count(&target, "nodes").await, 2);
+    assert_eq!(count(&target, "node_links").await, 1);
+    let state: String = sqlx::query_scalar("SELECT state FROM tasks WHERE node_id = ?")
+        .bind(&task.id)
+        .fetch_one(target.pool())
+        .await
+        .unwrap();
+    assert_eq!(state, "TODO");
+    assert_eq!(target.search_nodes("tomatoes", 10).await.unwrap().len(), 1);
+    assert!(target.search_nodes("Scratch", 10).await.unwrap().is_empty());
+    assert_eq!(fs::read(target.attachments_dir().join("photos/bed.png")).unwrap(), b"\x89PNG bed");
+}
+
+#[tokio::test]
+async fn test_backup_reads_tables_in_pages() {
+    let (temp_dir, db) = setup().await;
+    let rows = 1234;
+    let mut tx = db.pool().begin().await.unwrap();
+    for index in 0..rows {
+        sqlx::query("INSERT INTO nodes (id, content, order_index, properties, tags, created_by) VALUES (?, ?, ?, '{}', '[]', 'user')")
+            .bind(format!("node-{}", index))
+            .bind(format!("Line {}", index))
+            .bind(index)
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+    }
+    tx.commit().await.unwrap();
+
+    let path = temp_dir.path().join("graph.zip");
+    assert_eq!(db.create_backup(&path).await.unwrap().nodes, rows as usize);
+    let (_target_dir, target) = setup().await;
+    assert_eq!(target.restore_backup(&path).await.unwrap().nodes, rows as usize);
+    assert_eq!(count(&target, "nodes").await, rows);
+}
+
+#[tokio::test]
+async fn test_restore_refuses_damaged_backup() {
+    let (temp_dir, db) = setup().await;
+    create_node(&db, "Original", None, 0, &[]).await;
+    let path = temp_dir.path().join("graph.zip");
+    db.create_backup(&path).await.unwrap();
+
+    // Copy the archive with one node record edited
+    let damaged = temp_dir.path().join("damaged.zip");
+    let mut archive = zip::ZipArchive::new(File::open(&path).unwrap()).unwrap();
+    let mut writer = zip::ZipWriter::new(File::create(&damaged).unwrap());
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index).unwrap();
+        let mut contents = String::new();
+        entry.read_to_string(&mut contents).unwrap();
+        writer.start_file(entry.name(), SimpleFileOptions::default()).unwrap();
+        writer.write_all(contents.replace("Original", "Tampered").as_bytes()).unwrap();
+    }
+    writer.finish().unwrap();
+
+    let (_target_dir, target) = setup().await;
+    create_node(&target, "Keep me", None, 0, &[]).await;
+    let error = target.restore_backup(&damaged).await.unwrap_err();
+    assert!(error.to_string().contains("tables/nodes.jsonl does not match its checksum"), "{}", error);
+    assert_eq!(target.search_nodes("Keep", 10).await.unwrap().len(), 1);
+
+    fs::write(&damaged, b"not a zip").unwrap();
+    assert!(target.restore_backup(&damaged).await.is_err());
+    assert_eq!(count(&target, "nodes").await, 1);
+}
@@ -0,0 +1,102 @@
+use crate::errors::AppError;
+use crate::models::{Node, UpdateNodeRequest};
+use crate::services::database::connection::DatabaseService;
+use super::{create_node, setup};
+use chrono::NaiveDate;
+
+fn day(day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+}
+
+async fn create(db: &DatabaseService, content: &str, parent_id: Option<&str>, tags: &[&str]) -> Node {
+    let node = create_node(db, content, parent_id, 0, tags).await;
+    db.index_node_content_at(&node, day(17)).await.unwrap()
+}
+
+#[tokio::test]
+async fn test_tagged_nodes_become_due_cards() {
+    let (_temp_dir, db) = setup().await;
+
+    let cloze = create(&db, "Capital of France ?? Paris #card", None, &[]).await;
+    let tagged = create(&db, "What does SM-2 stand for?", None, &["card"]).await;
+    create(&db, "SuperMemo 2", Some(&tagged.id), &[]).await;
+    create(&db, "Not a card", None, &[]).await;
+
+    let cards = db.get_due_cards("2026-10-17", 50).await.unwrap();
+    assert_eq!(cards.len(), 2);
+
+    let cloze_card = cards.iter().find(|card| card.node_id == cloze.id).unwrap();
+    assert_eq!((cloze_card.question.as_str(), cloze_card.answer.as_str()), ("Capital of France", "Paris"));
+    assert_eq!(cloze_card.due_date, day(17));
+    assert!(cloze_card.last_reviewed_at.is_none());
+
+    let tagged_card = cards.iter().find(|card| card.node_id == tagged.id).unwrap();
+    assert_eq!(tagged_card.answer, "SuperMemo 2");
+
+    assert!(db.get_due_cards("2026-10-16", 50).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_removing_the_tag_drops_the_card() {
+    let (_temp_dir, db) = setup().await;
+
+    let node = create(&db, "Capital of Spain ?? Madrid #card", None, &[]).await;
+    let updated = db.update_node(&node.id, UpdateNodeRequest {
+        content: Some("Capital of Spain is Madrid".to_string()),
+        parent_id: None,
+        order: None,
+        properties: None,
+        tags: None,
+    }).await.unwrap();
+    db.index_node_content_at(&updated, day(17)).await.unwrap();
+
+    assert!(db.get_due_cards("2026-10-17", 50).await.unwrap().is_empty());
+    assert!(matches!(db.review_card_at(&node.id, 4, day(17)).await, Err(AppError::NotFound(_))));
+}
+
+#[tokio::test]
+async fn test_reviews_reschedule_cards() {
+    let (_temp_dir, db) = setup().await;
+
+    let node = create(&db, "2 + 2 ?? 4 #card", None, &[]).await;
+
+    let card = db.review_card_at(&node.id, 4, day(17)).await.unwrap();
+    assert_eq!((card.schedule.repetitions, card.schedule.interval_days), (1, 1));
+    assert_eq!(card.due_date, day(18));
+    assert!(card.last_reviewed_at.is_some());
+
+    let card = db.review_card_at(&node.id, 5, day(18)).await.unwrap();
+    assert_eq!(card.due_date, day(24));
+    assert!(db.get_due_cards("2026-10-23", 50).await.unwrap().is_empty());
+
+    let card = db.review_card_at(&node.id, 1, day(24)).await.unwrap();
+    assert_eq!((card.schedule.repetitions, card.schedule.lapses), (0, 1));
+    assert_eq!(card.due_date, day(25));
+
+    assert!(db.review_card_at(&node.id, 6, day(25)).await.is_err());
+}
+
+#[tokio::test]
+async fn test_review_stats() {
+    let (_temp_dir, db) = setup().await;
+
+    let first = create(&db, "One ?? 1 #card", None, &[]).await;
+    let second = create(&db, "Two ?? 2 #card", None, &[]).await;
+    create(&db, "Three ?? 3 #card", None, &[]).await;
+
+    db.review_card_at(&first.id, 5, day(17)).await.unwrap();
+    db.review_card_at(&second.id, 2, day(17)).await.unwrap();
+
+    let stats = db.get_review_stats("2026-10-17").await.unwrap();
+    assert_eq!(stats.total_cards, 3);
+    assert_eq!(stats.new_cards, 1);
+    assert_eq!(stats.due_cards, 1);
+    assert_eq!(stats.mature_cards, 0);
+    assert_eq!(stats.reviews_today, 2);
+    assert_eq!(stats.retention_30d, Some(0.5));
+
+    let later = db.get_review_stats("2026-12-01").await.unwrap();
+    assert_eq!(later.reviews_today, 0);
+    assert_eq!(later.due_cards, 3);
+    assert_eq!(later.retention_30d, None);
+}
@@ -0,0 +1,89 @@
+use crate::errors::AppError;
+use crate::models::Node;
+use crate::services::database::connection::DatabaseService;
+use super::{create_node, setup};
+use chrono::{DateTime, TimeZone, Utc};
+
+fn at(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 10, day, hour, minute, 0).unwrap()
+}
+
+async fn clock(db: &DatabaseService, node: &Node, from: DateTime<Utc>, to: DateTime<Utc>) {
+    db.clock_in_at(&node.id, from).await.unwrap();
+    db.clock_out_at(Some(&node.id), to).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_only_one_clock_runs_at_a_time() {
+    let (_temp_dir, db) = setup().await;
+
+    let first = create_node(&db, "Write report", None, 0, &[]).await;
+    let second = create_node(&db, "Review PR", None, 0, &[]).await;
+
+    let entry = db.clock_in_at(&first.id, at(17, 9, 0)).await.unwrap();
+    assert!(entry.ended_at.is_none());
+    assert!(matches!(db.clock_in_at(&second.id, at(17, 9, 5)).await, Err(AppError::ClockConflict(_))));
+    assert!(matches!(db.clock_out_at(Some(&second.id), at(17, 9, 10)).await, Err(AppError::ClockConflict(_))));
+    assert!(matches!(db.clock_out_at(None, at(17, 8, 0)).await, Err(AppError::ClockConflict(_))));
+    assert_eq!(db.get_running_clock().await.unwrap().map(|entry| entry.node_id), Some(first.id.clone()));
+
+    let entry = db.clock_out_at(None, at(17, 9, 30)).await.unwrap();
+    assert_eq!(entry.duration_seconds, 30 * 60);
+    assert!(db.get_running_clock().await.unwrap().is_none());
+    assert!(matches!(db.clock_out(None).await, Err(AppError::NotFound(_))));
+
+    db.clock_in_at(&second.id, at(17, 10, 0)).await.unwrap();
+    assert_eq!(db.get_clock_entries(&first.id).await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_time_report_aggregates_by_node_subtree_and_tag() {
+    let (_temp_dir, db) = setup().await;
+
+    let project = create_node(&db, "Acme website", None, 0, &["acme"]).await;
+    let design = create_node(&db, "Design\nmockups and review", Some(&project.id), 0, &["design"]).await;
+    let build = create_node(&db, "Build", Some(&project.id), 0, &[]).await;
+    let other = create_node(&db, "Admin", None, 0, &[]).await;
+
+    clock(&db, &design, at(16, 9, 0), at(16, 10, 0)).await;
+    clock(&db, &build, at(17, 9, 0), at(17, 11, 0)).await;
+    // Straddles midnight at the end of the window; only 30 minutes count
+    clock(&db, &other, at(17, 23, 30), at(18, 1, 0)).await;
+    // Outside the window
+    clock(&db, &design, at(20, 9, 0), at(20, 10, 0)).await;
+
+    let report = db.get_time_report("2026-10-16", "2026-10-17").await.unwrap();
+    assert_eq!(report.total_seconds, 3 * 3600 + 30 * 60);
+
+    let by_node: Vec<(&str, i64)> = report.by_node.iter().map(|t| (t.title.as_str(), t.seconds)).collect();
+    assert_eq!(by_node, vec![("Build", 7200), ("Design", 3600), ("Admin", 1800)]);
+
+    let subtree = report.by_subtree.iter().find(|t| t.node_id == project.id).unwrap();
+    assert_eq!(subtree.seconds, 3 * 3600);
+
+    let by_tag: Vec<(&str, i64)> = report.by_tag.iter().map(|t| (t.tag.as_str(), t.seconds)).collect();
+    assert_eq!(by_tag, vec![("acme", 3 * 3600), ("design", 3600)]);
+}
+
+#[tokio::test]
+async fn test_time_report_csv() {
+    let (_temp_dir, db) = setup().await;
+
+    let project = create_node(&db, "Acme, Inc.", None, 0, &["acme"]).await;
+    let task = create_node(&db, "Call \"Bob\"", Some(&project.id), 0, &["calls", "billable"]).await;
+    clock(&db, &task, at(17, 9, 0), at(17, 9, 45)).await;
+
+    let csv = db.export_time_report_csv("2026-10-17", "2026-10-17").await.unwrap();
+    let lines: Vec<&str> = csv.split("\r\n").collect();
+    assert_eq!(lines[0], "node_id,title,page,tags,started_at,ended_at,seconds");
+    assert_eq!(
+        lines[1],
+        format!(
+            "{},\"Call \"\"Bob\"\"\",\"Acme, Inc.\",calls;billable,2026-10-17T09:00:00+00:00,2026-10-17T09:45:00+00:00,2700",
+            task.id
+        )
+    );
+    assert_eq!(lines.len(), 3);
+
+    assert!(db.get_time_report("2026-10-18", "2026-10-17").await.is_err());
+}
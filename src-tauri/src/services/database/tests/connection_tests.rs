@@ -2,27 +2,27 @@
 mod tests {
     use crate::services::DatabaseService;
     use crate::errors::AppResult;
-    use tempfile::tempdir;
+    use tempfile::{tempdir, TempDir};
 
     /// Create a test database service with temporary database
-    fn create_test_db() -> AppResult<DatabaseService> {
+    async fn create_test_db() -> AppResult<(TempDir, DatabaseService)> {
         let temp_dir = tempdir().expect("Failed to create temp dir");
         let db_path = temp_dir.path().join("test.db");
         
-        let service = DatabaseService::new()?;
+        let service = DatabaseService::new_test(db_path.to_str().unwrap()).await?;
         
-        service.initialize_schema()?;
-        service.ensure_default_user()?;
+        service.initialize_schema().await?;
+        service.ensure_default_user().await?;
         
-        Ok(service)
+        Ok((temp_dir, service))
     }
 
-    #[test]
-    fn test_database_initialization() {
-        let db = create_test_db().expect("Failed to create test database");
+    #[tokio::test]
+    async fn test_database_initialization() {
+        let (_temp_dir, db) = create_test_db().await.expect("Failed to create test database");
         
         // Test that default user was created
-        let user_id = db.get_default_user_id().expect("Failed to get default user");
+        let user_id = db.get_default_user_id().await.expect("Failed to get default user");
         assert!(!user_id.is_empty());
     }
 } 
\ No newline at end of file
@@ -0,0 +1,112 @@
+use crate::models::UpdateNodeRequest;
+use crate::services::database::connection::DatabaseService;
+use crate::services::LinkService;
+use crate::utils::DateLocale;
+use super::{create_node, create_node_with_properties, setup};
+use chrono::NaiveDate;
+use std::collections::HashMap;
+
+// Saturday
+fn today() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, 10, 17).unwrap()
+}
+
+async fn node_dates(db: &DatabaseService, node_id: &str) -> Vec<(String, String, String)> {
+    sqlx::query_as("SELECT key, date, source FROM node_dates WHERE node_id = ? ORDER BY key")
+        .bind(node_id)
+        .fetch_all(db.pool())
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_date_references_point_at_journal_pages() {
+    let (_temp_dir, db) = setup().await;
+
+    let node = create_node(&db, "Call Sam [[next friday]]", None, 0, &[]).await;
+    let indexed = db.index_node_content_at(&node, today()).await.unwrap();
+    LinkService::new(db.clone()).update_links_for_node(&indexed).await.unwrap();
+
+    assert_eq!(indexed.content, "Call Sam [[Oct 23rd, 2026]]");
+    assert_eq!(indexed.version, node.version);
+
+    let journal = db.get_daily_note("2026-10-23").await.unwrap();
+    let backlinks: Vec<String> = sqlx::query_scalar("SELECT source_node_id FROM node_links WHERE target_node_id = ?")
+        .bind(&journal.id)
+        .fetch_all(db.pool())
+        .await
+        .unwrap();
+    assert_eq!(backlinks, vec![node.id]);
+}
+
+#[tokio::test]
+async fn test_page_links_are_not_read_as_dates() {
+    let (_temp_dir, db) = setup().await;
+
+    let monday = create_node(&db, "Monday", None, 0, &[]).await;
+    let mut properties = HashMap::new();
+    properties.insert("alias".to_string(), serde_json::json!("[[Next Friday]], Launch"));
+    create_node_with_properties(&db, "Release", None, 1, properties, &[]).await;
+
+    let node = create_node(&db, "Standup [[Monday]], chapter [[2.1]], ship [[next friday]]", None, 2, &[]).await;
+    let indexed = db.index_node_content_at(&node, today()).await.unwrap();
+    LinkService::new(db.clone()).update_links_for_node(&indexed).await.unwrap();
+
+    assert_eq!(indexed.content, node.content);
+    let journals: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM journals").fetch_one(db.pool()).await.unwrap();
+    assert_eq!(journals, 0);
+    let targets: Vec<String> = sqlx::query_scalar("SELECT target_node_id FROM node_links WHERE source_node_id = ?")
+        .bind(&node.id)
+        .fetch_all(db.pool())
+        .await
+        .unwrap();
+    assert!(targets.contains(&monday.id));
+}
+
+#[tokio::test]
+async fn test_date_properties_are_typed_and_indexed() {
+    let (_temp_dir, db) = setup().await;
+
+    let mut properties = HashMap::new();
+    properties.insert("start".to_string(), serde_json::json!("2026-10-01"));
+    let node = create_node_with_properties(&db, "Ship it\ndue:: tomorrow\nscheduled:: in 3 days", None, 0, properties, &[]).await;
+    let indexed = db.index_node_content_at(&node, today()).await.unwrap();
+
+    assert_eq!(indexed.content, "Ship it\ndue:: 2026-10-18\nscheduled:: 2026-10-20");
+    assert_eq!(indexed.properties.get("due"), Some(&serde_json::json!("2026-10-18")));
+    assert_eq!(indexed.properties.get("start"), Some(&serde_json::json!("2026-10-01")));
+    assert_eq!(
+        node_dates(&db, &node.id).await,
+        vec![
+            ("due".to_string(), "2026-10-18".to_string(), "content".to_string()),
+            ("scheduled".to_string(), "2026-10-20".to_string(), "content".to_string()),
+            ("start".to_string(), "2026-10-01".to_string(), "property".to_string()),
+        ]
+    );
+
+    // Dropping the line drops the property it produced, but not the user's own
+    let edited = db.update_node(&node.id, UpdateNodeRequest {
+        content: Some("Ship it\ndue:: 2026-10-18".to_string()),
+        parent_id: None,
+        order: None,
+        properties: None,
+        tags: None,
+    }).await.unwrap();
+    let reindexed = db.index_node_content_at(&edited, today()).await.unwrap();
+    assert!(!reindexed.properties.contains_key("scheduled"));
+    assert!(reindexed.properties.contains_key("start"));
+    assert_eq!(node_dates(&db, &node.id).await.len(), 2);
+}
+
+#[tokio::test]
+async fn test_date_locale_preference() {
+    let (_temp_dir, db) = setup().await;
+
+    assert_eq!(db.get_date_locale().await.unwrap(), DateLocale::EnUs);
+    db.set_date_locale(DateLocale::EnGb).await.unwrap();
+    assert_eq!(db.get_date_locale().await.unwrap(), DateLocale::EnGb);
+
+    let node = create_node(&db, "deadline:: 3/4/2027", None, 0, &[]).await;
+    let indexed = db.index_node_content_at(&node, today()).await.unwrap();
+    assert_eq!(indexed.content, "deadline:: 2027-04-03");
+}
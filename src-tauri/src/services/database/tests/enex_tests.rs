@@ -0,0 +1,75 @@
+use crate::models::NodeWithChildren;
+use super::setup;
+use std::fs;
+
+const TRAVEL: &str = include_str!("fixtures/Travel.enex");
+
+fn contents(tree: &NodeWithChildren) -> Vec<&str> {
+    tree.child_nodes.iter().map(|child| child.node.content.as_str()).collect()
+}
+
+#[tokio::test]
+async fn test_import_enex_notebook() {
+    let (temp_dir, db) = setup().await;
+    let path = temp_dir.path().join("Travel.enex");
+    fs::write(&path, TRAVEL).unwrap();
+
+    let summary = db.import_enex(&path, |_| {}).await.unwrap();
+    assert_eq!((summary.pages_created, summary.blocks_created), (1, 13));
+    assert!(summary.skipped.is_empty());
+
+    let notebook_id: String = sqlx::query_scalar("SELECT id FROM nodes WHERE parent_id IS NULL AND content = 'Travel'")
+        .fetch_one(db.pool())
+        .await
+        .unwrap();
+    let notebook = db.get_node_with_children(&notebook_id).await.unwrap();
+    assert_eq!(contents(&notebook), vec!["Lisbon trip", "Restaurants"]);
+
+    let trip = &notebook.child_nodes[0];
+    assert_eq!(trip.node.tags, vec!["travel", "portugal"]);
+    assert_eq!(trip.node.created_at.to_rfc3339(), "2026-09-01T08:15:00+00:00");
+    assert_eq!(trip.node.updated_at.to_rfc3339(), "2026-10-02T19:30:00+00:00");
+
+    let image = db.attachments_dir().join("old-town.png");
+    let budget = db.attachments_dir().join("budget.txt");
+    assert_eq!(contents(trip), vec![
+        "Flights booked for **October 20**.".to_string(),
+        "### Packing".to_string(),
+        "DONE Passport".to_string(),
+        "TODO Adapter".to_string(),
+        "Map of the old town:".to_string(),
+        format!("![old-town.png]({})", image.display()),
+        "More at [Visit Lisboa](https://www.visitlisboa.com)".to_string(),
+        format!("[budget.txt]({})", budget.display()),
+    ]);
+    assert_eq!(contents(&trip.child_nodes[3]), vec!["Type F"]);
+    assert_eq!(fs::read(&budget).unwrap(), b"Quarterly numbers\n");
+    assert!(fs::read(&image).unwrap().starts_with(b"\x89PNG"));
+
+    let restaurants = &notebook.child_nodes[1];
+    assert_eq!(contents(restaurants), vec!["Name | Area", "Taberna | Alfama"]);
+}
+
+#[tokio::test]
+async fn test_import_enex_skips_unreadable_notes() {
+    let (temp_dir, db) = setup().await;
+    let path = temp_dir.path().join("Broken.enex");
+    let svg = "<resource><data encoding=\"base64\">PHN2Zy8+</data><mime>image/svg+xml</mime></resource>";
+    fs::write(&path, format!("<en-export><note><title>Bad</title><content>&lt;en-note&gt;&lt;div&gt;&amp;bogus;&lt;/div&gt;&lt;/en-note&gt;</content>{0}</note>\
+        <note><title>Good</title><content>&lt;en-note&gt;Fine&lt;/en-note&gt;</content>{0}</note></en-export>", svg)).unwrap();
+
+    let summary = db.import_enex(&path, |_| {}).await.unwrap();
+    assert_eq!(summary.skipped.len(), 1);
+    assert!(summary.skipped[0].starts_with("Bad: "));
+    assert_eq!(summary.blocks_created, 3);
+
+    // Only the readable note's file is stored, named for its type
+    let stored: Vec<String> = fs::read_dir(db.attachments_dir())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    assert_eq!(stored, vec!["677433a0892aaed7b7d2628c313c9775.svg"]);
+
+    fs::write(&path, "<en-export><note>").unwrap();
+    assert!(db.import_enex(&path, |_| {}).await.is_err());
+}
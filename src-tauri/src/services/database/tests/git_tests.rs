@@ -0,0 +1,296 @@
+use crate::errors::AppError;
+use crate::models::{BlockBlame, GitCommit, GitSettings, Node, UpdateNodeRequest};
+use crate::services::database::connection::DatabaseService;
+use crate::services::GitManager;
+use crate::utils::parse_markdown_page;
+use super::create_node;
+use git2::Repository;
+use serde_json::json;
+use std::collections::HashMap;
+use std::fs;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+async fn setup() -> (TempDir, DatabaseService, GitManager) {
+    let (temp_dir, db) = super::setup().await;
+    let git = GitManager::new(db.clone());
+    (temp_dir, db, git)
+}
+
+async fn create(db: &DatabaseService, content: &str, parent: Option<&Node>, order: i32) -> Node {
+    let node = create_node(db, content, parent.map(|parent| parent.id.as_str()), order, &[]).await;
+    db.index_node_content(&node).await.unwrap()
+}
+
+async fn set_content(db: &DatabaseService, node: &Node, content: &str) {
+    db.update_node(&node.id, UpdateNodeRequest {
+        content: Some(content.to_string()),
+        parent_id: None,
+        order: None,
+        properties: None,
+        tags: None,
+    }).await.unwrap();
+}
+
+fn history(repo: &Repository) -> Vec<String> {
+    let mut walk = repo.revwalk().unwrap();
+    walk.push_head().unwrap();
+    walk.map(|id| repo.find_commit(id.unwrap()).unwrap().message().unwrap().to_string()).collect()
+}
+
+#[tokio::test]
+async fn test_mirror_pages_read_back() {
+    let (_temp_dir, db, _git) = setup().await;
+    let page = create(&db, "Alpha", None, 0).await;
+    let first = create(&db, "TODO Plan\nsecond line", Some(&page), 0).await;
+    let child = create(&db, "Step", Some(&first), 0).await;
+    let second = create(&db, "", Some(&page), 1).await;
+    db.update_node(&second.id, UpdateNodeRequest {
+        content: None,
+        parent_id: None,
+        order: None,
+        properties: Some(HashMap::from([("status".to_string(), json!("draft"))])),
+        tags: Some(vec!["later".to_string()]),
+    }).await.unwrap();
+
+    let pages = db.render_mirror_pages().await.unwrap();
+    assert_eq!(pages.len(), 1);
+    assert_eq!(pages[0].path, format!("pages/{}.md", page.id));
+    assert_eq!(pages[0].title, "Alpha");
+    assert_eq!(
+        pages[0].contents,
+        format!(
+            "title:: Alpha\nid:: {}\n\n- TODO Plan\n  second line\n  id:: {}\n\t- Step\n\t  id:: {}\n-\n  status:: draft\n  tags:: later\n  id:: {}\n",
+            page.id, first.id, child.id, second.id
+        )
+    );
+    // Rendering is stable
+    assert_eq!(db.render_mirror_pages().await.unwrap(), pages);
+
+    let parsed = parse_markdown_page("ignored", &pages[0].contents);
+    assert_eq!(parsed.title, "Alpha");
+    assert_eq!(parsed.id.as_deref(), Some(page.id.as_str()));
+    assert_eq!(parsed.blocks[0].id.as_deref(), Some(first.id.as_str()));
+    assert_eq!(parsed.blocks[0].content, "TODO Plan\nsecond line");
+    assert_eq!(parsed.blocks[0].children[0].id.as_deref(), Some(child.id.as_str()));
+    assert_eq!(parsed.blocks[1].tags, vec!["later"]);
+}
+
+#[tokio::test]
+async fn test_commit_mirror() {
+    let (temp_dir, db, git) = setup().await;
+    let dir = temp_dir.path().join("mirror");
+    git.set_settings(&GitSettings {
+        folder: Some(dir.to_string_lossy().into_owned()),
+        ..Default::default()
+    }).await.unwrap();
+
+    // Nothing to commit in an empty graph
+    assert!(git.commit_mirror().await.unwrap().is_none());
+
+    let alpha = create(&db, "Alpha", None, 0).await;
+    let block = create(&db, "First draft", Some(&alpha), 0).await;
+    let commit = git.commit_mirror().await.unwrap().unwrap();
+    assert_eq!(commit.message, "Add Alpha");
+    assert_eq!(commit.author, "Note");
+    assert!(git.commit_mirror().await.unwrap().is_none());
+
+    fs::write(dir.join("README.md"), "My notes").unwrap();
+    set_content(&db, &block, "Second draft").await;
+    assert_eq!(git.commit_mirror().await.unwrap().unwrap().message, "Update Alpha");
+    let page_file = fs::read_to_string(dir.join(format!("pages/{}.md", alpha.id))).unwrap();
+    assert!(page_file.contains("- Second draft\n"));
+
+    set_content(&db, &alpha, "Alpha renamed").await;
+    let beta = create(&db, "Beta", None, 1).await;
+    let gamma = create(&db, "Gamma", None, 2).await;
+    assert_eq!(
+        git.commit_mirror().await.unwrap().unwrap().message,
+        "Update 3 pages\n\nAdd Beta\nAdd Gamma\nUpdate Alpha renamed"
+    );
+
+    db.delete_node(&beta.id).await.unwrap();
+    db.delete_node(&gamma.id).await.unwrap();
+    assert_eq!(git.commit_mirror().await.unwrap().unwrap().message, "Delete 2 pages\n\nDelete Beta\nDelete Gamma");
+    assert!(!dir.join(format!("pages/{}.md", beta.id)).exists());
+
+    let repo = Repository::open(&dir).unwrap();
+    assert_eq!(history(&repo).len(), 4);
+    // Files of your own are neither removed nor committed
+    assert!(dir.join("README.md").exists());
+    assert!(repo.head().unwrap().peel_to_tree().unwrap().get_name("README.md").is_none());
+}
+
+#[tokio::test]
+async fn test_debounced_commits() {
+    let (temp_dir, db, git) = setup().await;
+    assert_eq!(git.repository_dir(&GitSettings::default()), temp_dir.path().join("repository"));
+    assert!(git.set_settings(&GitSettings { author_name: " ".to_string(), ..Default::default() }).await.is_err());
+    git.set_settings(&GitSettings { debounce_seconds: 30, ..Default::default() }).await.unwrap();
+
+    let start = Instant::now();
+    // Nothing recorded yet
+    assert!(git.commit_if_due(start + Duration::from_secs(60)).await.unwrap().is_none());
+
+    create(&db, "Alpha", None, 0).await;
+    git.record_change();
+    assert!(git.commit_if_due(Instant::now() + Duration::from_secs(10)).await.unwrap().is_none());
+    let commit = git.commit_if_due(Instant::now() + Duration::from_secs(31)).await.unwrap();
+    assert_eq!(commit.unwrap().message, "Add Alpha");
+    assert!(git.commit_if_due(Instant::now() + Duration::from_secs(60)).await.unwrap().is_none());
+
+    // Automatic commits can be switched off
+    git.set_settings(&GitSettings { enabled: false, ..Default::default() }).await.unwrap();
+    create(&db, "Beta", None, 1).await;
+    git.record_change();
+    assert!(git.commit_if_due(Instant::now() + Duration::from_secs(60)).await.unwrap().is_none());
+    let repo = Repository::open(temp_dir.path().join("repository")).unwrap();
+    assert_eq!(history(&repo), vec!["Add Alpha"]);
+}
+
+async fn mirror_setup() -> (TempDir, DatabaseService, GitManager) {
+    let (temp_dir, db, git) = setup().await;
+    git.set_settings(&GitSettings {
+        folder: Some(temp_dir.path().join("mirror").to_string_lossy().into_owned()),
+        ..Default::default()
+    }).await.unwrap();
+    (temp_dir, db, git)
+}
+
+fn blamed(blames: &[BlockBlame]) -> Vec<(String, String)> {
+    blames.iter().map(|blame| (blame.node_id.clone(), blame.commit.id.clone())).collect()
+}
+
+#[tokio::test]
+async fn test_page_history_and_blame() {
+    let (_temp_dir, db, git) = mirror_setup().await;
+    let alpha = create(&db, "Alpha", None, 0).await;
+    let one = create(&db, "One", Some(&alpha), 0).await;
+    let first = git.commit_mirror().await.unwrap().unwrap();
+    let two = create(&db, "Two\nwith a second line", Some(&alpha), 1).await;
+    let second = git.commit_mirror().await.unwrap().unwrap();
+    let beta = create(&db, "Beta", None, 1).await;
+    let third = git.commit_mirror().await.unwrap().unwrap();
+    set_content(&db, &one, "One, edited").await;
+    let fourth = git.commit_mirror().await.unwrap().unwrap();
+
+    let ids = |commits: Vec<GitCommit>| commits.into_iter().map(|commit| commit.id).collect::<Vec<_>>();
+    assert_eq!(ids(git.page_history(&alpha.id).await.unwrap()), vec![fourth.id.clone(), second.id.clone(), first.id.clone()]);
+    assert_eq!(ids(git.page_history(&beta.id).await.unwrap()), vec![third.id.clone()]);
+    assert!(git.page_history("missing").await.unwrap().is_empty());
+
+    let blames = git.blame_page(&alpha.id).await.unwrap();
+    assert_eq!(
+        blamed(&blames),
+        vec![(alpha.id.clone(), first.id.clone()), (one.id.clone(), fourth.id.clone()), (two.id.clone(), second.id.clone())]
+    );
+    assert_eq!(blames[1].commit.message, "Update Alpha");
+    assert!(git.blame_page("missing").await.is_err());
+    assert!(matches!(git.blame_page("../test").await, Err(AppError::InvalidPageData(_))));
+}
+
+#[tokio::test]
+async fn test_restore_page_from_commit() {
+    let (temp_dir, db, git) = mirror_setup().await;
+    let alpha = create(&db, "Alpha", None, 0).await;
+    let draft = create(&db, "TODO Draft", Some(&alpha), 0).await;
+    let keep = create(&db, "Keep", Some(&alpha), 1).await;
+    let detail = create(&db, "Detail", Some(&keep), 0).await;
+    db.update_node(&keep.id, UpdateNodeRequest {
+        content: None,
+        parent_id: None,
+        order: None,
+        properties: Some(HashMap::from([("estimate".to_string(), json!(3))])),
+        tags: None,
+    }).await.unwrap();
+    let beta = create(&db, "Beta", None, 1).await;
+    let original = git.commit_mirror().await.unwrap().unwrap();
+
+    set_content(&db, &draft, "DONE Final").await;
+    db.delete_node(&detail.id).await.unwrap();
+    let added = create(&db, "Added later", Some(&alpha), 2).await;
+    db.delete_node(&beta.id).await.unwrap();
+    git.commit_mirror().await.unwrap().unwrap();
+
+    let page = git.restore_page(&alpha.id, &original.id[..8]).await.unwrap();
+    assert_eq!(page.content, "Alpha");
+    let tree = db.get_node_with_children(&alpha.id).await.unwrap();
+    let children: Vec<&str> = tree.child_nodes.iter().map(|child| child.node.id.as_str()).collect();
+    assert_eq!(children, vec![draft.id.as_str(), keep.id.as_str()]);
+    assert_eq!(tree.child_nodes[0].node.content, "TODO Draft");
+    assert_eq!(tree.child_nodes[1].node.properties.get("estimate"), Some(&json!(3)));
+    assert_eq!(tree.child_nodes[1].child_nodes[0].node.id, detail.id);
+    assert!(db.get_node(&added.id).await.is_err());
+    let todo: Vec<String> = sqlx::query_scalar("SELECT node_id FROM tasks WHERE state = 'TODO'")
+        .fetch_all(db.pool())
+        .await
+        .unwrap();
+    assert_eq!(todo, vec![draft.id.clone()]);
+
+    // The restore is committed like an edit, and the page reads as it did
+    let commit = git.commit_if_due(Instant::now() + Duration::from_secs(31)).await.unwrap().unwrap();
+    assert_eq!(commit.message, "Update Alpha");
+    let repo = Repository::open(temp_dir.path().join("mirror")).unwrap();
+    let path = std::path::Path::new("pages").join(format!("{}.md", alpha.id));
+    let blob = |commit: &str| repo.find_commit(git2::Oid::from_str(commit).unwrap()).unwrap().tree().unwrap().get_path(&path).unwrap().id();
+    assert_eq!(blob(&commit.id), blob(&original.id));
+
+    // Deleted pages come back under their ID
+    let restored = git.restore_page(&beta.id, &original.id).await.unwrap();
+    assert_eq!((restored.id, restored.content), (beta.id.clone(), "Beta".to_string()));
+
+    assert!(git.restore_page(&alpha.id, "0000000").await.is_err());
+    assert!(git.restore_page("missing", &original.id).await.is_err());
+}
+
+#[tokio::test]
+async fn test_restore_keeps_whole_root_content() {
+    let (_temp_dir, db, git) = mirror_setup().await;
+    let content = "Alpha\n  indented: \"quoted\"\n- not a block\n\ntitle:: not a property";
+    let alpha = create(&db, content, None, 0).await;
+    create(&db, "One", Some(&alpha), 0).await;
+    let empty = create(&db, "", None, 1).await;
+    let original = git.commit_mirror().await.unwrap().unwrap();
+
+    let pages = db.render_mirror_pages().await.unwrap();
+    assert_eq!(pages[0].title, "Alpha");
+    assert!(pages[0].contents.starts_with("title:: Alpha\npage-content:: \"Alpha\\n"));
+
+    set_content(&db, &alpha, "Alpha").await;
+    set_content(&db, &empty, "Filled").await;
+    git.commit_mirror().await.unwrap().unwrap();
+
+    let page = git.restore_page(&alpha.id, &original.id).await.unwrap();
+    assert_eq!(page.content, content);
+    assert!(page.properties.is_empty());
+    let page = git.restore_page(&empty.id, &original.id).await.unwrap();
+    assert_eq!(page.content, "");
+}
+
+#[tokio::test]
+async fn test_restore_keeps_blocks_when_indexing_fails() {
+    let (_temp_dir, db, git) = mirror_setup().await;
+    let alpha = create(&db, "Alpha", None, 0).await;
+    let see = create(&db, "See [[Beta]]", Some(&alpha), 0).await;
+    let beta = create(&db, "Beta", None, 1).await;
+    let original = git.commit_mirror().await.unwrap().unwrap();
+    set_content(&db, &see, "Changed").await;
+    git.commit_mirror().await.unwrap().unwrap();
+
+    sqlx::query("CREATE TRIGGER no_links BEFORE INSERT ON node_links BEGIN SELECT RAISE(ABORT, 'no links'); END")
+        .execute(db.pool())
+        .await
+        .unwrap();
+    assert!(git.restore_page(&alpha.id, &original.id).await.is_err());
+    assert_eq!(db.get_node(&see.id).await.unwrap().content, "See [[Beta]]");
+
+    // Restoring again rebuilds what the failed attempt left out
+    sqlx::query("DROP TRIGGER no_links").execute(db.pool()).await.unwrap();
+    git.restore_page(&alpha.id, &original.id).await.unwrap();
+    let targets: Vec<String> = sqlx::query_scalar("SELECT target_node_id FROM node_links WHERE source_node_id = ?")
+        .bind(&see.id)
+        .fetch_all(db.pool())
+        .await
+        .unwrap();
+    assert_eq!(targets, vec![beta.id]);
+}
@@ -0,0 +1,104 @@
+use crate::errors::AppError;
+use crate::models::UpdateNodeRequest;
+use crate::services::database::connection::DatabaseService;
+use super::{create_node, setup};
+
+/// Write `content` as a block in the journal page of `date`
+async fn log(db: &DatabaseService, date: &str, content: &str) {
+    let page = db.get_or_create_daily_note(date).await.unwrap();
+    create_node(db, content, Some(&page.id), 0, &[]).await;
+}
+
+#[tokio::test]
+async fn test_habits_are_declared_by_key() {
+    let (_temp_dir, db) = setup().await;
+
+    let habit = db.add_habit("Exercise", None).await.unwrap();
+    assert_eq!((habit.key.as_str(), habit.name.as_str()), ("exercise", "exercise"));
+
+    let renamed = db.add_habit("exercise", Some("Workout")).await.unwrap();
+    assert_eq!(renamed.name, "Workout");
+    assert_eq!(renamed.created_at, habit.created_at);
+    db.add_habit("reading", None).await.unwrap();
+
+    let keys: Vec<String> = db.list_habits().await.unwrap().into_iter().map(|habit| habit.key).collect();
+    assert_eq!(keys, vec!["exercise", "reading"]);
+
+    assert!(db.add_habit("not a key", None).await.is_err());
+    db.remove_habit("reading").await.unwrap();
+    assert!(matches!(db.remove_habit("reading").await, Err(AppError::NotFound(_))));
+}
+
+#[tokio::test]
+async fn test_habit_report_streaks_and_rates() {
+    let (_temp_dir, db) = setup().await;
+    db.add_habit("exercise", None).await.unwrap();
+    db.add_habit("reading", None).await.unwrap();
+
+    log(&db, "2026-10-10", "exercise:: true").await;
+    log(&db, "2026-10-11", "Morning run\nexercise:: yes\nreading:: 0").await;
+    log(&db, "2026-10-12", "exercise:: no").await;
+    log(&db, "2026-10-13", "exercise:: done").await;
+    log(&db, "2026-10-14", "Exercise:: 1").await;
+    log(&db, "2026-10-14", "reading:: 20").await;
+    // Outside the range
+    log(&db, "2026-10-16", "exercise:: true").await;
+
+    let report = db.get_habit_report("2026-10-10", "2026-10-15").await.unwrap();
+    assert_eq!(report.dates.len(), 6);
+
+    let exercise = &report.habits[0];
+    assert_eq!(exercise.habit.key, "exercise");
+    assert_eq!(exercise.days, vec![Some(true), Some(true), Some(false), Some(true), Some(true), None]);
+    assert_eq!(exercise.completed, 4);
+    assert!((exercise.completion_rate - 4.0 / 6.0).abs() < 1e-9);
+    assert_eq!(exercise.longest_streak, 2);
+    assert_eq!(exercise.current_streak, 2);
+
+    let reading = &report.habits[1];
+    assert_eq!(reading.days, vec![None, Some(false), None, None, Some(true), None]);
+    assert_eq!((reading.completed, reading.current_streak, reading.longest_streak), (1, 1, 1));
+
+    assert!(matches!(
+        db.get_habit_report("2026-10-15", "2026-10-10").await,
+        Err(AppError::InvalidDate(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_habit_report_window_is_limited() {
+    let (_temp_dir, db) = setup().await;
+    db.add_habit("exercise", None).await.unwrap();
+
+    let year = db.get_habit_report("2025-10-17", "2026-10-18").await.unwrap();
+    assert_eq!(year.habits[0].days.len(), 367);
+    assert!(matches!(
+        db.get_habit_report("2025-10-17", "2026-10-19").await,
+        Err(AppError::InvalidDate(_))
+    ));
+    assert!(matches!(
+        db.get_habit_report("0001-01-01", "9999-12-31").await,
+        Err(AppError::InvalidDate(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_habit_report_reads_page_properties() {
+    let (_temp_dir, db) = setup().await;
+    db.add_habit("meditate", None).await.unwrap();
+
+    let page = db.get_or_create_daily_note("2026-10-17").await.unwrap();
+    let mut properties = page.properties.clone();
+    properties.insert("meditate".to_string(), serde_json::Value::Bool(true));
+    db.update_node(&page.id, UpdateNodeRequest {
+        content: None,
+        parent_id: None,
+        order: None,
+        properties: Some(properties),
+        tags: None,
+    }).await.unwrap();
+
+    let report = db.get_habit_report("2026-10-17", "2026-10-17").await.unwrap();
+    assert_eq!(report.habits[0].days, vec![Some(true)]);
+    assert_eq!(report.habits[0].completion_rate, 1.0);
+}
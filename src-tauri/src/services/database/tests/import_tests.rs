@@ -0,0 +1,110 @@
+use crate::models::{ImportProgress, NodeWithChildren, TaskState};
+use crate::services::database::connection::DatabaseService;
+use super::setup;
+use std::fs;
+use std::path::Path;
+
+fn write(vault: &Path, file: &str, text: &str) {
+    let path = vault.join(file);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, text).unwrap();
+}
+
+fn contents(tree: &NodeWithChildren) -> Vec<&str> {
+    tree.child_nodes.iter().map(|child| child.node.content.as_str()).collect()
+}
+
+async fn page_id(db: &DatabaseService, title: &str) -> String {
+    sqlx::query_scalar("SELECT id FROM nodes WHERE parent_id IS NULL AND content = ?")
+        .bind(title)
+        .fetch_one(db.pool())
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_import_logseq_vault() {
+    let (temp_dir, db) = setup().await;
+    let vault = temp_dir.path().join("vault");
+    write(&vault, "pages/Project Alpha.md", "alias:: Alpha\ntags:: work\n\n- TODO Ship it #release\n\t- See [[Beta]] and [[Missing]]\n- Notes");
+    write(&vault, "pages/Beta.md", "- Back to [[alpha]]");
+    write(&vault, "journals/2026_10_17.md", "- Worked on [[Project Alpha]]\n- exercise:: true");
+    write(&vault, "logseq/bak/pages/Beta.md", "- stale backup");
+    write(&vault, ".obsidian/notes.md", "- settings");
+    write(&vault, "pages/readme.txt", "not markdown");
+
+    let mut progress: Vec<ImportProgress> = Vec::new();
+    let summary = db.import_markdown_dir(&vault, |event| progress.push(event)).await.unwrap();
+
+    assert_eq!(summary.pages_created, 3);
+    assert_eq!(summary.pages_merged, 0);
+    assert_eq!(summary.blocks_created, 6);
+    assert_eq!(summary.links_created, 3);
+    assert_eq!(summary.unresolved_links, vec!["Missing"]);
+    assert!(summary.skipped.is_empty());
+    assert_eq!(progress.len(), 4);
+    assert_eq!((progress[3].processed, progress[3].total), (3, 3));
+
+    let alpha = db.get_node_with_children(&page_id(&db, "Project Alpha").await).await.unwrap();
+    assert_eq!(alpha.node.tags, vec!["work"]);
+    assert_eq!(contents(&alpha), vec!["TODO Ship it #release", "Notes"]);
+    let task = &alpha.child_nodes[0];
+    assert_eq!(task.node.tags, vec!["release"]);
+    assert_eq!(db.get_task_state(&task.node.id).await.unwrap(), Some(TaskState::Todo));
+    assert_eq!(contents(task), vec!["See [[Beta]] and [[Missing]]"]);
+
+    // `[[alpha]]` resolves through the page alias
+    let backlinks: Vec<String> = sqlx::query_scalar(
+        "SELECT n.content FROM node_links l JOIN nodes n ON n.id = l.source_node_id WHERE l.target_node_id = ? ORDER BY n.content"
+    )
+    .bind(&alpha.node.id)
+    .fetch_all(db.pool())
+    .await
+    .unwrap();
+    assert_eq!(backlinks, vec!["Back to [[alpha]]", "Worked on [[Project Alpha]]"]);
+
+    let journal = db.get_daily_note("2026-10-17").await.unwrap();
+    let journal = db.get_node_with_children(&journal.id).await.unwrap();
+    assert_eq!(contents(&journal), vec!["Worked on [[Project Alpha]]", "exercise:: true"]);
+}
+
+#[tokio::test]
+async fn test_import_copies_vault_images() {
+    let (temp_dir, db) = setup().await;
+    let vault = temp_dir.path().join("vault");
+    write(&vault, "assets/chart one.png", "png");
+    write(&vault, "pages/Alpha.md", "- ![chart](<../assets/chart one.png>)");
+    write(&vault, "pages/Beta.md", "- ![chart](<../assets/chart one.png>) ![gone](../assets/missing.png)");
+
+    db.import_markdown_dir(&vault, |_| {}).await.unwrap();
+    fs::remove_dir_all(vault.join("assets")).unwrap();
+
+    let stored = db.attachments_dir().join("chart one.png");
+    assert_eq!(fs::read(&stored).unwrap(), b"png");
+    assert_eq!(fs::read_dir(db.attachments_dir()).unwrap().count(), 1);
+    let beta = db.get_node_with_children(&page_id(&db, "Beta").await).await.unwrap();
+    assert_eq!(
+        contents(&beta),
+        vec![format!("![chart](<{}>) ![gone](../assets/missing.png)", stored.display())]
+    );
+}
+
+#[tokio::test]
+async fn test_import_merges_into_existing_pages() {
+    let (temp_dir, db) = setup().await;
+    let vault = temp_dir.path().join("vault");
+    write(&vault, "Beta.md", "---\ntags: [reading]\n---\nFirst paragraph");
+    write(&vault, "2026-10-17.md", "Evening notes");
+
+    db.get_or_create_daily_note("2026-10-17").await.unwrap();
+    db.import_markdown_dir(&vault, |_| {}).await.unwrap();
+    let summary = db.import_markdown_dir(&vault, |_| {}).await.unwrap();
+    assert_eq!((summary.pages_created, summary.pages_merged), (0, 2));
+
+    let beta = db.get_node_with_children(&page_id(&db, "Beta").await).await.unwrap();
+    assert_eq!(beta.node.tags, vec!["reading"]);
+    assert_eq!(contents(&beta), vec!["First paragraph", "First paragraph"]);
+    assert_eq!(beta.child_nodes[1].node.order, 1);
+
+    assert!(db.import_markdown_dir(&vault.join("nope"), |_| {}).await.is_err());
+}
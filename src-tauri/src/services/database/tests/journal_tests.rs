@@ -0,0 +1,123 @@
+use crate::errors::AppError;
+use super::setup;
+
+#[tokio::test]
+async fn test_get_or_create_daily_note_is_keyed_by_date() {
+    let (_temp_dir, db) = setup().await;
+
+    let first = db.get_or_create_daily_note("2026-10-17").await.unwrap();
+    let second = db.get_or_create_daily_note("2026-10-17").await.unwrap();
+
+    assert_eq!(first.id, second.id);
+    assert_eq!(first.content, "Oct 17th, 2026");
+    assert_eq!(first.properties["date"], serde_json::json!("2026-10-17"));
+    assert_eq!(db.get_daily_note("2026-10-17").await.unwrap().id, first.id);
+}
+
+#[tokio::test]
+async fn test_daily_note_rejects_invalid_dates() {
+    let (_temp_dir, db) = setup().await;
+
+    for date in ["2026-13-01", "2026-2-3", "yesterday", "2026-10-17%"] {
+        let result = db.get_or_create_daily_note(date).await;
+        assert!(matches!(result, Err(AppError::InvalidDate(_))), "{} should be rejected", date);
+    }
+}
+
+#[tokio::test]
+async fn test_get_daily_note_missing() {
+    let (_temp_dir, db) = setup().await;
+
+    let result = db.get_daily_note("2026-10-17").await;
+    assert!(matches!(result, Err(AppError::NotFound(_))));
+}
+
+#[tokio::test]
+async fn test_concurrent_creation_yields_one_page() {
+    let (_temp_dir, db) = setup().await;
+
+    let tasks: Vec<_> = (0..8)
+        .map(|_| {
+            let db = db.clone();
+            tokio::spawn(async move { db.get_or_create_daily_note("2026-10-17").await })
+        })
+        .collect();
+
+    let mut ids = Vec::new();
+    for task in tasks {
+        ids.push(task.await.unwrap().unwrap().id);
+    }
+    ids.dedup();
+    assert_eq!(ids.len(), 1);
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM nodes")
+        .fetch_one(db.pool())
+        .await
+        .unwrap();
+    assert_eq!(count, 1);
+}
+
+#[tokio::test]
+async fn test_custom_title_format() {
+    let (_temp_dir, db) = setup().await;
+
+    assert!(db.set_journal_title_format("%Q").await.is_err());
+    db.set_journal_title_format("%A, %B %o %Y").await.unwrap();
+
+    let node = db.get_or_create_daily_note("2026-10-01").await.unwrap();
+    assert_eq!(node.content, "Thursday, October 1st 2026");
+    assert_eq!(db.get_journal_title_format().await.unwrap(), "%A, %B %o %Y");
+}
+
+#[tokio::test]
+async fn test_list_journals_paginates_newest_first() {
+    let (_temp_dir, db) = setup().await;
+
+    for day in 1..=5 {
+        db.get_or_create_daily_note(&format!("2026-10-0{}", day)).await.unwrap();
+    }
+
+    let page = db.list_journals(None, None, 2, 0).await.unwrap();
+    let dates: Vec<String> = page.journals.iter().map(|j| j.date.to_string()).collect();
+    assert_eq!(dates, vec!["2026-10-05", "2026-10-04"]);
+    assert_eq!(page.total, 5);
+    assert!(page.has_more);
+
+    let last = db.list_journals(None, None, 2, 4).await.unwrap();
+    assert_eq!(last.journals.len(), 1);
+    assert!(!last.has_more);
+
+    let ranged = db.list_journals(Some("2026-10-02"), Some("2026-10-03"), 10, 0).await.unwrap();
+    let dates: Vec<String> = ranged.journals.iter().map(|j| j.date.to_string()).collect();
+    assert_eq!(dates, vec!["2026-10-03", "2026-10-02"]);
+    assert!(!ranged.has_more);
+}
+
+#[tokio::test]
+async fn test_legacy_daily_notes_are_adopted() {
+    let (_temp_dir, db) = setup().await;
+
+    sqlx::query(
+        "INSERT INTO nodes (id, content, order_index, properties, tags, created_by)
+         VALUES ('legacy', 'Daily Note - 2025-01-02', 0, '{\"type\":\"daily_note\",\"date\":\"2025-01-02\"}', '[]', 'default_user')"
+    )
+    .execute(db.pool())
+    .await
+    .unwrap();
+    db.init_database().await.unwrap();
+
+    let node = db.get_or_create_daily_note("2025-01-02").await.unwrap();
+    assert_eq!(node.id, "legacy");
+}
+
+#[tokio::test]
+async fn test_deleting_page_frees_the_date() {
+    let (_temp_dir, db) = setup().await;
+
+    let node = db.get_or_create_daily_note("2026-10-17").await.unwrap();
+    db.delete_node(&node.id).await.unwrap();
+
+    assert!(db.get_daily_note("2026-10-17").await.is_err());
+    let recreated = db.get_or_create_daily_note("2026-10-17").await.unwrap();
+    assert_ne!(recreated.id, node.id);
+}
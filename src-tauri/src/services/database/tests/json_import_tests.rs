@@ -0,0 +1,156 @@
+use crate::models::{Node, UpdateNodeRequest};
+use crate::services::database::connection::DatabaseService;
+use crate::services::database::export::JsonImportMode;
+use super::{create_node, setup};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+type Edit = Box<dyn Fn(&mut Value)>;
+
+async fn rename(db: &DatabaseService, node_id: &str, content: &str) {
+    db.update_node(node_id, UpdateNodeRequest {
+        content: Some(content.to_string()),
+        parent_id: None,
+        order: None,
+        properties: None,
+        tags: None,
+    }).await.unwrap();
+}
+
+async fn node_count(db: &DatabaseService) -> i64 {
+    sqlx::query_scalar("SELECT COUNT(*) FROM nodes").fetch_one(db.pool()).await.unwrap()
+}
+
+/// A page with a child that references it and links to it, exported to JSON
+async fn export_graph(db: &DatabaseService, dir: &Path) -> (Node, Node, PathBuf) {
+    let page = create_node(db, "Garden plan", None, 0, &[]).await;
+    let child = create_node(db, &format!("Tomatoes, see (({}))", page.id), Some(&page.id), 0, &[]).await;
+    sqlx::query("INSERT INTO node_links (source_node_id, target_node_id) VALUES (?, ?)")
+        .bind(&child.id)
+        .bind(&page.id)
+        .execute(db.pool())
+        .await
+        .unwrap();
+    let path = dir.join("export.json");
+    db.export_to_json(&path).await.unwrap();
+    (page, child, path)
+}
+
+fn edit_export(path: &Path, edit: impl FnOnce(&mut Value)) {
+    let mut data: Value = serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+    edit(&mut data);
+    fs::write(path, data.to_string()).unwrap();
+}
+
+#[tokio::test]
+async fn test_json_import_as_copy_remaps_ids() {
+    let (temp_dir, db) = setup().await;
+    let (page, child, path) = export_graph(&db, temp_dir.path()).await;
+
+    let report = db.import_from_json(&path, JsonImportMode::Copy, false).await.unwrap();
+    assert_eq!(report.created.len(), 2);
+    assert!(report.updated.is_empty() && report.unchanged.is_empty());
+    assert_eq!((report.links_created, report.links_skipped), (1, 0));
+    assert!(!report.created.contains(&page.id) && !report.created.contains(&child.id));
+
+    // The originals are untouched and the copy points at itself
+    assert_eq!(db.get_node(&child.id).await.unwrap().content, child.content);
+    let copies = db.search_nodes("Tomatoes", 10).await.unwrap();
+    assert_eq!(copies.len(), 2);
+    let copy = copies.iter().find(|node| node.id != child.id).unwrap();
+    let copied_page = copy.parent_id.clone().unwrap();
+    assert_ne!(copied_page, page.id);
+    assert_eq!(copy.content, format!("Tomatoes, see (({}))", copied_page));
+    let target: String = sqlx::query_scalar("SELECT target_node_id FROM node_links WHERE source_node_id = ?")
+        .bind(&copy.id)
+        .fetch_one(db.pool())
+        .await
+        .unwrap();
+    assert_eq!(target, copied_page);
+}
+
+#[tokio::test]
+async fn test_json_import_merge_and_replace() {
+    let (temp_dir, db) = setup().await;
+    let (page, child, path) = export_graph(&db, temp_dir.path()).await;
+    rename(&db, &child.id, "Tomatoes and basil").await;
+
+    // The local edit is newer than the export
+    let report = db.import_from_json(&path, JsonImportMode::MergeNewerWins, false).await.unwrap();
+    assert!(report.created.is_empty() && report.updated.is_empty());
+    assert_eq!(report.unchanged.len(), 2);
+    assert_eq!(db.get_node(&child.id).await.unwrap().content, "Tomatoes and basil");
+
+    // An edit made in the export after ours wins
+    edit_export(&path, |data| {
+        let node = data["nodes"].as_array_mut().unwrap().iter_mut().find(|node| node["id"] == child.id.as_str()).unwrap();
+        node["content"] = "Tomatoes and chillies".into();
+        node["updated_at"] = "2999-01-01T00:00:00Z".into();
+    });
+    let report = db.import_from_json(&path, JsonImportMode::MergeNewerWins, false).await.unwrap();
+    assert_eq!((report.updated.clone(), report.unchanged.clone()), (vec![child.id.clone()], vec![page.id.clone()]));
+    assert_eq!(db.search_nodes("chillies", 10).await.unwrap().len(), 1);
+
+    // Replacing overwrites the page without losing its children
+    rename(&db, &page.id, "Herb garden").await;
+    let report = db.import_from_json(&path, JsonImportMode::Replace, false).await.unwrap();
+    assert_eq!(report.updated.len(), 2);
+    assert!(!report.search_index_rebuilt);
+    assert_eq!(db.get_node(&page.id).await.unwrap().content, "Garden plan");
+    assert_eq!(db.get_node(&child.id).await.unwrap().parent_id.as_deref(), Some(page.id.as_str()));
+    assert!(db.search_nodes("Herb", 10).await.unwrap().is_empty());
+    assert_eq!(node_count(&db).await, 2);
+}
+
+#[tokio::test]
+async fn test_json_import_dry_run_changes_nothing() {
+    let (temp_dir, db) = setup().await;
+    let (_, _, path) = export_graph(&db, temp_dir.path()).await;
+    let (_target_dir, target) = setup().await;
+    create_node(&target, "Already here", None, 0, &[]).await;
+
+    let report = target.import_from_json(&path, JsonImportMode::Replace, true).await.unwrap();
+    assert!(report.dry_run);
+    assert_eq!((report.created.len(), report.links_created), (2, 1));
+    assert_eq!(node_count(&target).await, 1);
+
+    let report = target.import_from_json(&path, JsonImportMode::Replace, false).await.unwrap();
+    assert_eq!((report.created.len(), report.links_created), (2, 1));
+    assert_eq!(node_count(&target).await, 3);
+}
+
+#[tokio::test]
+async fn test_json_import_rejects_invalid_exports() {
+    let (temp_dir, db) = setup().await;
+    let (page, child, path) = export_graph(&db, temp_dir.path()).await;
+    let original = fs::read_to_string(&path).unwrap();
+    let (_target_dir, target) = setup().await;
+
+    let cases: Vec<(Edit, &str)> = vec![
+        (Box::new(|data| data["version"] = "999.0.0".into()), "newer than this app"),
+        (Box::new(|data| data["version"] = "latest".into()), "Unknown export version"),
+        (Box::new(|data| data["nodes"][1]["parent_id"] = "missing".into()), "unknown parent missing"),
+        (Box::new(|data| {
+            let copy = data["nodes"][0].clone();
+            data["nodes"].as_array_mut().unwrap().push(copy);
+        }), "more than once"),
+    ];
+    for (edit, message) in cases {
+        fs::write(&path, &original).unwrap();
+        edit_export(&path, edit);
+        let error = target.import_from_json(&path, JsonImportMode::Replace, true).await.unwrap_err();
+        assert!(error.to_string().contains(message), "{} should mention {:?}", error, message);
+    }
+
+    // Moving the page under its own child would loop
+    fs::write(&path, &original).unwrap();
+    edit_export(&path, |data| {
+        let node = data["nodes"].as_array_mut().unwrap().iter_mut().find(|node| node["id"] == page.id.as_str()).unwrap();
+        node["parent_id"] = child.id.as_str().into();
+    });
+    let error = db.import_from_json(&path, JsonImportMode::Replace, false).await.unwrap_err();
+    assert!(error.to_string().contains("its own ancestor"));
+    assert_eq!(node_count(&target).await, 0);
+    assert_eq!(db.get_node(&page.id).await.unwrap().parent_id, None);
+}
@@ -0,0 +1,98 @@
+use crate::models::{Node, NodeWithChildren};
+use crate::services::database::connection::DatabaseService;
+use crate::services::LinkService;
+use super::{create_node, setup};
+use std::collections::HashMap;
+use std::fs;
+use tempfile::{tempdir, TempDir};
+
+async fn create(db: &DatabaseService, content: &str, parent_id: Option<&str>, order: i32, tags: &[&str]) -> Node {
+    let node = create_node(db, content, parent_id, order, tags).await;
+    let node = db.index_node_content(&node).await.unwrap();
+    LinkService::new(db.clone()).update_links_for_node(&node).await.unwrap();
+    node
+}
+
+/// Pages as (title, outline) where the outline lists `depth content [tags]`
+async fn outline(db: &DatabaseService) -> Vec<(String, Vec<String>)> {
+    fn walk(tree: &NodeWithChildren, depth: usize, lines: &mut Vec<String>) {
+        for child in &tree.child_nodes {
+            lines.push(format!("{} {} {:?}", depth, child.node.content, child.node.tags));
+            walk(child, depth + 1, lines);
+        }
+    }
+
+    let mut pages = Vec::new();
+    for root in db.get_root_nodes().await.unwrap() {
+        let tree = db.get_node_with_children(&root.id).await.unwrap();
+        let mut lines = Vec::new();
+        walk(&tree, 0, &mut lines);
+        pages.push((root.content, lines));
+    }
+    pages.sort();
+    pages
+}
+
+/// Links as (source content, target ID); only linked-to nodes keep their IDs
+async fn links(db: &DatabaseService) -> Vec<(String, String)> {
+    sqlx::query_as(
+        "SELECT n.content, l.target_node_id FROM node_links l JOIN nodes n ON n.id = l.source_node_id ORDER BY 1, 2"
+    )
+    .fetch_all(db.pool())
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_markdown_export_round_trips() {
+    let temp_dir: TempDir = tempdir().unwrap();
+    let image = temp_dir.path().join("diagram.png");
+    fs::write(&image, b"png").unwrap();
+
+    let (_source_dir, source) = setup().await;
+    let alpha = create(&source, "Projects/Alpha: plan?", None, 0, &["work"]).await;
+    let goal = create(&source, "Goal block\nwith a second line\n\nand a gap", Some(&alpha.id), 0, &[]).await;
+    create(&source, "TODO Ship #release\nowner:: Sam", Some(&goal.id), 0, &["urgent"]).await;
+    create(&source, &format!("Sketch ![diagram]({})", image.display()), Some(&alpha.id), 1, &[]).await;
+    let beta = create(&source, "Beta", None, 1, &[]).await;
+    create(&source, "See [[Goal block]] and [[Projects/Alpha: plan?]]", Some(&beta.id), 0, &[]).await;
+    let journal = source.get_or_create_daily_note("2026-10-17").await.unwrap();
+    create(&source, "exercise:: true", Some(&journal.id), 0, &[]).await;
+
+    let export_dir = temp_dir.path().join("export");
+    let summary = source.export_markdown_dir(&export_dir).await.unwrap();
+    assert_eq!((summary.pages, summary.journals, summary.assets), (2, 1, 1));
+    assert!(export_dir.join("journals/2026_10_17.md").is_file());
+    assert!(export_dir.join("assets/diagram.png").is_file());
+    let alpha_file = fs::read_to_string(export_dir.join("pages/Projects___Alpha%3A plan%3F.md")).unwrap();
+    assert!(alpha_file.starts_with(&format!("id:: {}\ntags:: work\n\n- Goal block\n", alpha.id)));
+    assert!(alpha_file.contains(&format!("\n  and a gap\n  id:: {}\n", goal.id)));
+    assert!(alpha_file.contains("\t- TODO Ship #release\n\t  owner:: Sam\n\t  tags:: urgent\n"));
+    assert!(alpha_file.contains("![diagram](../assets/diagram.png)"));
+
+    let (_target_dir, target) = setup().await;
+    let imported = target.import_markdown_dir(&export_dir, |_| {}).await.unwrap();
+    assert!(imported.unresolved_links.is_empty());
+
+    // The image is copied into the attachments, tags only set through the
+    // API are spelled out in a `tags::` line, and inline `#tags` become tags
+    let exported_image = target.attachments_dir().join("diagram.png");
+    assert_eq!(fs::read(&exported_image).unwrap(), b"png");
+    let mut expected = outline(&source).await;
+    for (_, lines) in expected.iter_mut() {
+        for line in lines.iter_mut() {
+            *line = line
+                .replace(&image.display().to_string(), &exported_image.display().to_string())
+                .replace("owner:: Sam [\"urgent\"]", "owner:: Sam\ntags:: urgent [\"release\", \"urgent\"]");
+        }
+    }
+    assert_eq!(outline(&target).await, expected);
+    assert_eq!(links(&target).await, links(&source).await);
+
+    let imported_alpha = target.get_node(&alpha.id).await.unwrap();
+    assert_eq!(imported_alpha.tags, vec!["work"]);
+    assert_eq!(target.get_node(&goal.id).await.unwrap().parent_id, Some(alpha.id.clone()));
+    let task = target.get_node_with_children(&goal.id).await.unwrap().child_nodes.remove(0).node;
+    let properties: HashMap<_, _> = task.properties.into_iter().collect();
+    assert_eq!(properties.get("owner"), Some(&serde_json::json!("Sam")));
+}
@@ -4,4 +4,63 @@ pub mod export_tests;
 pub mod stats_tests;
 pub mod integration_tests;
 pub mod node_tests;
-pub mod link_tests; 
\ No newline at end of file
+pub mod link_tests;
+pub mod journal_tests;
+pub mod periodic_tests;
+pub mod template_tests;
+pub mod time_travel_tests;
+pub mod content_index_tests;
+pub mod task_tests;
+pub mod agenda_tests;
+pub mod clock_tests;
+pub mod card_tests;
+pub mod habit_tests;
+pub mod import_tests;
+pub mod markdown_export_tests;
+pub mod opml_tests;
+pub mod publish_tests;
+pub mod roam_tests;
+pub mod notion_tests;
+pub mod enex_tests;
+pub mod org_tests;
+pub mod json_import_tests;
+pub mod backup_tests;
+pub mod snapshot_tests;
+pub mod git_tests;
+
+use crate::models::{CreateNodeRequest, Node};
+use crate::services::database::connection::DatabaseService;
+use serde_json::Value;
+use std::collections::HashMap;
+use tempfile::{tempdir, TempDir};
+
+/// A fresh database in a temporary folder, removed when the `TempDir` drops
+pub async fn setup() -> (TempDir, DatabaseService) {
+    let temp_dir = tempdir().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let db = DatabaseService::new_test(db_path.to_str().unwrap()).await.unwrap();
+    (temp_dir, db)
+}
+
+/// Create a node at `order` under `parent_id`, with `tags` and no properties
+pub async fn create_node(db: &DatabaseService, content: &str, parent_id: Option<&str>, order: i32, tags: &[&str]) -> Node {
+    create_node_with_properties(db, content, parent_id, order, HashMap::new(), tags).await
+}
+
+/// Create a node at `order` under `parent_id` with `properties` and `tags`
+pub async fn create_node_with_properties(
+    db: &DatabaseService,
+    content: &str,
+    parent_id: Option<&str>,
+    order: i32,
+    properties: HashMap<String, Value>,
+    tags: &[&str],
+) -> Node {
+    db.create_node(CreateNodeRequest {
+        content: content.to_string(),
+        parent_id: parent_id.map(str::to_string),
+        order: Some(order),
+        properties: Some(properties),
+        tags: Some(tags.iter().map(|tag| tag.to_string()).collect()),
+    }).await.unwrap()
+}
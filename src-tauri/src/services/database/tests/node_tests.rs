@@ -3,12 +3,12 @@ use crate::services::database::connection::DatabaseService;
 use tempfile::tempdir;
 use std::collections::HashMap;
 
-#[test]
-fn test_create_node() {
+#[tokio::test]
+async fn test_create_node() {
     let temp_dir = tempdir().unwrap();
     let db_path = temp_dir.path().join("test.db");
-    let db = DatabaseService::new_test(db_path.to_str().unwrap());
-    db.init_database().unwrap();
+    let db = DatabaseService::new_test(db_path.to_str().unwrap()).await.unwrap();
+    db.init_database().await.unwrap();
 
     let request = CreateNodeRequest {
         content: "Test node content".to_string(),
@@ -18,7 +18,7 @@ fn test_create_node() {
         tags: None,
     };
 
-    let node = db.create_node(request).unwrap();
+    let node = db.create_node(request).await.unwrap();
     
     assert!(!node.id.is_empty());
     assert_eq!(node.content, "Test node content");
@@ -28,12 +28,12 @@ fn test_create_node() {
     assert_eq!(node.version, 1);
 }
 
-#[test]
-fn test_create_node_with_parent() {
+#[tokio::test]
+async fn test_create_node_with_parent() {
     let temp_dir = tempdir().unwrap();
     let db_path = temp_dir.path().join("test.db");
-    let db = DatabaseService::new_test(db_path.to_str().unwrap());
-    db.init_database().unwrap();
+    let db = DatabaseService::new_test(db_path.to_str().unwrap()).await.unwrap();
+    db.init_database().await.unwrap();
 
     // Create parent node
     let parent_request = CreateNodeRequest {
@@ -43,7 +43,7 @@ fn test_create_node_with_parent() {
         properties: None,
         tags: None,
     };
-    let parent = db.create_node(parent_request).unwrap();
+    let parent = db.create_node(parent_request).await.unwrap();
 
     // Create child node
     let child_request = CreateNodeRequest {
@@ -53,19 +53,19 @@ fn test_create_node_with_parent() {
         properties: None,
         tags: Some(vec!["test-tag".to_string()]),
     };
-    let child = db.create_node(child_request).unwrap();
+    let child = db.create_node(child_request).await.unwrap();
 
     assert_eq!(child.parent_id, Some(parent.id));
     assert_eq!(child.order, 1);
     assert_eq!(child.tags, vec!["test-tag"]);
 }
 
-#[test]
-fn test_get_node() {
+#[tokio::test]
+async fn test_get_node() {
     let temp_dir = tempdir().unwrap();
     let db_path = temp_dir.path().join("test.db");
-    let db = DatabaseService::new_test(db_path.to_str().unwrap());
-    db.init_database().unwrap();
+    let db = DatabaseService::new_test(db_path.to_str().unwrap()).await.unwrap();
+    db.init_database().await.unwrap();
 
     let request = CreateNodeRequest {
         content: "Get test node".to_string(),
@@ -74,30 +74,30 @@ fn test_get_node() {
         properties: None,
         tags: None,
     };
-    let created_node = db.create_node(request).unwrap();
+    let created_node = db.create_node(request).await.unwrap();
 
-    let retrieved_node = db.get_node(&created_node.id).unwrap();
+    let retrieved_node = db.get_node(&created_node.id).await.unwrap();
     assert_eq!(retrieved_node.id, created_node.id);
     assert_eq!(retrieved_node.content, "Get test node");
 }
 
-#[test]
-fn test_get_node_not_found() {
+#[tokio::test]
+async fn test_get_node_not_found() {
     let temp_dir = tempdir().unwrap();
     let db_path = temp_dir.path().join("test.db");
-    let db = DatabaseService::new_test(db_path.to_str().unwrap());
-    db.init_database().unwrap();
+    let db = DatabaseService::new_test(db_path.to_str().unwrap()).await.unwrap();
+    db.init_database().await.unwrap();
 
-    let result = db.get_node("non-existent-id");
+    let result = db.get_node("non-existent-id").await;
     assert!(result.is_err());
 }
 
-#[test]
-fn test_get_node_with_children() {
+#[tokio::test]
+async fn test_get_node_with_children() {
     let temp_dir = tempdir().unwrap();
     let db_path = temp_dir.path().join("test.db");
-    let db = DatabaseService::new_test(db_path.to_str().unwrap());
-    db.init_database().unwrap();
+    let db = DatabaseService::new_test(db_path.to_str().unwrap()).await.unwrap();
+    db.init_database().await.unwrap();
 
     // Create parent node
     let parent_request = CreateNodeRequest {
@@ -107,7 +107,7 @@ fn test_get_node_with_children() {
         properties: None,
         tags: None,
     };
-    let parent = db.create_node(parent_request).unwrap();
+    let parent = db.create_node(parent_request).await.unwrap();
 
     // Create child nodes
     for i in 0..3 {
@@ -118,10 +118,10 @@ fn test_get_node_with_children() {
             properties: None,
             tags: None,
         };
-        db.create_node(child_request).unwrap();
+        db.create_node(child_request).await.unwrap();
     }
 
-    let node_with_children = db.get_node_with_children(&parent.id).unwrap();
+    let node_with_children = db.get_node_with_children(&parent.id).await.unwrap();
     assert_eq!(node_with_children.node.content, "Parent with children");
     assert_eq!(node_with_children.child_nodes.len(), 3);
     
@@ -132,12 +132,12 @@ fn test_get_node_with_children() {
     }
 }
 
-#[test]
-fn test_update_node_content() {
+#[tokio::test]
+async fn test_update_node_content() {
     let temp_dir = tempdir().unwrap();
     let db_path = temp_dir.path().join("test.db");
-    let db = DatabaseService::new_test(db_path.to_str().unwrap());
-    db.init_database().unwrap();
+    let db = DatabaseService::new_test(db_path.to_str().unwrap()).await.unwrap();
+    db.init_database().await.unwrap();
 
     let request = CreateNodeRequest {
         content: "Original content".to_string(),
@@ -146,26 +146,28 @@ fn test_update_node_content() {
         properties: None,
         tags: None,
     };
-    let node = db.create_node(request).unwrap();
+    let node = db.create_node(request).await.unwrap();
 
     let update_request = UpdateNodeRequest {
         content: Some("Updated content".to_string()),
+        parent_id: None,
+        order: None,
         properties: None,
         tags: None,
     };
-    let updated_node = db.update_node(&node.id, update_request).unwrap();
+    let updated_node = db.update_node(&node.id, update_request).await.unwrap();
 
     assert_eq!(updated_node.content, "Updated content");
     assert_eq!(updated_node.version, 2);
     assert!(updated_node.updated_at > node.updated_at);
 }
 
-#[test]
-fn test_update_node_properties() {
+#[tokio::test]
+async fn test_update_node_properties() {
     let temp_dir = tempdir().unwrap();
     let db_path = temp_dir.path().join("test.db");
-    let db = DatabaseService::new_test(db_path.to_str().unwrap());
-    db.init_database().unwrap();
+    let db = DatabaseService::new_test(db_path.to_str().unwrap()).await.unwrap();
+    db.init_database().await.unwrap();
 
     let request = CreateNodeRequest {
         content: "Node with properties".to_string(),
@@ -174,7 +176,7 @@ fn test_update_node_properties() {
         properties: None,
         tags: None,
     };
-    let node = db.create_node(request).unwrap();
+    let node = db.create_node(request).await.unwrap();
 
     let mut properties = HashMap::new();
     properties.insert("key1".to_string(), serde_json::json!("value1"));
@@ -182,21 +184,23 @@ fn test_update_node_properties() {
 
     let update_request = UpdateNodeRequest {
         content: None,
+        parent_id: None,
+        order: None,
         properties: Some(properties.clone()),
         tags: None,
     };
-    let updated_node = db.update_node(&node.id, update_request).unwrap();
+    let updated_node = db.update_node(&node.id, update_request).await.unwrap();
 
     assert_eq!(updated_node.properties, properties);
     assert_eq!(updated_node.content, "Node with properties"); // Content unchanged
 }
 
-#[test]
-fn test_delete_node() {
+#[tokio::test]
+async fn test_delete_node() {
     let temp_dir = tempdir().unwrap();
     let db_path = temp_dir.path().join("test.db");
-    let db = DatabaseService::new_test(db_path.to_str().unwrap());
-    db.init_database().unwrap();
+    let db = DatabaseService::new_test(db_path.to_str().unwrap()).await.unwrap();
+    db.init_database().await.unwrap();
 
     let request = CreateNodeRequest {
         content: "Node to delete".to_string(),
@@ -205,22 +209,22 @@ fn test_delete_node() {
         properties: None,
         tags: None,
     };
-    let node = db.create_node(request).unwrap();
+    let node = db.create_node(request).await.unwrap();
 
     // Delete the node
-    db.delete_node(&node.id).unwrap();
+    db.delete_node(&node.id).await.unwrap();
 
     // Verify node is deleted
-    let result = db.get_node(&node.id);
+    let result = db.get_node(&node.id).await;
     assert!(result.is_err());
 }
 
-#[test]
-fn test_delete_node_cascades_children() {
+#[tokio::test]
+async fn test_delete_node_cascades_children() {
     let temp_dir = tempdir().unwrap();
     let db_path = temp_dir.path().join("test.db");
-    let db = DatabaseService::new_test(db_path.to_str().unwrap());
-    db.init_database().unwrap();
+    let db = DatabaseService::new_test(db_path.to_str().unwrap()).await.unwrap();
+    db.init_database().await.unwrap();
 
     // Create parent and child nodes
     let parent_request = CreateNodeRequest {
@@ -230,7 +234,7 @@ fn test_delete_node_cascades_children() {
         properties: None,
         tags: None,
     };
-    let parent = db.create_node(parent_request).unwrap();
+    let parent = db.create_node(parent_request).await.unwrap();
 
     let child_request = CreateNodeRequest {
         content: "Child to be cascaded".to_string(),
@@ -239,22 +243,22 @@ fn test_delete_node_cascades_children() {
         properties: None,
         tags: None,
     };
-    let child = db.create_node(child_request).unwrap();
+    let child = db.create_node(child_request).await.unwrap();
 
     // Delete parent
-    db.delete_node(&parent.id).unwrap();
+    db.delete_node(&parent.id).await.unwrap();
 
     // Verify both are deleted
-    assert!(db.get_node(&parent.id).is_err());
-    assert!(db.get_node(&child.id).is_err());
+    assert!(db.get_node(&parent.id).await.is_err());
+    assert!(db.get_node(&child.id).await.is_err());
 }
 
-#[test]
-fn test_move_node() {
+#[tokio::test]
+async fn test_move_node() {
     let temp_dir = tempdir().unwrap();
     let db_path = temp_dir.path().join("test.db");
-    let db = DatabaseService::new_test(db_path.to_str().unwrap());
-    db.init_database().unwrap();
+    let db = DatabaseService::new_test(db_path.to_str().unwrap()).await.unwrap();
+    db.init_database().await.unwrap();
 
     // Create nodes
     let parent1_request = CreateNodeRequest {
@@ -264,7 +268,7 @@ fn test_move_node() {
         properties: None,
         tags: None,
     };
-    let parent1 = db.create_node(parent1_request).unwrap();
+    let parent1 = db.create_node(parent1_request).await.unwrap();
 
     let parent2_request = CreateNodeRequest {
         content: "Parent 2".to_string(),
@@ -273,7 +277,7 @@ fn test_move_node() {
         properties: None,
         tags: None,
     };
-    let parent2 = db.create_node(parent2_request).unwrap();
+    let parent2 = db.create_node(parent2_request).await.unwrap();
 
     let child_request = CreateNodeRequest {
         content: "Child to move".to_string(),
@@ -282,22 +286,22 @@ fn test_move_node() {
         properties: None,
         tags: None,
     };
-    let child = db.create_node(child_request).unwrap();
+    let child = db.create_node(child_request).await.unwrap();
 
     // Move child from parent1 to parent2
-    db.move_node(&child.id, Some(parent2.id.clone()), 5).unwrap();
+    db.move_node(&child.id, Some(parent2.id.clone()), 5).await.unwrap();
 
-    let moved_child = db.get_node(&child.id).unwrap();
+    let moved_child = db.get_node(&child.id).await.unwrap();
     assert_eq!(moved_child.parent_id, Some(parent2.id));
     assert_eq!(moved_child.order, 5);
 }
 
-#[test]
-fn test_move_node_to_root() {
+#[tokio::test]
+async fn test_move_node_to_root() {
     let temp_dir = tempdir().unwrap();
     let db_path = temp_dir.path().join("test.db");
-    let db = DatabaseService::new_test(db_path.to_str().unwrap());
-    db.init_database().unwrap();
+    let db = DatabaseService::new_test(db_path.to_str().unwrap()).await.unwrap();
+    db.init_database().await.unwrap();
 
     // Create parent and child
     let parent_request = CreateNodeRequest {
@@ -307,7 +311,7 @@ fn test_move_node_to_root() {
         properties: None,
         tags: None,
     };
-    let parent = db.create_node(parent_request).unwrap();
+    let parent = db.create_node(parent_request).await.unwrap();
 
     let child_request = CreateNodeRequest {
         content: "Child to move to root".to_string(),
@@ -316,23 +320,24 @@ fn test_move_node_to_root() {
         properties: None,
         tags: None,
     };
-    let child = db.create_node(child_request).unwrap();
+    let child = db.create_node(child_request).await.unwrap();
 
     // Move child to root
-    db.move_node(&child.id, None, 10).unwrap();
+    db.move_node(&child.id, None, 10).await.unwrap();
 
-    let moved_child = db.get_node(&child.id).unwrap();
+    let moved_child = db.get_node(&child.id).await.unwrap();
     assert_eq!(moved_child.parent_id, None);
     assert_eq!(moved_child.order, 10);
 }
 
-#[test]
-fn test_get_daily_note() {
+#[tokio::test]
+async fn test_get_daily_note() {
     let temp_dir = tempdir().unwrap();
     let db_path = temp_dir.path().join("test.db");
-    let db = DatabaseService::new_test(db_path.to_str().unwrap());
-    db.init_database().unwrap();
+    let db = DatabaseService::new_test(db_path.to_str().unwrap()).await.unwrap();
+    db.init_database().await.unwrap();
 
+    // Journal pages are keyed by date, not found by their content
     let date = "2024-01-15";
     let journal_request = CreateNodeRequest {
         content: date.to_string(),
@@ -341,56 +346,51 @@ fn test_get_daily_note() {
         properties: None,
         tags: Some(vec!["#Journal".to_string()]),
     };
-    let journal = db.create_node(journal_request).unwrap();
+    db.create_node(journal_request).await.unwrap();
+    assert!(db.get_daily_note(date).await.is_err());
 
-    let retrieved = db.get_daily_note(date).unwrap();
+    let journal = db.get_or_create_daily_note(date).await.unwrap();
+    let retrieved = db.get_daily_note(date).await.unwrap();
     assert_eq!(retrieved.id, journal.id);
-    assert_eq!(retrieved.content, date);
-    assert!(retrieved.tags.contains(&"#Journal".to_string()));
+    assert_eq!(retrieved.content, "Jan 15th, 2024");
+    assert!(retrieved.tags.contains(&"journal".to_string()));
 }
 
-#[test]
-fn test_get_or_create_daily_note_existing() {
+#[tokio::test]
+async fn test_get_or_create_daily_note_existing() {
     let temp_dir = tempdir().unwrap();
     let db_path = temp_dir.path().join("test.db");
-    let db = DatabaseService::new_test(db_path.to_str().unwrap());
-    db.init_database().unwrap();
+    let db = DatabaseService::new_test(db_path.to_str().unwrap()).await.unwrap();
+    db.init_database().await.unwrap();
 
     let date = "2024-01-16";
-    let journal_request = CreateNodeRequest {
-        content: date.to_string(),
-        parent_id: None,
-        order: Some(0),
-        properties: None,
-        tags: Some(vec!["#Journal".to_string()]),
-    };
-    let journal = db.create_node(journal_request).unwrap();
+    let journal = db.get_or_create_daily_note(date).await.unwrap();
 
-    let retrieved = db.get_or_create_daily_note(date).unwrap();
+    let retrieved = db.get_or_create_daily_note(date).await.unwrap();
     assert_eq!(retrieved.id, journal.id);
 }
 
-#[test]
-fn test_get_or_create_daily_note_new() {
+#[tokio::test]
+async fn test_get_or_create_daily_note_new() {
     let temp_dir = tempdir().unwrap();
     let db_path = temp_dir.path().join("test.db");
-    let db = DatabaseService::new_test(db_path.to_str().unwrap());
-    db.init_database().unwrap();
+    let db = DatabaseService::new_test(db_path.to_str().unwrap()).await.unwrap();
+    db.init_database().await.unwrap();
 
     let date = "2024-01-17";
-    let journal = db.get_or_create_daily_note(date).unwrap();
+    let journal = db.get_or_create_daily_note(date).await.unwrap();
     
-    assert_eq!(journal.content, date);
-    assert!(journal.tags.contains(&"#Journal".to_string()));
+    assert_eq!(journal.content, "Jan 17th, 2024");
+    assert!(journal.tags.contains(&"journal".to_string()));
     assert_eq!(journal.parent_id, None);
 }
 
-#[test]
-fn test_node_hierarchy_integrity() {
+#[tokio::test]
+async fn test_node_hierarchy_integrity() {
     let temp_dir = tempdir().unwrap();
     let db_path = temp_dir.path().join("test.db");
-    let db = DatabaseService::new_test(db_path.to_str().unwrap());
-    db.init_database().unwrap();
+    let db = DatabaseService::new_test(db_path.to_str().unwrap()).await.unwrap();
+    db.init_database().await.unwrap();
 
     // Create a three-level hierarchy
     let root_request = CreateNodeRequest {
@@ -400,7 +400,7 @@ fn test_node_hierarchy_integrity() {
         properties: None,
         tags: None,
     };
-    let root = db.create_node(root_request).unwrap();
+    let root = db.create_node(root_request).await.unwrap();
 
     let level1_request = CreateNodeRequest {
         content: "Level 1 node".to_string(),
@@ -409,7 +409,7 @@ fn test_node_hierarchy_integrity() {
         properties: None,
         tags: None,
     };
-    let level1 = db.create_node(level1_request).unwrap();
+    let level1 = db.create_node(level1_request).await.unwrap();
 
     let level2_request = CreateNodeRequest {
         content: "Level 2 node".to_string(),
@@ -418,10 +418,10 @@ fn test_node_hierarchy_integrity() {
         properties: None,
         tags: None,
     };
-    let level2 = db.create_node(level2_request).unwrap();
+    let level2 = db.create_node(level2_request).await.unwrap();
 
     // Get the complete hierarchy
-    let hierarchy = db.get_node_with_children(&root.id).unwrap();
+    let hierarchy = db.get_node_with_children(&root.id).await.unwrap();
     
     assert_eq!(hierarchy.child_nodes.len(), 1);
     assert_eq!(hierarchy.child_nodes[0].node.id, level1.id);
@@ -429,12 +429,12 @@ fn test_node_hierarchy_integrity() {
     assert_eq!(hierarchy.child_nodes[0].child_nodes[0].node.id, level2.id);
 }
 
-#[test]
-fn test_concurrent_updates() {
+#[tokio::test]
+async fn test_concurrent_updates() {
     let temp_dir = tempdir().unwrap();
     let db_path = temp_dir.path().join("test.db");
-    let db = DatabaseService::new_test(db_path.to_str().unwrap());
-    db.init_database().unwrap();
+    let db = DatabaseService::new_test(db_path.to_str().unwrap()).await.unwrap();
+    db.init_database().await.unwrap();
 
     let request = CreateNodeRequest {
         content: "Concurrent test".to_string(),
@@ -443,23 +443,27 @@ fn test_concurrent_updates() {
         properties: None,
         tags: None,
     };
-    let node = db.create_node(request).unwrap();
+    let node = db.create_node(request).await.unwrap();
 
     // Simulate concurrent updates
     let update1 = UpdateNodeRequest {
         content: Some("Update 1".to_string()),
+        parent_id: None,
+        order: None,
         properties: None,
         tags: None,
     };
-    let updated1 = db.update_node(&node.id, update1).unwrap();
+    let updated1 = db.update_node(&node.id, update1).await.unwrap();
     assert_eq!(updated1.version, 2);
 
     let update2 = UpdateNodeRequest {
         content: Some("Update 2".to_string()),
+        parent_id: None,
+        order: None,
         properties: None,
         tags: None,
     };
-    let updated2 = db.update_node(&node.id, update2).unwrap();
+    let updated2 = db.update_node(&node.id, update2).await.unwrap();
     assert_eq!(updated2.version, 3);
     assert_eq!(updated2.content, "Update 2");
 }
\ No newline at end of file
@@ -0,0 +1,120 @@
+use crate::services::database::connection::DatabaseService;
+use crate::utils::rewrite_image_links;
+use super::setup;
+use serde_json::json;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+use zip::write::SimpleFileOptions;
+
+const HASH: &str = "0123456789abcdef0123456789abcdef";
+
+fn write_zip(path: &Path, entries: &[(String, &[u8])]) {
+    let mut zip = zip::ZipWriter::new(File::create(path).unwrap());
+    for (name, contents) in entries {
+        zip.start_file(name.as_str(), SimpleFileOptions::default()).unwrap();
+        zip.write_all(contents).unwrap();
+    }
+    zip.finish().unwrap();
+}
+
+async fn page(db: &DatabaseService, title: &str) -> (String, serde_json::Value) {
+    let (id, properties): (String, String) = sqlx::query_as("SELECT id, properties FROM nodes WHERE parent_id IS NULL AND content = ?")
+        .bind(title)
+        .fetch_one(db.pool())
+        .await
+        .unwrap();
+    (id, serde_json::from_str(&properties).unwrap())
+}
+
+#[tokio::test]
+async fn test_import_notion_zip() {
+    let (temp_dir, db) = setup().await;
+    let home = format!("Export-1/Home {}", HASH);
+    let tasks = format!("{}/Tasks {}", home, HASH);
+    let home_md = format!(
+        "# Home\n\nSee [Plan](Home%20{h}/Plan%20{h}.md)\n\n![chart](Home%20{h}/chart.png)",
+        h = HASH
+    );
+    let zip_path = temp_dir.path().join("notion.zip");
+    write_zip(&zip_path, &[
+        (format!("{}.md", home), home_md.as_bytes()),
+        (format!("{}/Plan {}.md", home, HASH), b"# Plan\n\n- Ship [[it]]\n- Back to [Home](../Home%200123456789abcdef0123456789abcdef.md)"),
+        (format!("{}/chart.png", home), b"png"),
+        (format!("{}.csv", tasks), b"Name,Done,Due\nWrite,No,October 17 2026\n"),
+        (format!("{}_all.csv", tasks), b"\xef\xbb\xbfName,Done,Due\nWrite,Yes,\"October 17, 2026\"\nReview,No,\n"),
+        (format!("{}/Write {}.md", tasks, HASH), b"# Write\n\nDone: Yes\nDue: October 17, 2026\n\nDraft first"),
+    ]);
+
+    let summary = db.import_notion_zip(&zip_path, |_| {}).await.unwrap();
+    assert_eq!((summary.pages_created, summary.pages_merged), (5, 1));
+    assert_eq!(summary.unresolved_links, vec!["it"]);
+
+    let (write_id, properties) = page(&db, "Home/Tasks/Write").await;
+    assert_eq!(properties, json!({"done": true, "due": "2026-10-17"}));
+    let write = db.get_node_with_children(&write_id).await.unwrap();
+    let contents: Vec<&str> = write.child_nodes.iter().map(|child| child.node.content.as_str()).collect();
+    assert_eq!(contents, vec!["Draft first"]);
+    let (_, review) = page(&db, "Home/Tasks/Review").await;
+    assert_eq!(review, json!({"done": false}));
+
+    let (tasks_id, _) = page(&db, "Home/Tasks").await;
+    let database = db.get_node_with_children(&tasks_id).await.unwrap();
+    assert_eq!(database.child_nodes[0].node.content, "[[Home/Tasks/Write]]");
+
+    let (home_id, _) = page(&db, "Home").await;
+    let home = db.get_node_with_children(&home_id).await.unwrap();
+    assert_eq!(home.child_nodes[0].node.content, "See [[Home/Plan]]");
+    let chart = db.attachments_dir().join("chart.png");
+    assert_eq!(fs::read(&chart).unwrap(), b"png");
+    assert_eq!(home.child_nodes[1].node.content, format!("![chart]({})", chart.display()));
+
+    // Links between pages and from the database to its rows are recorded
+    let (plan_id, _) = page(&db, "Home/Plan").await;
+    let linked: Vec<String> = sqlx::query_scalar(
+        "SELECT DISTINCT n.parent_id FROM node_links l JOIN nodes n ON n.id = l.source_node_id WHERE l.target_node_id IN (?, ?, ?) ORDER BY 1"
+    )
+    .bind(&home_id)
+    .bind(&plan_id)
+    .bind(&write_id)
+    .fetch_all(db.pool())
+    .await
+    .unwrap();
+    let mut expected = vec![home_id.clone(), plan_id.clone(), tasks_id.clone()];
+    expected.sort();
+    assert_eq!(linked, expected);
+
+    fs::write(&zip_path, "not a zip").unwrap();
+    assert!(db.import_notion_zip(&zip_path, |_| {}).await.is_err());
+}
+
+#[tokio::test]
+async fn test_import_notion_attachments() {
+    let (temp_dir, db) = setup().await;
+    let home = format!("Export-1/Home {}", HASH);
+    let home_md = format!("# Home\n\n![chart](Home%20{}/my%20chart.png)", HASH);
+    let zip_path = temp_dir.path().join("notion.zip");
+    write_zip(&zip_path, &[
+        (format!("{}.md", home), home_md.as_bytes()),
+        (format!("{}/my chart.png", home), b"png"),
+    ]);
+
+    // Paths with spaces are linked in angle brackets and read back as images
+    db.import_notion_zip(&zip_path, |_| {}).await.unwrap();
+    let (home_id, _) = page(&db, "Home").await;
+    let home_page = db.get_node_with_children(&home_id).await.unwrap();
+    let chart = db.attachments_dir().join("my chart.png");
+    let content = &home_page.child_nodes[0].node.content;
+    assert_eq!(*content, format!("![chart](<{}>)", chart.display()));
+    let mut targets = Vec::new();
+    rewrite_image_links(content, |target| {
+        targets.push(target.to_string());
+        None
+    });
+    assert_eq!(targets, vec![chart.display().to_string()]);
+
+    // Attachments that cannot be written fail the import
+    fs::remove_dir_all(db.attachments_dir()).unwrap();
+    fs::write(db.attachments_dir(), "not a folder").unwrap();
+    assert!(db.import_notion_zip(&zip_path, |_| {}).await.is_err());
+}
@@ -0,0 +1,66 @@
+use super::{create_node_with_properties, setup};
+use serde_json::json;
+use std::collections::HashMap;
+use std::fs;
+
+#[tokio::test]
+async fn test_opml_round_trip() {
+    let (temp_dir, db) = setup().await;
+    let page = create_node_with_properties(&db, "Reading <list>", None, 0, HashMap::from([("status".to_string(), json!("active"))]), &["books"]).await;
+    // Created out of order; the export follows `order`
+    create_node_with_properties(&db, "Second", Some(&page.id), 1, HashMap::new(), &[]).await;
+    let first = create_node_with_properties(&db, "First & \"quoted\"\nsecond line", Some(&page.id), 0, HashMap::from([("rating".to_string(), json!(5))]), &["fav", "sf"]).await;
+    create_node_with_properties(&db, "Nested", Some(&first.id), 0, HashMap::from([("has space".to_string(), json!("dropped"))]), &[]).await;
+
+    let opml = db.export_node_to_opml(&page.id).await.unwrap();
+    assert!(opml.contains("<title>Reading &lt;list&gt;</title>"));
+    assert!(opml.contains("<outline text=\"Reading &lt;list&gt;\" category=\"books\" status=\"active\">"));
+    assert!(opml.contains("<outline text=\"First &amp; &quot;quoted&quot;&#10;second line\" category=\"fav,sf\" rating=\"5\">"));
+    assert!(opml.find("First &amp;").unwrap() < opml.find("\"Second\"").unwrap());
+
+    let path = temp_dir.path().join("reading.opml");
+    fs::write(&path, &opml).unwrap();
+    let (_target_dir, target) = setup().await;
+    let summary = target.import_opml(&path).await.unwrap();
+    assert_eq!((summary.pages_created, summary.blocks_created), (1, 3));
+
+    let page_id: String = sqlx::query_scalar("SELECT id FROM nodes WHERE parent_id IS NULL AND content = 'Reading <list>'")
+        .fetch_one(target.pool())
+        .await
+        .unwrap();
+    let tree = target.get_node_with_children(&page_id).await.unwrap();
+    assert_eq!(tree.node.tags, vec!["books"]);
+    assert_eq!(tree.node.properties.get("status"), Some(&json!("active")));
+    let contents: Vec<&str> = tree.child_nodes.iter().map(|child| child.node.content.as_str()).collect();
+    assert_eq!(contents, vec!["First & \"quoted\"\nsecond line", "Second"]);
+    let first = &tree.child_nodes[0];
+    assert_eq!(first.node.tags, vec!["fav", "sf"]);
+    assert_eq!(first.node.properties.get("rating"), Some(&json!(5)));
+    assert_eq!(first.child_nodes[0].node.content, "Nested");
+    assert!(first.child_nodes[0].node.properties.is_empty());
+}
+
+#[tokio::test]
+async fn test_opml_deep_outline() {
+    let (temp_dir, db) = setup().await;
+    let depth = 500;
+    let mut opml = String::from("<opml version=\"2.0\"><head><title>Deep</title></head><body>");
+    for level in 0..depth {
+        opml.push_str(&format!("<outline text=\"Level {}\">", level));
+    }
+    opml.push_str(&"</outline>".repeat(depth));
+    opml.push_str("</body></opml>");
+    let path = temp_dir.path().join("deep.opml");
+    fs::write(&path, opml).unwrap();
+
+    let summary = db.import_opml(&path).await.unwrap();
+    assert_eq!(summary.blocks_created, depth);
+
+    let page_id: String = sqlx::query_scalar("SELECT id FROM nodes WHERE parent_id IS NULL AND content = 'Deep'")
+        .fetch_one(db.pool())
+        .await
+        .unwrap();
+    let exported = db.export_node_to_opml(&page_id).await.unwrap();
+    assert_eq!(exported.matches("<outline ").count(), depth + 1);
+    assert!(exported.contains(&format!("<outline text=\"Level {}\"/>", depth - 1)));
+}
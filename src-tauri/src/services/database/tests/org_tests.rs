@@ -0,0 +1,57 @@
+use super::{create_node_with_properties, setup};
+use serde_json::json;
+use std::collections::HashMap;
+use std::fs;
+
+#[tokio::test]
+async fn test_org_round_trip() {
+    let (temp_dir, db) = setup().await;
+    let page = create_node_with_properties(&db, "Launch plan", None, 0, HashMap::from([("status".to_string(), json!("active"))]), &["work"]).await;
+    let ship = create_node_with_properties(&db, "TODO [#A] Ship it\nSCHEDULED: <2026-10-20 Tue> DEADLINE: <2026-10-23 Fri>\nRelease notes first\n* not a headline", Some(&page.id), 0, HashMap::from([("owner".to_string(), json!("Sam")), ("points".to_string(), json!(3))]), &["release"]).await;
+    create_node_with_properties(&db, "DONE Book the room", Some(&ship.id), 0, HashMap::new(), &[]).await;
+    create_node_with_properties(&db, "Retro", Some(&page.id), 1, HashMap::from([("has space".to_string(), json!("kept"))]), &["next up", "50%"]).await;
+
+    let org = db.export_node_to_org(&page.id).await.unwrap();
+    assert_eq!(org, "#+TITLE: Launch plan\n\n\
+* Launch plan :work:\n  :PROPERTIES:\n  :status: active\n  :END:\n\
+** TODO [#A] Ship it :release:\n   SCHEDULED: <2026-10-20 Tue> DEADLINE: <2026-10-23 Fri>\n   :PROPERTIES:\n   :owner: Sam\n   :points: 3\n   :END:\n   Release notes first\n   * not a headline\n\
+*** DONE Book the room\n\
+** Retro :next%20up:50%25:\n   :PROPERTIES:\n   :has%20space: kept\n   :END:\n");
+
+    let path = temp_dir.path().join("launch.org");
+    fs::write(&path, &org).unwrap();
+    let (_target_dir, target) = setup().await;
+    let summary = target.import_org(&path).await.unwrap();
+    assert_eq!((summary.pages_created, summary.blocks_created), (1, 3));
+
+    let page_id: String = sqlx::query_scalar("SELECT id FROM nodes WHERE parent_id IS NULL AND content = 'Launch plan'")
+        .fetch_one(target.pool())
+        .await
+        .unwrap();
+    let tree = target.get_node_with_children(&page_id).await.unwrap();
+    assert_eq!(tree.node.tags, vec!["work"]);
+    assert_eq!(tree.node.properties.get("status"), Some(&json!("active")));
+    let ship = &tree.child_nodes[0];
+    assert_eq!(ship.node.content, "TODO [#A] Ship it\nSCHEDULED: <2026-10-20 Tue> DEADLINE: <2026-10-23 Fri>\nRelease notes first\n* not a headline");
+    assert_eq!(ship.node.tags, vec!["release"]);
+    assert_eq!(ship.node.properties.get("points"), Some(&json!(3)));
+    assert_eq!(ship.child_nodes[0].node.content, "DONE Book the room");
+    let retro = &tree.child_nodes[1].node;
+    assert_eq!(retro.content, "Retro");
+    assert_eq!(retro.tags, vec!["next up", "50%"]);
+    assert_eq!(retro.properties.get("has space"), Some(&json!("kept")));
+    assert_eq!(target.export_node_to_org(&page_id).await.unwrap(), org);
+
+    let (state, priority): (String, Option<String>) = sqlx::query_as("SELECT state, priority FROM tasks WHERE node_id = ?")
+        .bind(&ship.node.id)
+        .fetch_one(target.pool())
+        .await
+        .unwrap();
+    assert_eq!((state.as_str(), priority.as_deref()), ("TODO", Some("A")));
+    let dates: Vec<(String, String)> = sqlx::query_as("SELECT key, date FROM node_dates WHERE node_id = ? ORDER BY key")
+        .bind(&ship.node.id)
+        .fetch_all(target.pool())
+        .await
+        .unwrap();
+    assert_eq!(dates, vec![("deadline".to_string(), "2026-10-23".to_string()), ("scheduled".to_string(), "2026-10-20".to_string())]);
+}
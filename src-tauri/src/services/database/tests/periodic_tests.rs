@@ -0,0 +1,104 @@
+use crate::models::{CreateNodeRequest, PeriodKind};
+use crate::services::database::connection::DatabaseService;
+use crate::services::LinkService;
+use super::setup;
+
+async fn link_targets(db: &DatabaseService, source_id: &str) -> Vec<String> {
+    sqlx::query_scalar("SELECT target_node_id FROM node_links WHERE source_node_id = ? ORDER BY target_node_id")
+        .bind(source_id)
+        .fetch_all(db.pool())
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_get_or_create_periodic_note_is_idempotent() {
+    let (_temp_dir, db) = setup().await;
+
+    let week = db.get_or_create_periodic_note(PeriodKind::Week, "2026-10-17").await.unwrap();
+    let same_week = db.get_or_create_periodic_note(PeriodKind::Week, "2026-10-12").await.unwrap();
+    let next_week = db.get_or_create_periodic_note(PeriodKind::Week, "2026-10-19").await.unwrap();
+
+    assert_eq!(week.id, same_week.id);
+    assert_ne!(week.id, next_week.id);
+    assert_eq!(week.content, "Week 42, 2026");
+    assert_eq!(week.properties["period_key"], serde_json::json!("2026-W42"));
+
+    let month = db.get_or_create_periodic_note(PeriodKind::Month, "2026-10-17").await.unwrap();
+    assert_eq!(month.content, "October 2026");
+}
+
+#[tokio::test]
+async fn test_daily_notes_link_to_period_pages() {
+    let (_temp_dir, db) = setup().await;
+
+    // Daily note created before the period page gets linked when the page appears
+    let before = db.get_or_create_daily_note("2026-10-13").await.unwrap();
+    let month = db.get_or_create_periodic_note(PeriodKind::Month, "2026-10-01").await.unwrap();
+    assert_eq!(link_targets(&db, &before.id).await, vec![month.id.clone()]);
+
+    // Daily note created afterwards links on creation
+    let after = db.get_or_create_daily_note("2026-10-20").await.unwrap();
+    assert_eq!(link_targets(&db, &after.id).await, vec![month.id.clone()]);
+
+    // Notes outside the period stay unlinked
+    let other = db.get_or_create_daily_note("2026-11-01").await.unwrap();
+    assert!(link_targets(&db, &other.id).await.is_empty());
+
+    // Rebuilding content links keeps the period link
+    let link_service = LinkService::new(db.clone());
+    link_service.update_links_for_node(&after).await.unwrap();
+    assert_eq!(link_targets(&db, &after.id).await, vec![month.id]);
+}
+
+#[tokio::test]
+async fn test_period_rollup_collects_references_and_tags() {
+    let (_temp_dir, db) = setup().await;
+    let link_service = LinkService::new(db.clone());
+
+    let day = db.get_or_create_daily_note("2026-10-14").await.unwrap();
+    let outside_day = db.get_or_create_daily_note("2026-09-30").await.unwrap();
+
+    let reference = db.create_node(CreateNodeRequest {
+        content: format!("Follow up from [[{}]]", day.content),
+        parent_id: None,
+        order: None,
+        properties: None,
+        tags: None,
+    }).await.unwrap();
+    link_service.update_links_for_node(&reference).await.unwrap();
+
+    let unrelated = db.create_node(CreateNodeRequest {
+        content: format!("Old note about [[{}]]", outside_day.content),
+        parent_id: None,
+        order: None,
+        properties: None,
+        tags: None,
+    }).await.unwrap();
+    link_service.update_links_for_node(&unrelated).await.unwrap();
+
+    let tagged = db.create_node(CreateNodeRequest {
+        content: "Shipped the release".to_string(),
+        parent_id: Some(day.id.clone()),
+        order: None,
+        properties: None,
+        tags: Some(vec!["work".to_string(), "release".to_string()]),
+    }).await.unwrap();
+    sqlx::query("UPDATE nodes SET updated_at = '2026-10-14 09:00:00' WHERE id = ?")
+        .bind(&tagged.id)
+        .execute(db.pool())
+        .await
+        .unwrap();
+
+    let rollup = db.get_period_rollup(PeriodKind::Month, "2026-10-01").await.unwrap();
+
+    assert_eq!(rollup.key, "2026-10");
+    assert!(rollup.page.is_none());
+    assert_eq!(rollup.journals.len(), 1);
+    assert_eq!(rollup.journals[0].node.id, day.id);
+    let referenced: Vec<&str> = rollup.referenced.iter().map(|n| n.id.as_str()).collect();
+    assert_eq!(referenced, vec![reference.id.as_str()]);
+    let tagged_ids: Vec<&str> = rollup.tagged.iter().map(|n| n.id.as_str()).collect();
+    assert_eq!(tagged_ids, vec![tagged.id.as_str()]);
+    assert_eq!(rollup.tag_counts, vec![("release".to_string(), 1), ("work".to_string(), 1)]);
+}
@@ -0,0 +1,83 @@
+use crate::models::{Node, UpdateNodeRequest};
+use crate::services::database::connection::DatabaseService;
+use crate::services::LinkService;
+use crate::services::database::publish::SearchIndexEntry;
+use super::{create_node_with_properties, setup};
+use serde_json::json;
+use std::collections::HashMap;
+use std::fs;
+
+async fn create(db: &DatabaseService, content: &str, parent_id: Option<&str>, order: i32, public: Option<bool>) -> Node {
+    let properties = public.map(|public| HashMap::from([("public".to_string(), json!(public))])).unwrap_or_default();
+    let node = create_node_with_properties(db, content, parent_id, order, properties, &[]).await;
+    LinkService::new(db.clone()).update_links_for_node(&node).await.unwrap();
+    node
+}
+
+#[tokio::test]
+async fn test_publish_public_pages() {
+    let (temp_dir, db) = setup().await;
+    let garden = create(&db, "Garden Notes", None, 0, Some(true)).await;
+    let diary = create(&db, "Diary", None, 1, None).await;
+    create(&db, "Private thoughts", Some(&diary.id), 0, None).await;
+    let tools = create(&db, "Tools", None, 2, Some(true)).await;
+    create(&db, "Hammer & <nails> #hardware", Some(&tools.id), 0, None).await;
+    create(&db, "Planting in spring #seasons", Some(&garden.id), 0, None).await;
+    create(&db, "See [[Tools]] and [[Diary]]", Some(&garden.id), 1, None).await;
+    let secret = create(&db, "Neighbour gossip", Some(&garden.id), 2, Some(false)).await;
+    create(&db, "More gossip", Some(&secret.id), 0, None).await;
+
+    let site = temp_dir.path().join("site");
+    let summary = db.publish_site(&site).await.unwrap();
+    assert_eq!((summary.pages, summary.tags), (2, 2));
+    assert!(!site.join("pages/diary.html").exists());
+
+    let garden_html = fs::read_to_string(site.join("pages/garden-notes.html")).unwrap();
+    assert!(garden_html.contains("<a class=\"page-ref\" href=\"tools.html\">Tools</a> and Diary"));
+    assert!(garden_html.contains("<a class=\"tag\" href=\"../tags/seasons.html\">#seasons</a>"));
+    assert!(!garden_html.contains("gossip"));
+
+    let tools_html = fs::read_to_string(site.join("pages/tools.html")).unwrap();
+    assert!(tools_html.contains("Hammer &amp; &lt;nails&gt;"));
+    assert!(tools_html.contains("Linked references"));
+    assert!(tools_html.contains("<a href=\"garden-notes.html\">Garden Notes</a>"));
+    assert!(!garden_html.contains("Linked references"));
+
+    let tag_html = fs::read_to_string(site.join("tags/hardware.html")).unwrap();
+    assert!(tag_html.contains("<a href=\"../pages/tools.html\">Tools</a>"));
+    let index = fs::read_to_string(site.join("index.html")).unwrap();
+    assert!(index.find("garden-notes.html").unwrap() < index.find("tools.html").unwrap());
+    assert!(!index.contains("Diary"));
+
+    let search: Vec<SearchIndexEntry> = serde_json::from_str(&fs::read_to_string(site.join("search-index.json")).unwrap()).unwrap();
+    assert_eq!(search.len(), 2);
+    assert_eq!(search[0].url, "pages/garden-notes.html");
+    assert_eq!(search[0].tags, vec!["seasons"]);
+    assert!(!search.iter().any(|entry| entry.text.contains("gossip") || entry.text.contains("Private")));
+}
+
+#[tokio::test]
+async fn test_publish_again_drops_unpublished_pages() {
+    let (temp_dir, db) = setup().await;
+    let page = create(&db, "Draft", None, 0, Some(true)).await;
+    let site = temp_dir.path().join("site");
+    db.publish_site(&site).await.unwrap();
+    assert!(site.join("pages/draft.html").is_file());
+
+    db.update_node(&page.id, UpdateNodeRequest {
+        content: None,
+        parent_id: None,
+        order: None,
+        properties: Some(HashMap::from([("public".to_string(), json!("false"))])),
+        tags: None,
+    }).await.unwrap();
+    let summary = db.publish_site(&site).await.unwrap();
+    assert_eq!(summary.pages, 0);
+    assert!(!site.join("pages/draft.html").exists());
+
+    let other = temp_dir.path().join("other");
+    fs::create_dir_all(&other).unwrap();
+    fs::write(other.join("notes.txt"), "keep me").unwrap();
+    assert!(db.publish_site(&other).await.is_err());
+    assert!(other.join("notes.txt").is_file());
+}
@@ -0,0 +1,88 @@
+use crate::services::database::connection::DatabaseService;
+use super::setup;
+use std::fs;
+
+const EXPORT: &str = r#"[
+    {"title": "Project Alpha", "uid": "alpha-page", "create-time": 1700000000000, "edit-time": 1700000900000, "children": [
+        {"string": "{{[[TODO]]}} Ship it", "uid": "ship-it", "create-time": 1700000100000, "edit-time": 1700000200000},
+        {"string": "Notes", "uid": "notes", "children": [
+            {"string": "Depends on ((ship-it)) and ((gone))", "uid": "depends"}
+        ]}
+    ]},
+    {"title": "October 17th, 2026", "children": [
+        {"string": "Worked on [[Project Alpha]]", "uid": "daily-1"}
+    ]},
+    {"title": "Reading", "children": [
+        {"string": "See [[October 17th, 2026]]", "uid": "reading-1"}
+    ]}
+]"#;
+
+async fn content_of(db: &DatabaseService, id: &str) -> String {
+    db.get_node(id).await.unwrap().content
+}
+
+#[tokio::test]
+async fn test_import_roam_export() {
+    let (temp_dir, db) = setup().await;
+    let path = temp_dir.path().join("roam.json");
+    fs::write(&path, EXPORT).unwrap();
+
+    let summary = db.import_roam_json(&path, |_| {}).await.unwrap();
+    assert_eq!((summary.pages_created, summary.blocks_created), (3, 5));
+    assert_eq!(summary.unresolved_links, vec!["((gone))"]);
+
+    let page = db.get_node("alpha-page").await.unwrap();
+    assert_eq!(page.created_at.timestamp(), 1_700_000_000);
+    assert_eq!(page.updated_at.timestamp(), 1_700_000_900);
+    let task = db.get_node("ship-it").await.unwrap();
+    assert_eq!(task.content, "TODO Ship it");
+    assert_eq!((task.created_at.timestamp(), task.parent_id.as_deref()), (1_700_000_100, Some("alpha-page")));
+    assert_eq!(content_of(&db, "depends").await, "Depends on ((ship-it)) and ((gone))");
+
+    let journal = db.get_daily_note("2026-10-17").await.unwrap();
+    assert_eq!(db.get_node("daily-1").await.unwrap().parent_id, Some(journal.id.clone()));
+
+    let links: Vec<(String, String)> = sqlx::query_as("SELECT source_node_id, target_node_id FROM node_links ORDER BY 1")
+        .fetch_all(db.pool())
+        .await
+        .unwrap();
+    assert_eq!(links, vec![
+        ("daily-1".to_string(), "alpha-page".to_string()),
+        ("depends".to_string(), "ship-it".to_string()),
+        ("reading-1".to_string(), journal.id.clone()),
+    ]);
+}
+
+#[tokio::test]
+async fn test_import_roam_remaps_taken_uids() {
+    let (temp_dir, db) = setup().await;
+    let path = temp_dir.path().join("roam.json");
+    fs::write(&path, EXPORT).unwrap();
+    db.import_roam_json(&path, |_| {}).await.unwrap();
+
+    // The pages merge by title, and since the UIDs are taken the second
+    // copy's blocks get fresh IDs that its block refs follow
+    let summary = db.import_roam_json(&path, |_| {}).await.unwrap();
+    assert_eq!((summary.pages_created, summary.pages_merged), (0, 3));
+    let (copy_id, content): (String, String) = sqlx::query_as(
+        "SELECT id, content FROM nodes WHERE content LIKE 'Depends on%' AND id != 'depends'"
+    )
+    .fetch_one(db.pool())
+    .await
+    .unwrap();
+    let task_id: String = sqlx::query_scalar("SELECT id FROM nodes WHERE content = 'TODO Ship it' AND id != 'ship-it'")
+        .fetch_one(db.pool())
+        .await
+        .unwrap();
+    assert_eq!(content, format!("Depends on (({})) and ((gone))", task_id));
+
+    let targets: Vec<String> = sqlx::query_scalar("SELECT target_node_id FROM node_links WHERE source_node_id = ?")
+        .bind(&copy_id)
+        .fetch_all(db.pool())
+        .await
+        .unwrap();
+    assert_eq!(targets, vec![task_id]);
+
+    fs::write(&path, "{\"not\": \"roam\"}").unwrap();
+    assert!(db.import_roam_json(&path, |_| {}).await.is_err());
+}
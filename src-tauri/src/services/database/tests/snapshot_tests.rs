@@ -0,0 +1,90 @@
+use crate::models::{BackupSettings, UpdateNodeRequest};
+use crate::services::database::connection::DatabaseService;
+use super::{create_node, setup};
+use chrono::{Duration, TimeZone, Utc};
+use std::fs;
+
+#[tokio::test]
+async fn test_backup_settings() {
+    let (temp_dir, db) = setup().await;
+    assert_eq!(db.get_backup_settings().await.unwrap(), BackupSettings::default());
+    assert_eq!(db.backup_folder(&BackupSettings::default()), temp_dir.path().join("backups"));
+
+    let settings = BackupSettings { interval_minutes: 15, keep_weekly: 2, ..Default::default() };
+    db.set_backup_settings(&settings).await.unwrap();
+    assert_eq!(db.get_backup_settings().await.unwrap(), settings);
+    assert!(db.set_backup_settings(&BackupSettings { interval_minutes: 0, ..Default::default() }).await.is_err());
+}
+
+#[tokio::test]
+async fn test_scheduled_backups_rotate() {
+    let (temp_dir, db) = setup().await;
+    let folder = temp_dir.path().join("snapshots");
+    db.set_backup_settings(&BackupSettings {
+        folder: Some(folder.to_string_lossy().into_owned()),
+        keep_hourly: 2,
+        keep_daily: 1,
+        keep_weekly: 0,
+        ..Default::default()
+    }).await.unwrap();
+    create_node(&db, "Saved in a snapshot", None, 0, &[]).await;
+    fs::create_dir_all(&folder).unwrap();
+    fs::write(folder.join("notes.txt"), "not a snapshot").unwrap();
+
+    let start = Utc.with_ymd_and_hms(2026, 10, 18, 9, 0, 0).unwrap();
+    let first = db.run_scheduled_backup(start).await.unwrap().unwrap();
+    assert_eq!(first.file_name, "note-20261018T090000Z.db");
+    assert!(first.size > 0);
+    assert!(db.run_scheduled_backup(start + Duration::minutes(30)).await.unwrap().is_none());
+    for hours in 1..=3 {
+        assert!(db.run_scheduled_backup(start + Duration::hours(hours)).await.unwrap().is_some());
+    }
+
+    // Two hourly snapshots stay, and the day's newest is one of them
+    let names: Vec<String> = db.list_snapshots().await.unwrap().into_iter().map(|snapshot| snapshot.file_name).collect();
+    assert_eq!(names, vec!["note-20261018T120000Z.db", "note-20261018T110000Z.db"]);
+    assert!(folder.join("notes.txt").exists());
+
+    let snapshot = DatabaseService::new_test(folder.join(&names[0]).to_str().unwrap()).await.unwrap();
+    assert_eq!(snapshot.search_nodes("snapshot", 10).await.unwrap().len(), 1);
+
+    let mut settings = db.get_backup_settings().await.unwrap();
+    settings.enabled = false;
+    db.set_backup_settings(&settings).await.unwrap();
+    assert!(db.run_scheduled_backup(start + Duration::days(1)).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_restore_snapshot() {
+    let (_temp_dir, db) = setup().await;
+    let kept = create_node(&db, "Before the snapshot", None, 0, &[]).await;
+    let snapshot = db.create_snapshot().await.unwrap();
+
+    let added = create_node(&db, "Written afterwards", None, 0, &[]).await;
+    db.update_node(&kept.id, UpdateNodeRequest {
+        content: Some("Edited afterwards".to_string()),
+        parent_id: None,
+        order: None,
+        properties: None,
+        tags: None,
+    }).await.unwrap();
+    let settings = BackupSettings { keep_daily: 3, ..Default::default() };
+    db.set_backup_settings(&settings).await.unwrap();
+
+    // Let the safety snapshot get a name of its own
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+    let safety = db.restore_snapshot(&snapshot.file_name).await.unwrap();
+    assert_ne!(safety.file_name, snapshot.file_name);
+    assert_eq!(db.get_node(&kept.id).await.unwrap().content, "Before the snapshot");
+    assert!(db.get_node(&added.id).await.is_err());
+    assert_eq!(db.search_nodes("afterwards", 10).await.unwrap().len(), 0);
+    assert_eq!(db.get_backup_settings().await.unwrap(), settings);
+
+    // The state before the restore can be brought back in turn
+    db.restore_snapshot(&safety.file_name).await.unwrap();
+    assert_eq!(db.get_node(&kept.id).await.unwrap().content, "Edited afterwards");
+    assert_eq!(db.search_nodes("afterwards", 10).await.unwrap().len(), 2);
+
+    assert!(db.restore_snapshot("../test.db").await.is_err());
+    assert!(db.restore_snapshot("note-20000101T000000Z.db").await.is_err());
+}
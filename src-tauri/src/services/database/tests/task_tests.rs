@@ -0,0 +1,172 @@
+use crate::models::{Node, Priority, Task, TaskFilter, TaskState, UpdateNodeRequest};
+use crate::services::database::connection::DatabaseService;
+use super::{create_node, setup};
+use chrono::NaiveDate;
+
+async fn create(db: &DatabaseService, content: &str, parent_id: Option<&str>, tags: &[&str]) -> Node {
+    let node = create_node(db, content, parent_id, 0, tags).await;
+    db.index_node_content_at(&node, NaiveDate::from_ymd_opt(2026, 10, 17).unwrap()).await.unwrap()
+}
+
+fn ids(tasks: &[Task]) -> Vec<&str> {
+    tasks.iter().map(|task| task.node.id.as_str()).collect()
+}
+
+#[tokio::test]
+async fn test_markers_are_tracked_as_task_state() {
+    let (_temp_dir, db) = setup().await;
+
+    let page = create(&db, "Project", None, &[]).await;
+    let task = create(&db, "TODO write report", Some(&page.id), &[]).await;
+    create(&db, "Just a note about TODO lists", Some(&page.id), &[]).await;
+
+    let tasks = db.list_tasks(&TaskFilter::default()).await.unwrap();
+    assert_eq!(ids(&tasks), vec![task.id.as_str()]);
+    assert_eq!(tasks[0].state, TaskState::Todo);
+    assert_eq!(tasks[0].page_id, page.id);
+}
+
+#[tokio::test]
+async fn test_cycle_task_state_records_transitions() {
+    let (_temp_dir, db) = setup().await;
+
+    let node = create(&db, "write report", None, &[]).await;
+    let node = db.cycle_task_state(&node.id).await.unwrap();
+    assert_eq!(node.content, "TODO write report");
+    let node = db.cycle_task_state(&node.id).await.unwrap();
+    assert_eq!(node.content, "DOING write report");
+    let node = db.cycle_task_state(&node.id).await.unwrap();
+    assert_eq!(node.content, "DONE write report");
+    let node = db.set_task_state(&node.id, None).await.unwrap();
+    assert_eq!(node.content, "write report");
+
+    let history: Vec<(Option<TaskState>, Option<TaskState>)> = db
+        .get_task_history(&node.id)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|transition| (transition.from_state, transition.to_state))
+        .collect();
+    assert_eq!(
+        history,
+        vec![
+            (None, Some(TaskState::Todo)),
+            (Some(TaskState::Todo), Some(TaskState::Doing)),
+            (Some(TaskState::Doing), Some(TaskState::Done)),
+            (Some(TaskState::Done), None),
+        ]
+    );
+    assert!(db.list_tasks(&TaskFilter::default()).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_list_tasks_filters() {
+    let (_temp_dir, db) = setup().await;
+
+    let work = create(&db, "Work", None, &[]).await;
+    let home = create(&db, "Home", None, &[]).await;
+    let report = create(&db, "TODO report\ndue:: 2026-10-20", Some(&work.id), &["urgent"]).await;
+    let review = create(&db, "DOING review\ndue:: 2026-10-30", Some(&work.id), &[]).await;
+    let dishes = create(&db, "DONE dishes", Some(&home.id), &["urgent"]).await;
+
+    let all = db.list_tasks(&TaskFilter::default()).await.unwrap();
+    assert_eq!(ids(&all), vec![report.id.as_str(), review.id.as_str(), dishes.id.as_str()]);
+    assert_eq!(all[0].due, NaiveDate::from_ymd_opt(2026, 10, 20));
+
+    let open = db.list_tasks(&TaskFilter {
+        states: Some(vec![TaskState::Todo, TaskState::Doing]),
+        ..Default::default()
+    }).await.unwrap();
+    assert_eq!(ids(&open), vec![report.id.as_str(), review.id.as_str()]);
+
+    let on_home = db.list_tasks(&TaskFilter { page_id: Some(home.id.clone()), ..Default::default() }).await.unwrap();
+    assert_eq!(ids(&on_home), vec![dishes.id.as_str()]);
+
+    let urgent = db.list_tasks(&TaskFilter { tag: Some("urgent".to_string()), ..Default::default() }).await.unwrap();
+    assert_eq!(ids(&urgent), vec![report.id.as_str(), dishes.id.as_str()]);
+
+    let due_soon = db.list_tasks(&TaskFilter {
+        due_before: NaiveDate::from_ymd_opt(2026, 10, 25),
+        ..Default::default()
+    }).await.unwrap();
+    assert_eq!(ids(&due_soon), vec![report.id.as_str()]);
+}
+
+#[tokio::test]
+async fn test_completing_a_repeating_task_advances_it() {
+    let (_temp_dir, db) = setup().await;
+
+    let node = create(&db, "TODO water plants\nSCHEDULED: <2026-10-17 Sat .+3d>", None, &[]).await;
+    let done = db.update_node(&node.id, UpdateNodeRequest {
+        content: Some(node.content.replacen("TODO", "DONE", 1)),
+        parent_id: None,
+        order: None,
+        properties: None,
+        tags: None,
+    }).await.unwrap();
+    let today = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+    let advanced = db.index_node_content_at(&done, today).await.unwrap();
+
+    assert_eq!(advanced.content, "TODO water plants\nSCHEDULED: <2026-10-21 Wed .+3d>");
+    assert_eq!(advanced.properties.get("scheduled"), Some(&serde_json::json!("2026-10-21")));
+    assert_eq!(db.get_task_state(&node.id).await.unwrap(), Some(TaskState::Todo));
+
+    let completions = db.get_task_completions(&node.id).await.unwrap();
+    assert_eq!(completions.len(), 1);
+    assert_eq!(completions[0].scheduled_for, NaiveDate::from_ymd_opt(2026, 10, 17));
+
+    let states: Vec<_> = db.get_task_history(&node.id).await.unwrap().into_iter().map(|t| t.to_state).collect();
+    assert_eq!(states, vec![Some(TaskState::Todo), Some(TaskState::Done), Some(TaskState::Todo)]);
+}
+
+#[tokio::test]
+async fn test_completing_a_plain_task_is_logged() {
+    let (_temp_dir, db) = setup().await;
+
+    let node = create(&db, "TODO file taxes\ndue:: 2026-10-20", None, &[]).await;
+    let node = db.set_task_state(&node.id, Some(TaskState::Done)).await.unwrap();
+    assert_eq!(node.content, "DONE file taxes\ndue:: 2026-10-20");
+
+    let completions = db.get_task_completions(&node.id).await.unwrap();
+    assert_eq!(completions.len(), 1);
+    assert_eq!(completions[0].scheduled_for, NaiveDate::from_ymd_opt(2026, 10, 20));
+}
+
+#[tokio::test]
+async fn test_task_plan_sorts_by_priority_deadline_and_effort() {
+    let (_temp_dir, db) = setup().await;
+
+    let work = create(&db, "Work", None, &[]).await;
+    let home = create(&db, "Home", None, &[]).await;
+    let slow = create(&db, "TODO [#B] slow\neffort:: 3h\nDEADLINE: <2026-10-20>", Some(&work.id), &[]).await;
+    let quick = create(&db, "TODO [#B] quick\neffort:: 30m\nDEADLINE: <2026-10-20>", Some(&work.id), &[]).await;
+    let urgent = create(&db, "DOING [#A] urgent", Some(&home.id), &[]).await;
+    let soon = create(&db, "TODO [#B] soon\ndue:: 2026-10-18", Some(&work.id), &[]).await;
+    let plain = create(&db, "TODO someday", Some(&home.id), &[]).await;
+    create(&db, "DONE [#A] finished", Some(&work.id), &[]).await;
+
+    let tasks = db.list_tasks(&TaskFilter::default()).await.unwrap();
+    let slow_task = tasks.iter().find(|task| task.node.id == slow.id).unwrap();
+    assert_eq!(slow_task.priority, Some(Priority::B));
+    assert_eq!(slow_task.effort_minutes, Some(180));
+    assert_eq!(slow_task.deadline, NaiveDate::from_ymd_opt(2026, 10, 20));
+
+    let plan = db.get_task_plan(&TaskFilter::default()).await.unwrap();
+    let pages: Vec<&str> = plan.iter().map(|group| group.page.content.as_str()).collect();
+    assert_eq!(pages, vec!["Home", "Work"]);
+    assert_eq!(ids(&plan[0].tasks), vec![urgent.id.as_str(), plain.id.as_str()]);
+    assert_eq!(ids(&plan[1].tasks), vec![soon.id.as_str(), quick.id.as_str(), slow.id.as_str()]);
+
+    // Priority changes are picked up on the next edit
+    let edited = db.update_node(&plain.id, UpdateNodeRequest {
+        content: Some("TODO [#C] someday".to_string()),
+        parent_id: None,
+        order: None,
+        properties: None,
+        tags: None,
+    }).await.unwrap();
+    db.sync_task_state(&edited).await.unwrap();
+    let tasks = db.list_tasks(&TaskFilter::default()).await.unwrap();
+    let plain_task = tasks.iter().find(|task| task.node.id == plain.id).unwrap();
+    assert_eq!(plain_task.priority, Some(Priority::C));
+}
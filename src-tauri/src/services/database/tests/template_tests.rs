@@ -0,0 +1,143 @@
+use crate::models::{Node, TaskState};
+use chrono::NaiveDate;
+use crate::services::database::connection::DatabaseService;
+use super::{create_node, create_node_with_properties, setup};
+use std::collections::HashMap;
+
+async fn create_template(db: &DatabaseService, name: &str) -> Node {
+    let mut properties = HashMap::new();
+    properties.insert("template".to_string(), serde_json::json!(name));
+    create_node_with_properties(db, &format!("{} template", name), None, 0, properties, &[]).await
+}
+
+async fn journal_count(db: &DatabaseService) -> i64 {
+    sqlx::query_scalar("SELECT COUNT(*) FROM journals").fetch_one(db.pool()).await.unwrap()
+}
+
+#[tokio::test]
+async fn test_daily_template_is_copied_into_new_journal_pages() {
+    let (_temp_dir, db) = setup().await;
+
+    let template = create_template(&db, "daily").await;
+    let plan = create_node(&db, "Plan for {{weekday}} {{date}}", Some(&template.id), 0, &[]).await;
+    create_node(&db, "Review {{yesterday}}", Some(&plan.id), 0, &[]).await;
+    create_node(&db, "Gratitude", Some(&template.id), 1, &[]).await;
+
+    let page = db.get_or_create_daily_note("2026-10-17").await.unwrap();
+    let tree = db.get_node_with_children(&page.id).await.unwrap();
+
+    let top: Vec<&str> = tree.child_nodes.iter().map(|c| c.node.content.as_str()).collect();
+    assert_eq!(top, vec!["Plan for Saturday 2026-10-17", "Gratitude"]);
+    assert_eq!(tree.child_nodes[0].child_nodes[0].node.content, "Review [[Oct 16th, 2026]]");
+
+    // The template itself is untouched and opening the page again copies nothing
+    let again = db.get_or_create_daily_note("2026-10-17").await.unwrap();
+    assert_eq!(again.children.len(), 2);
+    assert_eq!(db.get_node(&template.id).await.unwrap().children.len(), 2);
+}
+
+#[tokio::test]
+async fn test_apply_template_appends_to_any_page() {
+    let (_temp_dir, db) = setup().await;
+
+    let template = create_template(&db, "meeting").await;
+    let mut properties = HashMap::new();
+    properties.insert("when".to_string(), serde_json::json!("{{date}}"));
+    properties.insert("template".to_string(), serde_json::json!("nested"));
+    create_node_with_properties(&db, "Attendees", Some(&template.id), 0, properties, &["meeting"]).await;
+
+    let journal = db.get_or_create_daily_note("2026-03-02").await.unwrap();
+    create_node(&db, "Existing block", Some(&journal.id), 0, &[]).await;
+
+    let created = db.apply_template(&template.id, &journal.id).await.unwrap();
+
+    assert_eq!(created.len(), 1);
+    assert_eq!(created[0].order, 1);
+    assert_eq!(created[0].tags, vec!["meeting"]);
+    assert_eq!(created[0].properties.get("when"), Some(&serde_json::json!("2026-03-02")));
+    assert!(!created[0].properties.contains_key("template"));
+    assert_eq!(db.get_node(&journal.id).await.unwrap().children.len(), 2);
+}
+
+#[tokio::test]
+async fn test_template_links_are_resolved() {
+    let (_temp_dir, db) = setup().await;
+
+    let yesterday = db.get_or_create_daily_note("2026-10-16").await.unwrap();
+    let template = create_template(&db, "standup").await;
+    create_node(&db, "Continue from {{yesterday}}", Some(&template.id), 0, &[]).await;
+    let target = create_node(&db, "Standup notes", None, 1, &[]).await;
+
+    let date = NaiveDate::from_ymd_opt(2026, 10, 17).unwrap();
+    let created = db.apply_template_for_date(&template.id, &target.id, date).await.unwrap();
+    assert_eq!(created[0].content, format!("Continue from [[{}]]", yesterday.content));
+
+    let targets: Vec<String> = sqlx::query_scalar("SELECT target_node_id FROM node_links WHERE source_node_id = ?")
+        .bind(&created[0].id)
+        .fetch_all(db.pool())
+        .await
+        .unwrap();
+    assert_eq!(targets, vec![yesterday.id]);
+}
+
+#[tokio::test]
+async fn test_templates_can_be_marked_in_content() {
+    let (_temp_dir, db) = setup().await;
+
+    let template = create_node(&db, "Daily plan\ntemplate:: daily", None, 0, &[]).await;
+    create_node(&db, "Plan for {{weekday}}", Some(&template.id), 0, &[]).await;
+    create_node(&db, "Nested\nTemplate:: nested", Some(&template.id), 1, &[]).await;
+    create_node(&db, "Notes on template:: syntax", None, 1, &[]).await;
+
+    let names: Vec<String> = db.list_templates().await.unwrap().into_iter().map(|node| node.content).collect();
+    assert_eq!(names, vec!["Daily plan\ntemplate:: daily", "Nested\nTemplate:: nested"]);
+    assert_eq!(db.find_template("daily").await.unwrap().map(|node| node.id), Some(template.id.clone()));
+
+    let page = db.get_or_create_daily_note("2026-10-17").await.unwrap();
+    let tree = db.get_node_with_children(&page.id).await.unwrap();
+    let copied: Vec<&str> = tree.child_nodes.iter().map(|c| c.node.content.as_str()).collect();
+    assert_eq!(copied, vec!["Plan for Saturday", "Nested"]);
+}
+
+#[tokio::test]
+async fn test_copied_blocks_are_indexed() {
+    let (_temp_dir, db) = setup().await;
+
+    let daily = create_template(&db, "daily").await;
+    create_node(&db, "TODO Water plants\ndue:: 2026-10-20", Some(&daily.id), 0, &[]).await;
+    create_node(&db, "Prepare {{tomorrow}}", Some(&daily.id), 1, &[]).await;
+
+    // Copies into a new journal page do not create the pages they mention
+    let page = db.get_or_create_daily_note("2026-10-17").await.unwrap();
+    let task = db.get_node(&page.children[0]).await.unwrap();
+    assert_eq!(db.get_task_state(&task.id).await.unwrap(), Some(TaskState::Todo));
+    assert_eq!(task.properties.get("due"), Some(&serde_json::json!("2026-10-20")));
+    assert_eq!(journal_count(&db).await, 1);
+
+    // Applying a template by hand creates them, and stops at their own template
+    let meeting = create_template(&db, "meeting").await;
+    create_node(&db, "TODO Send notes {{tomorrow}}", Some(&meeting.id), 0, &[]).await;
+    let target = create_node(&db, "Kickoff", None, 2, &[]).await;
+    let date = NaiveDate::from_ymd_opt(2026, 10, 17).unwrap();
+    let created = db.apply_template_for_date(&meeting.id, &target.id, date).await.unwrap();
+
+    assert_eq!(db.get_task_state(&created[0].id).await.unwrap(), Some(TaskState::Todo));
+    assert_eq!(journal_count(&db).await, 2);
+    let tomorrow = db.get_daily_note("2026-10-18").await.unwrap();
+    assert_eq!(tomorrow.children.len(), 2);
+    let targets: Vec<String> = sqlx::query_scalar("SELECT target_node_id FROM node_links WHERE source_node_id = ?")
+        .bind(&created[0].id)
+        .fetch_all(db.pool())
+        .await
+        .unwrap();
+    assert_eq!(targets, vec![tomorrow.id]);
+}
+
+#[tokio::test]
+async fn test_template_cannot_target_itself() {
+    let (_temp_dir, db) = setup().await;
+
+    let template = create_template(&db, "daily").await;
+    assert!(db.apply_template(&template.id, &template.id).await.is_err());
+    assert_eq!(db.list_templates().await.unwrap().len(), 1);
+}
@@ -0,0 +1,80 @@
+use crate::services::database::connection::DatabaseService;
+use super::{create_node, setup};
+
+async fn create_old_node(db: &DatabaseService, content: &str, parent_id: Option<String>, updated_at: &str) -> String {
+    let node = create_node(db, content, parent_id.as_deref(), 0, &[]).await;
+    sqlx::query("UPDATE nodes SET updated_at = ? WHERE id = ?")
+        .bind(updated_at)
+        .bind(&node.id)
+        .execute(db.pool())
+        .await
+        .unwrap();
+    node.id
+}
+
+#[tokio::test]
+async fn test_on_this_day_returns_earlier_years() {
+    let (_temp_dir, db) = setup().await;
+
+    for date in ["2024-10-17", "2025-10-17", "2026-10-17", "2025-10-18", "2027-10-17"] {
+        db.get_or_create_daily_note(date).await.unwrap();
+    }
+
+    let entries = db.get_on_this_day("2026-10-17").await.unwrap();
+    let dates: Vec<String> = entries.iter().map(|e| e.date.to_string()).collect();
+    assert_eq!(dates, vec!["2025-10-17", "2024-10-17"]);
+}
+
+#[tokio::test]
+async fn test_on_this_day_includes_leap_day_on_february_28th() {
+    let (_temp_dir, db) = setup().await;
+
+    db.get_or_create_daily_note("2024-02-29").await.unwrap();
+    db.get_or_create_daily_note("2024-02-28").await.unwrap();
+
+    let common_year = db.get_on_this_day("2026-02-28").await.unwrap();
+    let dates: Vec<String> = common_year.iter().map(|e| e.date.to_string()).collect();
+    assert_eq!(dates, vec!["2024-02-29", "2024-02-28"]);
+
+    let leap_year = db.get_on_this_day("2028-02-28").await.unwrap();
+    assert_eq!(leap_year.len(), 1);
+}
+
+#[tokio::test]
+async fn test_resurface_skips_recent_and_recently_viewed_nodes() {
+    let (_temp_dir, db) = setup().await;
+
+    let journal = db.get_or_create_daily_note("2020-01-01").await.unwrap();
+    sqlx::query("UPDATE nodes SET updated_at = '2020-01-01 00:00:00' WHERE id = ?")
+        .bind(&journal.id)
+        .execute(db.pool())
+        .await
+        .unwrap();
+
+    let old = create_old_node(&db, "An old idea", Some(journal.id.clone()), "2020-01-01 10:00:00").await;
+    let viewed = create_old_node(&db, "Seen yesterday", None, "2020-01-01 10:00:00").await;
+    create_old_node(&db, "Fresh thought", None, "2999-01-01 00:00:00").await;
+    create_old_node(&db, "   ", None, "2020-01-01 10:00:00").await;
+    db.record_node_view(&viewed).await.unwrap();
+
+    let picks = db.resurface_nodes(10, 30, 7, Some(1)).await.unwrap();
+
+    assert_eq!(picks.len(), 1);
+    assert_eq!(picks[0].node.id, old);
+    assert_eq!(picks[0].journal_date.map(|d| d.to_string()), Some("2020-01-01".to_string()));
+    assert!(picks[0].days_since_touched > 30.0);
+}
+
+#[tokio::test]
+async fn test_resurface_is_reproducible_with_a_seed() {
+    let (_temp_dir, db) = setup().await;
+
+    for i in 0..20 {
+        create_old_node(&db, &format!("Note {}", i), None, &format!("20{:02}-01-01 00:00:00", i)).await;
+    }
+
+    let first: Vec<String> = db.resurface_nodes(3, 30, 7, Some(99)).await.unwrap().into_iter().map(|r| r.node.id).collect();
+    let second: Vec<String> = db.resurface_nodes(3, 30, 7, Some(99)).await.unwrap().into_iter().map(|r| r.node.id).collect();
+    assert_eq!(first.len(), 3);
+    assert_eq!(first, second);
+}
@@ -0,0 +1,127 @@
+use crate::errors::{AppError, AppResult};
+use super::connection::DatabaseService;
+use crate::models::{JournalEntry, ResurfacedNode};
+use crate::utils::{format_iso_date, parse_iso_date, weighted_sample};
+use chrono::{Datelike, NaiveDate, Utc};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use sqlx::Row;
+
+/// Upper bound on the candidates considered for one resurfacing draw; the
+/// longest left alone are kept, as they are the likeliest picks anyway
+const RESURFACE_CANDIDATE_LIMIT: i64 = 2000;
+
+impl DatabaseService {
+    /// Journal pages written on the same month and day in earlier years, newest first.
+    ///
+    /// On February 28th of a common year, leap-day entries are included too.
+    pub async fn get_on_this_day(&self, date: &str) -> AppResult<Vec<JournalEntry>> {
+        let date = parse_iso_date(date)?;
+        let month_day = date.format("%m-%d").to_string();
+        let leap_day = if date.month() == 2 && date.day() == 28 && NaiveDate::from_ymd_opt(date.year(), 2, 29).is_none() {
+            "02-29".to_string()
+        } else {
+            month_day.clone()
+        };
+
+        let rows = sqlx::query(
+            r#"
+            SELECT journal_date, node_id FROM journals
+            WHERE substr(journal_date, 6) IN (?, ?) AND journal_date < ?
+            ORDER BY journal_date DESC
+            "#
+        )
+        .bind(&month_day)
+        .bind(&leap_day)
+        .bind(format_iso_date(date))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+
+        let mut entries = Vec::new();
+        for row in rows {
+            let date = parse_iso_date(&row.get::<String, _>("journal_date"))?;
+            let node = self.get_node(&row.get::<String, _>("node_id")).await?;
+            entries.push(JournalEntry { date, node });
+        }
+        Ok(entries)
+    }
+
+    /// Remember that the user just looked at a node
+    pub async fn record_node_view(&self, node_id: &str) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO node_views (node_id, last_viewed_at, view_count) VALUES (?, ?, 1)
+            ON CONFLICT(node_id) DO UPDATE SET
+                last_viewed_at = excluded.last_viewed_at,
+                view_count = view_count + 1
+            "#
+        )
+        .bind(node_id)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Draw old nodes to resurface.
+    ///
+    /// Candidates were last edited at least `min_age_days` ago and not viewed
+    /// in the last `cooldown_days`; the longer a node has been left alone, the
+    /// more likely it is picked. Pass a `seed` for a reproducible draw.
+    pub async fn resurface_nodes(
+        &self,
+        count: usize,
+        min_age_days: i64,
+        cooldown_days: i64,
+        seed: Option<u64>,
+    ) -> AppResult<Vec<ResurfacedNode>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT n.id,
+                   julianday('now') - MAX(julianday(n.updated_at), COALESCE(julianday(v.last_viewed_at), 0)) AS idle_days
+            FROM nodes n
+            LEFT JOIN node_views v ON v.node_id = n.id
+            WHERE julianday('now') - julianday(n.updated_at) >= ?
+              AND (v.last_viewed_at IS NULL OR julianday('now') - julianday(v.last_viewed_at) >= ?)
+              AND trim(n.content) != ''
+              AND n.id NOT IN (SELECT node_id FROM journals)
+              AND n.id NOT IN (SELECT node_id FROM periodic_notes)
+            ORDER BY MAX(julianday(n.updated_at), COALESCE(julianday(v.last_viewed_at), 0)), n.id
+            LIMIT ?
+            "#
+        )
+        .bind(min_age_days)
+        .bind(cooldown_days)
+        .bind(RESURFACE_CANDIDATE_LIMIT)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::DatabaseQueryFailed(e.to_string()))?;
+
+        // The query's order is stable, so a seeded draw is reproducible
+        let candidates: Vec<(String, f64)> = rows
+            .into_iter()
+            .map(|row| (row.get("id"), row.get::<Option<f64>, _>("idle_days").unwrap_or(0.0)))
+            .collect();
+        let weights: Vec<f64> = candidates.iter().map(|(_, idle_days)| *idle_days).collect();
+
+        let mut rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        let picks = weighted_sample(&weights, count, &mut rng);
+
+        let mut resurfaced = Vec::new();
+        for index in picks {
+            let (id, idle_days) = &candidates[index];
+            resurfaced.push(ResurfacedNode {
+                node: self.get_node(id).await?,
+                journal_date: self.get_containing_journal_date(id).await?,
+                days_since_touched: *idle_days,
+            });
+        }
+        Ok(resurfaced)
+    }
+}
@@ -0,0 +1,384 @@
+use crate::errors::{AppError, AppResult};
+use super::database::connection::DatabaseService;
+use super::database::mirror::{mirror_page_path, MirrorPage, MIRROR_PAGES_DIR};
+use crate::models::{BlockBlame, GitCommit, GitSettings, Node};
+use crate::utils::parse_markdown_page;
+use chrono::{TimeZone, Utc};
+use git2::{Commit, Delta, IndexAddOption, Oid, Repository, Signature, Sort, Tree};
+use regex::Regex;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const GIT_SETTINGS_PREFERENCE: &str = "git_settings";
+
+/// How often `run_git_mirror` checks whether a commit is due
+const GIT_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Mirrors the graph into a Git working tree, one Markdown file per page,
+/// and commits the changes once edits settle down
+#[derive(Clone)]
+pub struct GitManager {
+    db: DatabaseService,
+    /// When the graph last changed without being committed
+    last_change: Arc<Mutex<Option<Instant>>>,
+    /// Keeps the scheduler and manual commits from writing at the same time
+    commit_lock: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl GitManager {
+    pub fn new(db: DatabaseService) -> Self {
+        GitManager {
+            db,
+            last_change: Arc::new(Mutex::new(None)),
+            commit_lock: Arc::new(tokio::sync::Mutex::new(())),
+        }
+    }
+
+    pub async fn get_settings(&self) -> AppResult<GitSettings> {
+        let value = self.db.get_user_preference(GIT_SETTINGS_PREFERENCE).await?;
+        Ok(value.and_then(|value| serde_json::from_value(value).ok()).unwrap_or_default())
+    }
+
+    pub async fn set_settings(&self, settings: &GitSettings) -> AppResult<()> {
+        if settings.author_name.trim().is_empty() || settings.author_email.trim().is_empty() {
+            return Err(AppError::ConfigurationError("Git commits need an author name and email".to_string()));
+        }
+        self.db.set_user_preference(GIT_SETTINGS_PREFERENCE, serde_json::to_value(settings)?).await
+    }
+
+    /// Working tree of the mirror under `settings`
+    pub fn repository_dir(&self, settings: &GitSettings) -> PathBuf {
+        match settings.folder.as_deref().filter(|folder| !folder.trim().is_empty()) {
+            Some(folder) => PathBuf::from(folder),
+            None => self.db.db_path.with_file_name("repository"),
+        }
+    }
+
+    /// Note that the graph changed. The change is committed once no further
+    /// ones have come in for the debounce period.
+    pub fn record_change(&self) {
+        *self.last_change.lock().unwrap() = Some(Instant::now());
+    }
+
+    /// Commit the recorded changes if automatic commits are on and the
+    /// graph has been quiet for the debounce period as of `now`
+    pub async fn commit_if_due(&self, now: Instant) -> AppResult<Option<GitCommit>> {
+        let settings = self.get_settings().await?;
+        let debounce = Duration::from_secs(settings.debounce_seconds);
+        {
+            let mut last_change = self.last_change.lock().unwrap();
+            let due = last_change.is_some_and(|last| now.saturating_duration_since(last) >= debounce);
+            if !settings.enabled || !due {
+                return Ok(None);
+            }
+            // Edits from here on are left for the next commit
+            *last_change = None;
+        }
+
+        let result = self.commit_mirror().await;
+        if result.is_err() {
+            self.last_change.lock().unwrap().get_or_insert(now);
+        }
+        result
+    }
+
+    /// Write every page to the mirror and commit whatever changed since the
+    /// last commit, creating the repository on first use. Returns the new
+    /// commit, or `None` when the mirror was already up to date.
+    pub async fn commit_mirror(&self) -> AppResult<Option<GitCommit>> {
+        let _guard = self.commit_lock.lock().await;
+        let settings = self.get_settings().await?;
+        let dir = self.repository_dir(&settings);
+        let pages = self.db.render_mirror_pages().await?;
+        tokio::task::spawn_blocking(move || commit_pages(&dir, &pages, &settings))
+            .await
+            .map_err(|e| AppError::GitCommitFailed(e.to_string()))?
+    }
+
+    /// Commits that changed page `page_id`, newest first
+    pub async fn page_history(&self, page_id: &str) -> AppResult<Vec<GitCommit>> {
+        let dir = self.repository_dir(&self.get_settings().await?);
+        let path = page_file(page_id)?;
+        tokio::task::spawn_blocking(move || page_history(&dir, &path))
+            .await
+            .map_err(history_failed)?
+    }
+
+    /// The commit that last changed each block of page `page_id` as of the
+    /// latest commit, in page order. The page's own lines (title, tags and
+    /// properties) are blamed under the page's ID.
+    pub async fn blame_page(&self, page_id: &str) -> AppResult<Vec<BlockBlame>> {
+        let dir = self.repository_dir(&self.get_settings().await?);
+        let path = page_file(page_id)?;
+        let page_id = page_id.to_string();
+        tokio::task::spawn_blocking(move || blame_page(&dir, &page_id, &path))
+            .await
+            .map_err(history_failed)?
+    }
+
+    /// Bring page `page_id` back to how it was in commit `commit_id`, a full
+    /// or abbreviated hash or any other revision Git understands. The
+    /// restore is committed like any other edit.
+    pub async fn restore_page(&self, page_id: &str, commit_id: &str) -> AppResult<Node> {
+        let dir = self.repository_dir(&self.get_settings().await?);
+        let path = page_file(page_id)?;
+        let revision = commit_id.to_string();
+        let text = tokio::task::spawn_blocking(move || page_at_commit(&dir, &path, &revision))
+            .await
+            .map_err(history_failed)??;
+
+        let mut page = parse_markdown_page(page_id, &text);
+        page.id = Some(page_id.to_string());
+        let restored = self.db.restore_mirror_page(page).await;
+        // A restore whose indexing failed has still changed the page
+        self.record_change();
+        restored
+    }
+
+    /// Keep the mirror committed for as long as the app runs. Whatever
+    /// changed while the app was closed, or through imports and other paths
+    /// that do not record changes, is committed shortly after startup.
+    pub async fn run_git_mirror(&self) {
+        self.record_change();
+        loop {
+            match self.commit_if_due(Instant::now()).await {
+                Ok(Some(commit)) => tracing::info!("Committed to the Git mirror: {}", commit.message.lines().next().unwrap_or_default()),
+                Ok(None) => {}
+                Err(e) => tracing::warn!("Git mirror commit failed: {}", e),
+            }
+            tokio::time::sleep(GIT_CHECK_INTERVAL).await;
+        }
+    }
+}
+
+fn commit_failed(e: impl std::fmt::Display) -> AppError {
+    AppError::GitCommitFailed(e.to_string())
+}
+
+fn history_failed(e: impl std::fmt::Display) -> AppError {
+    AppError::GitHistoryFailed(e.to_string())
+}
+
+/// Path of page `page_id`'s file, refusing IDs that would leave the pages folder
+fn page_file(page_id: &str) -> AppResult<String> {
+    if page_id.trim().is_empty() || page_id.contains(['/', '\\']) || page_id.starts_with('.') {
+        return Err(AppError::InvalidPageData(format!("Invalid page ID: {}", page_id)));
+    }
+    Ok(mirror_page_path(page_id))
+}
+
+/// Open the repository at `dir`, creating it if there is none
+fn open_repository(dir: &Path) -> AppResult<Repository> {
+    match Repository::open(dir) {
+        Ok(repo) => Ok(repo),
+        Err(_) => {
+            fs::create_dir_all(dir).map_err(|e| AppError::GitInitFailed(e.to_string()))?;
+            Repository::init(dir).map_err(|e| AppError::GitInitFailed(e.to_string()))
+        }
+    }
+}
+
+/// Bring the working tree's page files in line with `pages` and commit
+/// them; files outside the pages folder are left alone
+fn commit_pages(dir: &Path, pages: &[MirrorPage], settings: &GitSettings) -> AppResult<Option<GitCommit>> {
+    let repo = open_repository(dir)?;
+    let pages_dir = dir.join(MIRROR_PAGES_DIR);
+    fs::create_dir_all(&pages_dir).map_err(commit_failed)?;
+
+    let mut written = HashSet::new();
+    for page in pages {
+        let path = dir.join(&page.path);
+        if fs::read_to_string(&path).ok().as_deref() != Some(page.contents.as_str()) {
+            fs::write(&path, &page.contents).map_err(commit_failed)?;
+        }
+        written.insert(path);
+    }
+    for entry in fs::read_dir(&pages_dir).map_err(commit_failed)? {
+        let path = entry.map_err(commit_failed)?.path();
+        if path.extension().is_some_and(|extension| extension == "md") && !written.contains(&path) {
+            fs::remove_file(&path).map_err(commit_failed)?;
+        }
+    }
+
+    let mut index = repo.index().map_err(commit_failed)?;
+    index.add_all([MIRROR_PAGES_DIR], IndexAddOption::DEFAULT, None).map_err(commit_failed)?;
+    index.update_all([MIRROR_PAGES_DIR], None).map_err(commit_failed)?;
+    index.write().map_err(commit_failed)?;
+    let tree = repo.find_tree(index.write_tree().map_err(commit_failed)?).map_err(commit_failed)?;
+
+    let head = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+    let head_tree = head.as_ref().map(Commit::tree).transpose().map_err(commit_failed)?;
+    let unchanged = match &head_tree {
+        Some(head_tree) => head_tree.id() == tree.id(),
+        None => tree.is_empty(),
+    };
+    if unchanged {
+        return Ok(None);
+    }
+
+    let message = commit_message(&repo, head_tree.as_ref(), &tree)?;
+    let signature = Signature::now(&settings.author_name, &settings.author_email).map_err(commit_failed)?;
+    let parents: Vec<&Commit> = head.iter().collect();
+    let id = repo
+        .commit(Some("HEAD"), &signature, &signature, &message, &tree, &parents)
+        .map_err(commit_failed)?;
+    let commit = repo.find_commit(id).map_err(commit_failed)?;
+    Ok(Some(git_commit(&commit)))
+}
+
+/// The app's view of a Git commit
+fn git_commit(commit: &Commit) -> GitCommit {
+    let author = commit.author();
+    GitCommit {
+        id: commit.id().to_string(),
+        message: commit.message().unwrap_or_default().trim_end().to_string(),
+        author: author.name().unwrap_or_default().to_string(),
+        committed_at: Utc.timestamp_opt(commit.time().seconds(), 0).single().unwrap_or_default(),
+    }
+}
+
+/// Title written in the `title::` line of a mirrored page
+fn mirror_title(contents: &[u8]) -> Option<String> {
+    let contents = std::str::from_utf8(contents).ok()?;
+    let title = contents.lines().next()?.strip_prefix("title::")?.trim();
+    (!title.is_empty()).then(|| title.to_string())
+}
+
+/// Describe the pages a commit adds, changes and removes: "Update Alpha"
+/// for a single page, otherwise a count with one line per page below it
+fn commit_message(repo: &Repository, old: Option<&Tree>, new: &Tree) -> AppResult<String> {
+    let diff = repo.diff_tree_to_tree(old, Some(new), None).map_err(commit_failed)?;
+    let mut changes: Vec<(usize, String)> = Vec::new();
+    for delta in diff.deltas() {
+        let (rank, file) = match delta.status() {
+            Delta::Added => (0, delta.new_file()),
+            Delta::Deleted => (2, delta.old_file()),
+            _ => (1, delta.new_file()),
+        };
+        let title = repo
+            .find_blob(file.id())
+            .ok()
+            .and_then(|blob| mirror_title(blob.content()))
+            .or_else(|| file.path().and_then(Path::file_stem).map(|stem| stem.to_string_lossy().into_owned()))
+            .unwrap_or_default();
+        changes.push((rank, title));
+    }
+    changes.sort();
+
+    const VERBS: [&str; 3] = ["Add", "Update", "Delete"];
+    let lines: Vec<String> = changes.iter().map(|(rank, title)| format!("{} {}", VERBS[*rank], title)).collect();
+    if lines.len() == 1 {
+        return Ok(lines[0].clone());
+    }
+    let verb = match changes.first() {
+        Some((rank, _)) if changes.iter().all(|(other, _)| other == rank) => VERBS[*rank],
+        _ => "Update",
+    };
+    Ok(format!("{} {} pages\n\n{}", verb, lines.len(), lines.join("\n")))
+}
+
+/// The existing repository at `dir`
+fn open_existing_repository(dir: &Path) -> AppResult<Repository> {
+    Repository::open(dir).map_err(|_| AppError::NotFound(format!("Git mirror at {}", dir.display())))
+}
+
+/// Blob of the file at `path` in `commit`, if it has one
+fn blob_at(commit: &Commit, path: &str) -> Option<Oid> {
+    commit.tree().ok()?.get_path(Path::new(path)).ok().map(|entry| entry.id())
+}
+
+fn page_history(dir: &Path, path: &str) -> AppResult<Vec<GitCommit>> {
+    let repo = open_existing_repository(dir)?;
+    let mut history = Vec::new();
+    if repo.head().is_err() {
+        return Ok(history);
+    }
+    let mut walk = repo.revwalk().map_err(history_failed)?;
+    walk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME).map_err(history_failed)?;
+    walk.push_head().map_err(history_failed)?;
+    for id in walk {
+        let commit = repo.find_commit(id.map_err(history_failed)?).map_err(history_failed)?;
+        let before = commit.parent(0).ok().and_then(|parent| blob_at(&parent, path));
+        if blob_at(&commit, path) != before {
+            history.push(git_commit(&commit));
+        }
+    }
+    Ok(history)
+}
+
+fn blame_page(dir: &Path, page_id: &str, path: &str) -> AppResult<Vec<BlockBlame>> {
+    let repo = open_existing_repository(dir)?;
+    let not_found = || AppError::NotFound(format!("Page {} in the Git mirror", page_id));
+    let head = repo.head().ok().and_then(|head| head.peel_to_commit().ok()).ok_or_else(not_found)?;
+    let blob = repo.find_blob(blob_at(&head, path).ok_or_else(not_found)?).map_err(history_failed)?;
+    let text = String::from_utf8_lossy(blob.content()).into_owned();
+    let blame = repo.blame_file(Path::new(path), None).map_err(history_failed)?;
+
+    let mut commits: HashMap<Oid, GitCommit> = HashMap::new();
+    let mut blames = Vec::new();
+    for (node_id, lines) in block_lines(page_id, &text) {
+        // Mirror history is linear, so the newest commit descends from the others
+        let mut newest: Option<Oid> = None;
+        for line in lines {
+            let Some(hunk) = blame.get_line(line + 1) else { continue };
+            let id = hunk.final_commit_id();
+            newest = match newest {
+                Some(current) if current == id || !repo.graph_descendant_of(id, current).map_err(history_failed)? => Some(current),
+                _ => Some(id),
+            };
+        }
+        let Some(id) = newest else { continue };
+        let commit = match commits.entry(id) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(git_commit(&repo.find_commit(id).map_err(history_failed)?)),
+        };
+        blames.push(BlockBlame { node_id, commit: commit.clone() });
+    }
+    Ok(blames)
+}
+
+/// Line numbers (from 0) of each block in a mirrored page, with the ID from
+/// the block's last `id::` line; the lines before the first block belong to
+/// the page itself
+fn block_lines(page_id: &str, text: &str) -> Vec<(String, Range<usize>)> {
+    let bullet = Regex::new(r"^\t*-( |$)").unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+    let mut starts: Vec<usize> = vec![0];
+    starts.extend((0..lines.len()).filter(|index| *index > 0 && bullet.is_match(lines[*index])));
+
+    let mut blocks = Vec::new();
+    for (position, start) in starts.iter().enumerate() {
+        let end = starts.get(position + 1).copied().unwrap_or(lines.len());
+        let id = if position == 0 && !bullet.is_match(lines.first().copied().unwrap_or_default()) {
+            Some(page_id.to_string())
+        } else {
+            lines[*start..end]
+                .iter()
+                .rev()
+                .find_map(|line| line.trim_start().strip_prefix("id::"))
+                .map(|id| id.trim().to_string())
+        };
+        if let Some(id) = id {
+            blocks.push((id, *start..end));
+        }
+    }
+    blocks
+}
+
+/// Contents of the file at `path` as of `revision`
+fn page_at_commit(dir: &Path, path: &str, revision: &str) -> AppResult<String> {
+    let repo = open_existing_repository(dir)?;
+    let commit = repo
+        .revparse_single(revision)
+        .and_then(|object| object.peel_to_commit())
+        .map_err(|_| AppError::NotFound(format!("Commit {}", revision)))?;
+    let blob_id = blob_at(&commit, path)
+        .ok_or_else(|| AppError::NotFound(format!("{} as of commit {}", path, revision)))?;
+    let blob = repo.find_blob(blob_id).map_err(history_failed)?;
+    String::from_utf8(blob.content().to_vec()).map_err(|e| AppError::InvalidPageData(e.to_string()))
+}
@@ -1,7 +1,9 @@
 use crate::errors::AppResult;
 use super::database::connection::DatabaseService;
 use crate::models::Node;
+use crate::utils::escape_like;
 use regex::Regex;
+use sqlx::SqliteConnection;
 
 pub struct LinkService {
     db: DatabaseService,
@@ -15,42 +17,11 @@ impl LinkService {
     /// Update all links for a given node
     pub async fn update_links_for_node(&self, node: &Node) -> AppResult<()> {
         let mut tx = self.db.pool().begin().await?;
-        
-        // Find all links in the node content
-        let link_regex = Regex::new(r"\[\[(.*?)\]\]").unwrap();
-        let link_texts: Vec<String> = link_regex
-            .captures_iter(&node.content)
-            .map(|cap| cap[1].to_string())
-            .collect();
-        
-        // Remove existing links for this source node
-        sqlx::query("DELETE FROM node_links WHERE source_node_id = ?")
-            .bind(&node.id)
-            .execute(&mut *tx)
-            .await?;
-        
-        // For each link text, find the corresponding node by content
-        for link_text in link_texts {
-            // Try to find a node whose content matches the link text
-            let result = sqlx::query_scalar::<_, String>(
-                "SELECT id FROM nodes WHERE content = ? OR content LIKE ? LIMIT 1"
-            )
-            .bind(&link_text)
-            .bind(format!("{}%", link_text))
-            .fetch_optional(&mut *tx)
-            .await?;
-            
-            if let Some(target_id) = result {
-                sqlx::query("INSERT OR IGNORE INTO node_links (source_node_id, target_node_id) VALUES (?, ?)")
-                    .bind(&node.id)
-                    .bind(&target_id)
-                    .execute(&mut *tx)
-                    .await?;
-            }
-            // If no node found, we just skip this link (unlinked reference)
-        }
-        
+        rebuild_node_links(&mut tx, &node.id, &node.content).await?;
         tx.commit().await?;
+
+        // Journal pages keep their automatic links to week/month/... pages
+        self.db.link_journal_to_periods(&node.id).await?;
         Ok(())
     }
 
@@ -91,4 +62,44 @@ impl LinkService {
         
         Ok(nodes)
     }
-}
\ No newline at end of file
+}
+
+/// Replace the outgoing links of a node with the `[[links]]` in its content,
+/// on a connection the caller may already hold a transaction on
+pub(crate) async fn rebuild_node_links(conn: &mut SqliteConnection, node_id: &str, content: &str) -> AppResult<()> {
+    // Find all links in the node content
+    let link_regex = Regex::new(r"\[\[(.*?)\]\]").unwrap();
+    let link_texts: Vec<String> = link_regex
+        .captures_iter(content)
+        .map(|cap| cap[1].to_string())
+        .collect();
+    
+    // Remove existing links for this source node
+    sqlx::query("DELETE FROM node_links WHERE source_node_id = ?")
+        .bind(node_id)
+        .execute(&mut *conn)
+        .await?;
+    
+    // For each link text, find the corresponding node by content
+    for link_text in link_texts {
+        // Try to find a node whose content matches the link text
+        let result = sqlx::query_scalar::<_, String>(
+            "SELECT id FROM nodes WHERE content = ? OR content LIKE ? ESCAPE '\\' LIMIT 1"
+        )
+        .bind(&link_text)
+        .bind(format!("{}%", escape_like(&link_text)))
+        .fetch_optional(&mut *conn)
+        .await?;
+        
+        if let Some(target_id) = result {
+            sqlx::query("INSERT OR IGNORE INTO node_links (source_node_id, target_node_id) VALUES (?, ?)")
+                .bind(node_id)
+                .bind(&target_id)
+                .execute(&mut *conn)
+                .await?;
+        }
+        // If no node found, we just skip this link (unlinked reference)
+    }
+
+    Ok(())
+}
@@ -4,8 +4,8 @@
 pub mod database;
 pub mod link_service;
 
-// Phase 2: Git manager (to be implemented)  
-// pub mod git_manager;
+// Phase 2: Git manager
+pub mod git_manager;
 
 // Phase 3: CRDT engine and WebSocket (to be implemented)
 // pub mod crdt_engine;
@@ -13,4 +13,5 @@ pub mod link_service;
 
 // Re-exports for easier access
 pub use database::connection::DatabaseService;
-pub use link_service::LinkService; 
\ No newline at end of file
+pub use link_service::LinkService;
+pub use git_manager::GitManager; 
\ No newline at end of file
@@ -0,0 +1,262 @@
+use chrono::{NaiveDate, Weekday};
+use regex::{Captures, Regex};
+use std::collections::{BTreeMap, HashSet};
+
+use super::{format_iso_date, format_journal_title, parse_date_expression, parse_date_reference, parse_iso_date, DateLocale};
+use crate::errors::AppError;
+
+/// A `key:: value` line: indentation, key and value
+const PROPERTY_LINE: &str = r"^(\s*)([A-Za-z][A-Za-z0-9_-]*)::[ \t]*(.*?)\s*$";
+
+/// Property keys whose values are read as dates (`due:: tomorrow`)
+pub const DATE_PROPERTY_KEYS: &[&str] = &["due", "scheduled", "deadline", "date", "start", "end", "remind"];
+
+/// Node content after date expressions were normalized
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedContent {
+    /// Content with `[[date]]` references pointing at journal titles and
+    /// date properties spelled as ISO dates
+    pub content: String,
+    /// Journal dates referenced through `[[...]]`, in order of appearance
+    pub journal_refs: Vec<NaiveDate>,
+    /// Date properties found in `key:: value` and planning lines
+    pub date_properties: BTreeMap<String, NaiveDate>,
+}
+
+/// `key:: value` lines in `content`, keyed by lowercased key
+pub fn parse_property_lines(content: &str) -> BTreeMap<String, String> {
+    let property_line = Regex::new(PROPERTY_LINE).unwrap();
+    content
+        .lines()
+        .filter_map(|line| property_line.captures(line))
+        .map(|caps| (caps[2].to_lowercase(), caps[3].to_string()))
+        .collect()
+}
+
+/// Normalize natural-language dates in node content.
+///
+/// `[[next friday]]` becomes a reference to that day's journal page and
+/// `due:: in 3 days` becomes `due:: 2026-10-20`. Org-style planning lines
+/// (`SCHEDULED: <2026-10-20 Tue>`, `DEADLINE: friday`) are spelled out the
+/// same way and recorded as `scheduled` / `deadline`. Text that does not
+/// parse as a date is left as it was, and so are references that name one
+/// of `pages` (lowercased page titles and aliases) or only read as a date
+/// loosely; see `parse_date_reference`.
+pub fn parse_content_dates(
+    content: &str,
+    today: NaiveDate,
+    locale: DateLocale,
+    title_format: &str,
+    pages: &HashSet<String>,
+) -> Result<ParsedContent, AppError> {
+    let property_line = Regex::new(PROPERTY_LINE).unwrap();
+    let reference = Regex::new(r"\[\[(.*?)\]\]").unwrap();
+
+    let mut date_properties = BTreeMap::new();
+    let mut lines = Vec::new();
+    for line in content.split('\n') {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        let caps = match property_line.captures(line) {
+            Some(caps) => caps,
+            None => {
+                match normalize_planning_line(line, today, locale) {
+                    Some((normalized, dates)) => {
+                        lines.push(normalized);
+                        date_properties.extend(dates);
+                    }
+                    None => lines.push(line.to_string()),
+                }
+                continue;
+            }
+        };
+
+        let key = caps[2].to_lowercase();
+        let value = caps[3].trim_start_matches("[[").trim_end_matches("]]");
+        match parse_date_expression(value, today, locale) {
+            Some(date) if DATE_PROPERTY_KEYS.contains(&key.as_str()) => {
+                lines.push(format!("{}{}:: {}", &caps[1], &caps[2], format_iso_date(date)));
+                date_properties.insert(key, date);
+            }
+            _ => lines.push(line.to_string()),
+        }
+    }
+    let content = lines.join("\n");
+
+    let mut journal_refs = Vec::new();
+    let mut error = None;
+    let content = reference
+        .replace_all(&content, |caps: &Captures| {
+            if pages.contains(&caps[1].to_lowercase()) {
+                return caps[0].to_string();
+            }
+            let Some(date) = parse_date_reference(&caps[1], today, locale) else {
+                return caps[0].to_string();
+            };
+            match format_journal_title(date, title_format) {
+                Ok(title) => {
+                    if !journal_refs.contains(&date) {
+                        journal_refs.push(date);
+                    }
+                    format!("[[{}]]", title)
+                }
+                Err(e) => {
+                    error.get_or_insert(e);
+                    caps[0].to_string()
+                }
+            }
+        })
+        .into_owned();
+
+    if let Some(e) = error {
+        return Err(e);
+    }
+    Ok(ParsedContent { content, journal_refs, date_properties })
+}
+
+/// Normalize a `SCHEDULED: ... DEADLINE: ...` planning line, returning
+/// `None` when `line` is not one
+fn normalize_planning_line(
+    line: &str,
+    today: NaiveDate,
+    locale: DateLocale,
+) -> Option<(String, Vec<(String, NaiveDate)>)> {
+    let keyword = Regex::new(r"\b(SCHEDULED|DEADLINE):").unwrap();
+    let body = line.trim_start();
+    if !body.starts_with("SCHEDULED:") && !body.starts_with("DEADLINE:") {
+        return None;
+    }
+    let indent = &line[..line.len() - body.len()];
+
+    let matches: Vec<_> = keyword.captures_iter(body).collect();
+    let mut parts = Vec::new();
+    let mut dates = Vec::new();
+    for (index, caps) in matches.iter().enumerate() {
+        let whole = caps.get(0).unwrap();
+        let end = matches.get(index + 1).map_or(body.len(), |next| next.get(0).unwrap().start());
+        let value = body[whole.end()..end].trim();
+        match normalize_planning_value(value, today, locale) {
+            Some((date, timestamp)) => {
+                parts.push(format!("{} {}", whole.as_str(), timestamp));
+                dates.push((caps[1].to_lowercase(), date));
+            }
+            None => parts.push(format!("{} {}", whole.as_str(), value).trim_end().to_string()),
+        }
+    }
+    Some((format!("{}{}", indent, parts.join(" ")), dates))
+}
+
+/// Read a planning value as an org timestamp (`<2026-10-20 Tue +1w>`),
+/// keeping anything after the date such as a time or repeater
+fn normalize_planning_value(value: &str, today: NaiveDate, locale: DateLocale) -> Option<(NaiveDate, String)> {
+    let inner = value
+        .trim_start_matches(['<', '['])
+        .trim_end_matches(['>', ']'])
+        .trim();
+    if let Some(date) = parse_date_expression(inner, today, locale) {
+        return Some((date, format!("<{} {}>", format_iso_date(date), date.format("%a"))));
+    }
+
+    let mut tokens = inner.split_whitespace();
+    let date = parse_iso_date(tokens.next()?).ok()?;
+    let mut timestamp = format!("{} {}", format_iso_date(date), date.format("%a"));
+    for token in tokens.filter(|token| token.parse::<Weekday>().is_err()) {
+        timestamp.push(' ');
+        timestamp.push_str(token);
+    }
+    Some((date, format!("<{}>", timestamp)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::DEFAULT_JOURNAL_TITLE_FORMAT;
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, 17).unwrap()
+    }
+
+    fn parse(content: &str) -> ParsedContent {
+        parse_content_dates(content, today(), DateLocale::EnUs, DEFAULT_JOURNAL_TITLE_FORMAT, &HashSet::new()).unwrap()
+    }
+
+    #[test]
+    fn test_parse_property_lines() {
+        let properties = parse_property_lines("Morning\nExercise:: yes\n  reading:: 30\nnot:a property\nempty::");
+        assert_eq!(properties.get("exercise").map(String::as_str), Some("yes"));
+        assert_eq!(properties.get("reading").map(String::as_str), Some("30"));
+        assert_eq!(properties.get("empty").map(String::as_str), Some(""));
+        assert_eq!(properties.len(), 3);
+    }
+
+    #[test]
+    fn test_references_become_journal_titles() {
+        let parsed = parse("Call Sam [[next friday]] about [[Project X]], see [[yesterday]]");
+        assert_eq!(parsed.content, "Call Sam [[Oct 23rd, 2026]] about [[Project X]], see [[Oct 16th, 2026]]");
+        assert_eq!(
+            parsed.journal_refs,
+            vec![NaiveDate::from_ymd_opt(2026, 10, 23).unwrap(), NaiveDate::from_ymd_opt(2026, 10, 16).unwrap()]
+        );
+        assert!(parsed.date_properties.is_empty());
+    }
+
+    #[test]
+    fn test_page_links_are_kept() {
+        let text = "[[Monday]] [[Sun]] [[now]] [[Next Week]] [[2.1]] [[3-4]] [[12/25]]";
+        let parsed = parse(text);
+        assert_eq!(parsed.content, text);
+        assert!(parsed.journal_refs.is_empty());
+
+        let pages = HashSet::from(["tomorrow".to_string()]);
+        let parsed = parse_content_dates("[[Tomorrow]]", today(), DateLocale::EnUs, DEFAULT_JOURNAL_TITLE_FORMAT, &pages).unwrap();
+        assert_eq!(parsed.content, "[[Tomorrow]]");
+        assert!(parsed.journal_refs.is_empty());
+    }
+
+    #[test]
+    fn test_date_properties_are_normalized() {
+        let parsed = parse("Ship it\ndue:: tomorrow\n  Scheduled:: [[in 3 days]]\nowner:: tomorrow\nend:: someday");
+        assert_eq!(parsed.content, "Ship it\ndue:: 2026-10-18\n  Scheduled:: 2026-10-20\nowner:: tomorrow\nend:: someday");
+        assert_eq!(parsed.date_properties.len(), 2);
+        assert_eq!(parsed.date_properties["due"], NaiveDate::from_ymd_opt(2026, 10, 18).unwrap());
+        assert_eq!(parsed.date_properties["scheduled"], NaiveDate::from_ymd_opt(2026, 10, 20).unwrap());
+    }
+
+    #[test]
+    fn test_normalizing_twice_is_stable() {
+        let once = parse("[[tomorrow]]\ndeadline:: next week");
+        let twice = parse_content_dates(
+            &once.content,
+            NaiveDate::from_ymd_opt(2027, 1, 1).unwrap(),
+            DateLocale::EnUs,
+            DEFAULT_JOURNAL_TITLE_FORMAT,
+            &HashSet::new(),
+        )
+        .unwrap();
+        assert_eq!(once.content, twice.content);
+        assert_eq!(once.date_properties, twice.date_properties);
+    }
+
+    #[test]
+    fn test_locale_is_used_for_numeric_dates() {
+        let parsed = parse_content_dates("due:: 3/4/2026", today(), DateLocale::EnGb, DEFAULT_JOURNAL_TITLE_FORMAT, &HashSet::new()).unwrap();
+        assert_eq!(parsed.content, "due:: 2026-04-03");
+    }
+
+    #[test]
+    fn test_planning_lines() {
+        let parsed = parse("TODO ship it\nSCHEDULED: tomorrow\n  DEADLINE: <2026-10-23 Fri 17:00 +1w>");
+        assert_eq!(
+            parsed.content,
+            "TODO ship it\nSCHEDULED: <2026-10-18 Sun>\n  DEADLINE: <2026-10-23 Fri 17:00 +1w>"
+        );
+        assert_eq!(parsed.date_properties["scheduled"], NaiveDate::from_ymd_opt(2026, 10, 18).unwrap());
+        assert_eq!(parsed.date_properties["deadline"], NaiveDate::from_ymd_opt(2026, 10, 23).unwrap());
+
+        let parsed = parse("SCHEDULED: <2026-10-20> DEADLINE: next friday");
+        assert_eq!(parsed.content, "SCHEDULED: <2026-10-20 Tue> DEADLINE: <2026-10-23 Fri>");
+
+        let parsed = parse("SCHEDULED: someday\nThe DEADLINE: is soft");
+        assert_eq!(parsed.content, "SCHEDULED: someday\nThe DEADLINE: is soft");
+        assert!(parsed.date_properties.is_empty());
+    }
+}
@@ -0,0 +1,83 @@
+/// Format one CSV record (RFC 4180), quoting fields that need it
+pub fn csv_record<S: AsRef<str>>(fields: &[S]) -> String {
+    let mut record = fields
+        .iter()
+        .map(|field| {
+            let field = field.as_ref();
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    record.push_str("\r\n");
+    record
+}
+
+/// Read CSV text (RFC 4180) into records of fields; a leading byte order
+/// mark and blank lines are skipped
+pub fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted => {
+                if chars.peek() == Some(&'"') {
+                    field.push('"');
+                    chars.next();
+                } else {
+                    quoted = false;
+                }
+            }
+            '"' if field.is_empty() => quoted = true,
+            ',' if !quoted => record.push(std::mem::take(&mut field)),
+            '\r' | '\n' if !quoted => {
+                if c == '\r' && chars.peek() == Some(&'\n') {
+                    chars.next();
+                }
+                record.push(std::mem::take(&mut field));
+                if record.len() > 1 || !record[0].is_empty() {
+                    records.push(std::mem::take(&mut record));
+                } else {
+                    record.clear();
+                }
+            }
+            c => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_record_quotes_when_needed() {
+        assert_eq!(csv_record(&["a", "b c", "3600"]), "a,b c,3600\r\n");
+        assert_eq!(csv_record(&["x,y", "say \"hi\"", "two\nlines"]), "\"x,y\",\"say \"\"hi\"\"\",\"two\nlines\"\r\n");
+        assert_eq!(csv_record::<&str>(&[]), "\r\n");
+    }
+
+    #[test]
+    fn test_parse_csv_reads_quoted_fields() {
+        let text = "\u{feff}Name,Notes\r\n\"x,y\",\"say \"\"hi\"\"\nagain\"\r\n\r\nlast,\n";
+        assert_eq!(parse_csv(text), vec![
+            vec!["Name", "Notes"],
+            vec!["x,y", "say \"hi\"\nagain"],
+            vec!["last", ""],
+        ]);
+        assert_eq!(parse_csv(&csv_record(&["a", "b,c"])), vec![vec!["a", "b,c"]]);
+    }
+}
@@ -0,0 +1,346 @@
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::add_months;
+
+/// Ordering used for ambiguous numeric dates such as `03/04/2026`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DateLocale {
+    /// Month first: `10/17/2026`
+    #[default]
+    EnUs,
+    /// Day first: `17/10/2026`
+    EnGb,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Unit {
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+/// Parse a date expression relative to `today`.
+///
+/// Understands relative words ("today", "tomorrow", "next friday",
+/// "last week", "in 3 days", "2 weeks ago"), ISO dates, numeric dates in
+/// the locale's order (dotted dates are always day first) and month names
+/// ("Oct 17th, 2026", "17 October"). Returns `None` for anything else.
+pub fn parse_date_expression(input: &str, today: NaiveDate, locale: DateLocale) -> Option<NaiveDate> {
+    let text = input.to_lowercase().replace(',', " ");
+    let words: Vec<&str> = text.split_whitespace().collect();
+    if words.is_empty() {
+        return None;
+    }
+
+    parse_relative(&words, today)
+        .or_else(|| parse_numeric(&words.join(" "), today, locale))
+        .or_else(|| parse_month_name(&words, today))
+}
+
+/// Parse the text of a `[[reference]]` as a date. Page links go through
+/// here too, so only unmistakable forms count: today, tomorrow and
+/// yesterday, qualified weekdays ("next friday"), offsets ("in 3 days",
+/// "2 weeks ago") and dates that include a year. Bare weekdays, "now",
+/// "next week" and year-less dates such as `12/25` stay page links.
+pub fn parse_date_reference(input: &str, today: NaiveDate, locale: DateLocale) -> Option<NaiveDate> {
+    let text = input.to_lowercase().replace(',', " ");
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let with_year = Regex::new(r"^(?:\d{4}-\d{1,2}-\d{1,2}|\d{1,2}[/.-]\d{1,2}[/.-](?:\d{2}|\d{4}))$|\s\d{4}$").unwrap();
+    let clear = match words.as_slice() {
+        ["today"] | ["tomorrow"] | ["yesterday"] => true,
+        ["next" | "last" | "this", word] => parse_weekday(word).is_some(),
+        ["in", _, _] | [_, _, "ago"] | [_, _, "from", "now"] => true,
+        _ => with_year.is_match(&words.join(" ")),
+    };
+    if !clear {
+        return None;
+    }
+    parse_date_expression(input, today, locale)
+}
+
+fn parse_relative(words: &[&str], today: NaiveDate) -> Option<NaiveDate> {
+    match words {
+        ["today"] | ["now"] => Some(today),
+        ["tomorrow"] | ["tmr"] | ["tmrw"] => today.succ_opt(),
+        ["yesterday"] => today.pred_opt(),
+        ["day", "after", "tomorrow"] | ["the", "day", "after", "tomorrow"] => shift(today, 2, Unit::Day),
+        ["day", "before", "yesterday"] | ["the", "day", "before", "yesterday"] => shift(today, -2, Unit::Day),
+        [day] => parse_weekday(day).map(|weekday| next_weekday(today, weekday, true)),
+        ["next", word] => match parse_weekday(word) {
+            Some(weekday) => Some(next_weekday(today, weekday, false)),
+            None => shift(today, 1, parse_unit(word)?),
+        },
+        ["last", word] => match parse_weekday(word) {
+            Some(weekday) => Some(previous_weekday(today, weekday)),
+            None => shift(today, -1, parse_unit(word)?),
+        },
+        ["this", word] => {
+            let weekday = parse_weekday(word)?;
+            let monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+            Some(monday + Duration::days(weekday.num_days_from_monday() as i64))
+        }
+        ["in", count, unit] => shift(today, parse_count(count)?, parse_unit(unit)?),
+        [count, unit, "ago"] => shift(today, -parse_count(count)?, parse_unit(unit)?),
+        [count, unit, "from", "now"] => shift(today, parse_count(count)?, parse_unit(unit)?),
+        _ => None,
+    }
+}
+
+fn parse_numeric(text: &str, today: NaiveDate, locale: DateLocale) -> Option<NaiveDate> {
+    let iso = Regex::new(r"^(\d{4})-(\d{1,2})-(\d{1,2})$").unwrap();
+    if let Some(caps) = iso.captures(text) {
+        return NaiveDate::from_ymd_opt(caps[1].parse().ok()?, caps[2].parse().ok()?, caps[3].parse().ok()?);
+    }
+
+    let numeric = Regex::new(r"^(\d{1,2})([/.-])(\d{1,2})(?:[/.-](\d{2}|\d{4}))?$").unwrap();
+    let caps = numeric.captures(text)?;
+    let first: u32 = caps[1].parse().ok()?;
+    let second: u32 = caps[3].parse().ok()?;
+    let year = match caps.get(4) {
+        Some(year) if year.as_str().len() == 2 => 2000 + year.as_str().parse::<i32>().ok()?,
+        Some(year) => year.as_str().parse().ok()?,
+        None => today.year(),
+    };
+
+    let day_first = &caps[2] == "." || locale == DateLocale::EnGb;
+    let (month, day) = if day_first { (second, first) } else { (first, second) };
+    NaiveDate::from_ymd_opt(year, month, day)
+}
+
+fn parse_month_name(words: &[&str], today: NaiveDate) -> Option<NaiveDate> {
+    // Drop filler words and a leading weekday ("Saturday, Oct 17th")
+    let mut words: Vec<&str> = words.iter().copied().filter(|w| *w != "of" && *w != "the").collect();
+    if words.len() > 2 && parse_weekday(words[0]).is_some() {
+        words.remove(0);
+    }
+
+    let (month, day, year) = match words.as_slice() {
+        [a, b] => match parse_month(a) {
+            Some(month) => (month, parse_day(b)?, None),
+            None => (parse_month(b)?, parse_day(a)?, None),
+        },
+        [a, b, year] => match parse_month(a) {
+            Some(month) => (month, parse_day(b)?, Some(year)),
+            None => (parse_month(b)?, parse_day(a)?, Some(year)),
+        },
+        _ => return None,
+    };
+
+    let year = match year {
+        Some(year) if year.len() == 4 => year.parse().ok()?,
+        Some(_) => return None,
+        None => today.year(),
+    };
+    NaiveDate::from_ymd_opt(year, month, day)
+}
+
+fn parse_weekday(word: &str) -> Option<Weekday> {
+    match word {
+        "monday" | "mon" => Some(Weekday::Mon),
+        "tuesday" | "tue" | "tues" => Some(Weekday::Tue),
+        "wednesday" | "wed" => Some(Weekday::Wed),
+        "thursday" | "thu" | "thur" | "thurs" => Some(Weekday::Thu),
+        "friday" | "fri" => Some(Weekday::Fri),
+        "saturday" | "sat" => Some(Weekday::Sat),
+        "sunday" | "sun" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+fn parse_month(word: &str) -> Option<u32> {
+    let month = match word.trim_end_matches('.') {
+        "january" | "jan" => 1,
+        "february" | "feb" => 2,
+        "march" | "mar" => 3,
+        "april" | "apr" => 4,
+        "may" => 5,
+        "june" | "jun" => 6,
+        "july" | "jul" => 7,
+        "august" | "aug" => 8,
+        "september" | "sep" | "sept" => 9,
+        "october" | "oct" => 10,
+        "november" | "nov" => 11,
+        "december" | "dec" => 12,
+        _ => return None,
+    };
+    Some(month)
+}
+
+/// Day of month, with an optional English ordinal suffix ("17th")
+fn parse_day(word: &str) -> Option<u32> {
+    let digits = word
+        .strip_suffix("st")
+        .or_else(|| word.strip_suffix("nd"))
+        .or_else(|| word.strip_suffix("rd"))
+        .or_else(|| word.strip_suffix("th"))
+        .unwrap_or(word);
+    if digits.is_empty() || digits.len() > 2 || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+fn parse_count(word: &str) -> Option<i64> {
+    let count = match word {
+        "a" | "an" | "one" => 1,
+        "two" => 2,
+        "three" => 3,
+        "four" => 4,
+        "five" => 5,
+        "six" => 6,
+        "seven" => 7,
+        "eight" => 8,
+        "nine" => 9,
+        "ten" => 10,
+        _ if !word.is_empty() && word.len() <= 4 && word.chars().all(|c| c.is_ascii_digit()) => word.parse().ok()?,
+        _ => return None,
+    };
+    Some(count)
+}
+
+fn parse_unit(word: &str) -> Option<Unit> {
+    match word {
+        "day" | "days" | "d" => Some(Unit::Day),
+        "week" | "weeks" | "w" | "wk" | "wks" => Some(Unit::Week),
+        "month" | "months" | "mo" | "mos" => Some(Unit::Month),
+        "year" | "years" | "y" | "yr" | "yrs" => Some(Unit::Year),
+        _ => None,
+    }
+}
+
+fn shift(date: NaiveDate, count: i64, unit: Unit) -> Option<NaiveDate> {
+    match unit {
+        Unit::Day => date.checked_add_signed(Duration::days(count)),
+        Unit::Week => date.checked_add_signed(Duration::weeks(count)),
+        Unit::Month => add_months(date, i32::try_from(count).ok()?),
+        Unit::Year => add_months(date, i32::try_from(count.checked_mul(12)?).ok()?),
+    }
+}
+
+/// Next `weekday` after `today`; `inclusive` also accepts today itself
+fn next_weekday(today: NaiveDate, weekday: Weekday, inclusive: bool) -> NaiveDate {
+    let mut ahead = (7 + weekday.num_days_from_monday() as i64 - today.weekday().num_days_from_monday() as i64) % 7;
+    if ahead == 0 && !inclusive {
+        ahead = 7;
+    }
+    today + Duration::days(ahead)
+}
+
+/// Most recent `weekday` strictly before `today`
+fn previous_weekday(today: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let mut behind = (7 + today.weekday().num_days_from_monday() as i64 - weekday.num_days_from_monday() as i64) % 7;
+    if behind == 0 {
+        behind = 7;
+    }
+    today - Duration::days(behind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Saturday
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, 17).unwrap()
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(y, m, d)
+    }
+
+    fn parse(text: &str) -> Option<NaiveDate> {
+        parse_date_expression(text, today(), DateLocale::EnUs)
+    }
+
+    #[test]
+    fn test_relative_words() {
+        assert_eq!(parse("today"), date(2026, 10, 17));
+        assert_eq!(parse("Tomorrow"), date(2026, 10, 18));
+        assert_eq!(parse("  yesterday "), date(2026, 10, 16));
+        assert_eq!(parse("day after tomorrow"), date(2026, 10, 19));
+        assert_eq!(parse("the day before yesterday"), date(2026, 10, 15));
+    }
+
+    #[test]
+    fn test_weekdays() {
+        assert_eq!(parse("friday"), date(2026, 10, 23));
+        assert_eq!(parse("saturday"), date(2026, 10, 17));
+        assert_eq!(parse("next friday"), date(2026, 10, 23));
+        assert_eq!(parse("next saturday"), date(2026, 10, 24));
+        assert_eq!(parse("next mon"), date(2026, 10, 19));
+        assert_eq!(parse("last friday"), date(2026, 10, 16));
+        assert_eq!(parse("last saturday"), date(2026, 10, 10));
+        assert_eq!(parse("this monday"), date(2026, 10, 12));
+        assert_eq!(parse("this sunday"), date(2026, 10, 18));
+    }
+
+    #[test]
+    fn test_offsets() {
+        assert_eq!(parse("in 3 days"), date(2026, 10, 20));
+        assert_eq!(parse("in a week"), date(2026, 10, 24));
+        assert_eq!(parse("in two weeks"), date(2026, 10, 31));
+        assert_eq!(parse("2 weeks ago"), date(2026, 10, 3));
+        assert_eq!(parse("3 days from now"), date(2026, 10, 20));
+        assert_eq!(parse("next week"), date(2026, 10, 24));
+        assert_eq!(parse("last month"), date(2026, 9, 17));
+        assert_eq!(parse("next year"), date(2027, 10, 17));
+        assert_eq!(parse("in 4 months"), date(2027, 2, 17));
+    }
+
+    #[test]
+    fn test_month_offsets_clamp() {
+        let jan_31 = NaiveDate::from_ymd_opt(2026, 1, 31).unwrap();
+        assert_eq!(parse_date_expression("in 1 month", jan_31, DateLocale::EnUs), date(2026, 2, 28));
+        let leap_day = NaiveDate::from_ymd_opt(2028, 2, 29).unwrap();
+        assert_eq!(parse_date_expression("next year", leap_day, DateLocale::EnUs), date(2029, 2, 28));
+    }
+
+    #[test]
+    fn test_numeric_dates_follow_locale() {
+        assert_eq!(parse("2026-10-17"), date(2026, 10, 17));
+        assert_eq!(parse("10/17/2026"), date(2026, 10, 17));
+        assert_eq!(parse("3/4/26"), date(2026, 3, 4));
+        assert_eq!(parse_date_expression("3/4/26", today(), DateLocale::EnGb), date(2026, 4, 3));
+        assert_eq!(parse_date_expression("17/10/2026", today(), DateLocale::EnGb), date(2026, 10, 17));
+        assert_eq!(parse("17.10.2026"), date(2026, 10, 17));
+        assert_eq!(parse("12/25"), date(2026, 12, 25));
+        assert_eq!(parse("17/10/2026"), None);
+    }
+
+    #[test]
+    fn test_month_names() {
+        assert_eq!(parse("Oct 17th, 2026"), date(2026, 10, 17));
+        assert_eq!(parse("October 17"), date(2026, 10, 17));
+        assert_eq!(parse("17 October 2027"), date(2027, 10, 17));
+        assert_eq!(parse("the 1st of march"), date(2026, 3, 1));
+        assert_eq!(parse("Saturday, Oct 17th, 2026"), date(2026, 10, 17));
+        assert_eq!(parse("sept. 2"), date(2026, 9, 2));
+    }
+
+    #[test]
+    fn test_references_need_clear_dates() {
+        let reference = |text| parse_date_reference(text, today(), DateLocale::EnUs);
+        assert_eq!(reference("next friday"), date(2026, 10, 23));
+        assert_eq!(reference("in 3 days"), date(2026, 10, 20));
+        assert_eq!(reference("2 weeks ago"), date(2026, 10, 3));
+        assert_eq!(reference("Tomorrow"), date(2026, 10, 18));
+        assert_eq!(reference("2026-10-17"), date(2026, 10, 17));
+        assert_eq!(reference("10/17/2026"), date(2026, 10, 17));
+        assert_eq!(reference("3/4/26"), date(2026, 3, 4));
+        assert_eq!(reference("Oct 17th, 2026"), date(2026, 10, 17));
+        for text in ["Monday", "Sun", "wed", "now", "Next Week", "last month", "2.1", "3-4", "12/25", "October 17", "in 3 apples"] {
+            assert_eq!(reference(text), None, "{:?} should stay a page link", text);
+        }
+    }
+
+    #[test]
+    fn test_rejects_non_dates() {
+        for text in ["", "next blorp", "in many days", "32/01/2026", "feb 30", "hello world", "May I", "friday night plans", "in 99999 days"] {
+            assert_eq!(parse(text), None, "{:?} should not parse", text);
+        }
+    }
+}
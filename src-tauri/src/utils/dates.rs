@@ -0,0 +1,156 @@
+use chrono::format::{Item, StrftimeItems};
+use chrono::{Datelike, NaiveDate};
+use std::fmt::Write;
+
+use crate::errors::AppError;
+
+/// Canonical storage format for journal dates
+pub const ISO_DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Default title format for journal pages, e.g. "Oct 17th, 2026"
+pub const DEFAULT_JOURNAL_TITLE_FORMAT: &str = "%b %o, %Y";
+
+/// Parse a strict ISO-8601 calendar date (`YYYY-MM-DD`)
+pub fn parse_iso_date(value: &str) -> Result<NaiveDate, AppError> {
+    let date = NaiveDate::parse_from_str(value, ISO_DATE_FORMAT)
+        .map_err(|_| AppError::InvalidDate(format!("'{}' is not a YYYY-MM-DD date", value)))?;
+
+    // chrono accepts unpadded fields, so require the canonical spelling
+    if format_iso_date(date) != value {
+        return Err(AppError::InvalidDate(format!("'{}' is not a YYYY-MM-DD date", value)));
+    }
+    Ok(date)
+}
+
+/// Format a date in the canonical `YYYY-MM-DD` form
+pub fn format_iso_date(date: NaiveDate) -> String {
+    date.format(ISO_DATE_FORMAT).to_string()
+}
+
+/// English ordinal for a day of the month ("1st", "2nd", "11th", "23rd")
+pub fn ordinal_day(day: u32) -> String {
+    let suffix = match (day % 10, day % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", day, suffix)
+}
+
+/// Shift a date by whole months, clamping to the end of shorter months
+/// (Jan 31st + 1 month = Feb 28th)
+pub fn add_months(date: NaiveDate, months: i32) -> Option<NaiveDate> {
+    let total = date.year().checked_mul(12)?.checked_add(date.month0() as i32)?.checked_add(months)?;
+    let year = total.div_euclid(12);
+    let month = total.rem_euclid(12) as u32 + 1;
+    let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+    let last_day = super::last_day_of_month(first.year(), first.month()).day();
+    NaiveDate::from_ymd_opt(year, month, date.day().min(last_day))
+}
+
+/// Validate a journal title format.
+///
+/// Formats are chrono strftime strings with one extension: `%o` renders
+/// the ordinal day of the month.
+pub fn validate_title_format(format: &str) -> Result<(), AppError> {
+    super::validate_not_empty(format, "journal title format")?;
+    let expanded = expand_ordinal(format, "1st");
+    if StrftimeItems::new(&expanded).any(|item| matches!(item, Item::Error)) {
+        return Err(AppError::ConfigurationError(format!(
+            "Invalid journal title format: {}",
+            format
+        )));
+    }
+    Ok(())
+}
+
+/// Render a journal page title for `date` using a validated title format
+pub fn format_journal_title(date: NaiveDate, format: &str) -> Result<String, AppError> {
+    validate_title_format(format)?;
+    let expanded = expand_ordinal(format, &ordinal_day(date.day()));
+    let mut title = String::new();
+    write!(title, "{}", date.format(&expanded))
+        .map_err(|_| AppError::ConfigurationError(format!("Invalid journal title format: {}", format)))?;
+    Ok(title)
+}
+
+/// Replace `%o` with `ordinal`, leaving escaped `%%` sequences untouched
+fn expand_ordinal(format: &str, ordinal: &str) -> String {
+    let mut result = String::with_capacity(format.len());
+    let mut chars = format.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '%' {
+            match chars.peek() {
+                Some('o') => {
+                    chars.next();
+                    result.push_str(ordinal);
+                }
+                Some('%') => {
+                    chars.next();
+                    result.push_str("%%");
+                }
+                _ => result.push(c),
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_iso_date() {
+        assert_eq!(parse_iso_date("2026-10-17").unwrap(), NaiveDate::from_ymd_opt(2026, 10, 17).unwrap());
+        assert!(parse_iso_date("2026-2-30").is_err());
+        assert!(parse_iso_date("2026-02-30").is_err());
+        assert!(parse_iso_date("2026-1-05").is_err());
+        assert!(parse_iso_date("17/10/2026").is_err());
+        assert!(parse_iso_date("2026-10-17T00:00:00").is_err());
+        assert!(parse_iso_date("").is_err());
+    }
+
+    #[test]
+    fn test_ordinal_day() {
+        assert_eq!(ordinal_day(1), "1st");
+        assert_eq!(ordinal_day(2), "2nd");
+        assert_eq!(ordinal_day(3), "3rd");
+        assert_eq!(ordinal_day(11), "11th");
+        assert_eq!(ordinal_day(12), "12th");
+        assert_eq!(ordinal_day(13), "13th");
+        assert_eq!(ordinal_day(21), "21st");
+        assert_eq!(ordinal_day(22), "22nd");
+        assert_eq!(ordinal_day(31), "31st");
+    }
+
+    #[test]
+    fn test_format_journal_title() {
+        let date = NaiveDate::from_ymd_opt(2026, 10, 17).unwrap();
+        assert_eq!(format_journal_title(date, DEFAULT_JOURNAL_TITLE_FORMAT).unwrap(), "Oct 17th, 2026");
+        assert_eq!(format_journal_title(date, "%Y-%m-%d").unwrap(), "2026-10-17");
+        assert_eq!(format_journal_title(date, "%A, %B %o").unwrap(), "Saturday, October 17th");
+        assert_eq!(format_journal_title(date, "100%% %o").unwrap(), "100% 17th");
+    }
+
+    #[test]
+    fn test_add_months_clamps_to_month_end() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert_eq!(add_months(date(2026, 1, 31), 1), Some(date(2026, 2, 28)));
+        assert_eq!(add_months(date(2028, 1, 31), 1), Some(date(2028, 2, 29)));
+        assert_eq!(add_months(date(2026, 3, 31), -1), Some(date(2026, 2, 28)));
+        assert_eq!(add_months(date(2026, 11, 15), 3), Some(date(2027, 2, 15)));
+        assert_eq!(add_months(date(2026, 1, 15), -13), Some(date(2024, 12, 15)));
+    }
+
+    #[test]
+    fn test_validate_title_format() {
+        assert!(validate_title_format("%b %o, %Y").is_ok());
+        assert!(validate_title_format("").is_err());
+        assert!(validate_title_format("%Q").is_err());
+    }
+}
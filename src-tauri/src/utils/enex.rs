@@ -0,0 +1,461 @@
+use base64::Engine;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use md5::{Digest, Md5};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+use crate::errors::AppError;
+use crate::models::ImportedBlock;
+use crate::utils::content_tags;
+
+/// A file embedded in an Evernote note
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EnexResource {
+    /// MD5 of the data, which `<en-media hash="…">` refers to
+    pub hash: String,
+    pub mime: String,
+    pub file_name: Option<String>,
+    pub data: Vec<u8>,
+}
+
+/// One note of an ENEX export, with its content still in ENML
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EnexNote {
+    pub title: String,
+    pub created: Option<DateTime<Utc>>,
+    pub updated: Option<DateTime<Utc>>,
+    pub tags: Vec<String>,
+    pub content: String,
+    pub resources: Vec<EnexResource>,
+}
+
+/// Read the notes of an Evernote ENEX export
+pub fn parse_enex(text: &str) -> Result<Vec<EnexNote>, AppError> {
+    let invalid = |e: &dyn std::fmt::Display| AppError::InvalidPageData(format!("Invalid ENEX: {}", e));
+
+    let mut reader = Reader::from_str(text);
+    let mut notes = Vec::new();
+    let mut note: Option<EnexNote> = None;
+    let mut resource: Option<EnexResource> = None;
+    let mut base64_data = String::new();
+    // Names of the open elements, innermost last
+    let mut path: Vec<Vec<u8>> = Vec::new();
+
+    loop {
+        let value = match reader.read_event().map_err(|e| invalid(&e))? {
+            Event::Start(element) => {
+                match element.name().as_ref() {
+                    b"note" => note = Some(EnexNote::default()),
+                    b"resource" => {
+                        resource = Some(EnexResource::default());
+                        base64_data.clear();
+                    }
+                    _ => {}
+                }
+                path.push(element.name().as_ref().to_vec());
+                continue;
+            }
+            Event::End(element) => {
+                path.pop();
+                match element.name().as_ref() {
+                    b"note" => notes.extend(note.take()),
+                    b"resource" => {
+                        if let (Some(note), Some(mut resource)) = (note.as_mut(), resource.take()) {
+                            resource.data = base64::engine::general_purpose::STANDARD
+                                .decode(&base64_data)
+                                .map_err(|e| invalid(&e))?;
+                            resource.hash = format!("{:x}", Md5::digest(&resource.data));
+                            note.resources.push(resource);
+                        }
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+            Event::Text(text) => text.unescape().map_err(|e| invalid(&e))?.into_owned(),
+            Event::CData(data) => String::from_utf8_lossy(&data.into_inner()).into_owned(),
+            Event::Eof if path.is_empty() => break,
+            Event::Eof => return Err(invalid(&"unexpected end of file")),
+            _ => continue,
+        };
+
+        let (Some(note), Some(element)) = (note.as_mut(), path.last()) else {
+            continue;
+        };
+        match (element.as_slice(), resource.as_mut()) {
+            (b"data", Some(_)) => base64_data.extend(value.chars().filter(|c| !c.is_whitespace())),
+            (b"mime", Some(resource)) => resource.mime.push_str(value.trim()),
+            (b"file-name", Some(resource)) => resource.file_name = Some(value.trim().to_string()),
+            (b"title", None) => note.title.push_str(&value),
+            (b"created", None) => note.created = parse_enex_time(&value),
+            (b"updated", None) => note.updated = parse_enex_time(&value),
+            (b"tag", None) => note.tags.push(value.trim().to_string()),
+            (b"content", None) => note.content.push_str(&value),
+            _ => {}
+        }
+    }
+    Ok(notes)
+}
+
+/// File extension for a resource's MIME type, `bin` when it tells nothing
+pub fn mime_extension(mime: &str) -> &str {
+    let mime = mime.split(';').next().unwrap_or_default().trim();
+    match mime {
+        "image/jpeg" | "image/pjpeg" => "jpg",
+        "image/svg+xml" => "svg",
+        "image/x-icon" | "image/vnd.microsoft.icon" => "ico",
+        "text/plain" => "txt",
+        "text/markdown" => "md",
+        "audio/mpeg" => "mp3",
+        "audio/wav" | "audio/x-wav" => "wav",
+        "video/quicktime" => "mov",
+        "application/msword" => "doc",
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.document" => "docx",
+        "application/vnd.ms-excel" => "xls",
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet" => "xlsx",
+        "application/vnd.ms-powerpoint" => "ppt",
+        "application/vnd.openxmlformats-officedocument.presentationml.presentation" => "pptx",
+        "application/octet-stream" => "bin",
+        // `image/png`, `application/pdf` and the like name their extension
+        _ => mime
+            .split_once('/')
+            .map(|(_, subtype)| subtype)
+            .filter(|subtype| !subtype.is_empty() && subtype.chars().all(|c| c.is_ascii_alphanumeric()))
+            .unwrap_or("bin"),
+    }
+}
+
+/// ENEX timestamps look like `20261017T093000Z`
+fn parse_enex_time(value: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(value.trim(), "%Y%m%dT%H%M%SZ").ok().map(|time| time.and_utc())
+}
+
+/// Convert a note's ENML into blocks. Paragraphs, headings and table rows
+/// become blocks, list items become blocks with their nested lists as
+/// children, and checkboxes become `TODO`/`DONE` markers. `media` turns the
+/// hash of an `<en-media>` element into the Markdown that shows it.
+pub fn enml_to_blocks(enml: &str, media: impl FnMut(&str) -> Option<String>) -> Result<Vec<ImportedBlock>, AppError> {
+    let invalid = |e: &dyn std::fmt::Display| AppError::InvalidPageData(format!("Invalid ENML: {}", e));
+
+    let mut reader = Reader::from_str(enml);
+    // ENML is XHTML in theory, but exports are not always well formed
+    reader.config_mut().check_end_names = false;
+    let mut converter = EnmlConverter {
+        frames: vec![Frame { block: ImportedBlock::default(), item: false, has_text: false }],
+        text: String::new(),
+        heading: None,
+        pre: 0,
+        skip: 0,
+        links: Vec::new(),
+        bold: Vec::new(),
+        media,
+    };
+
+    loop {
+        match reader.read_event().map_err(|e| invalid(&e))? {
+            Event::Start(element) => converter.start(&element),
+            Event::Empty(element) => {
+                converter.start(&element);
+                converter.end(element.name().as_ref());
+            }
+            Event::End(element) => converter.end(element.name().as_ref()),
+            Event::Text(text) => converter.push_text(&text.unescape().map_err(|e| invalid(&e))?),
+            Event::CData(data) => converter.push_text(&String::from_utf8_lossy(&data.into_inner())),
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    converter.flush();
+    while converter.frames.len() > 1 {
+        converter.close_item();
+    }
+    Ok(converter.frames.pop().unwrap().block.children)
+}
+
+/// Elements that start a new block
+const BLOCK_ELEMENTS: [&[u8]; 17] = [
+    b"div", b"p", b"blockquote", b"pre", b"table", b"tr", b"ul", b"ol", b"hr",
+    b"h1", b"h2", b"h3", b"h4", b"h5", b"h6", b"section", b"en-note",
+];
+
+/// Elements whose content is not part of the note's text
+const SKIPPED_ELEMENTS: [&[u8]; 5] = [b"en-crypt", b"style", b"script", b"head", b"title"];
+
+struct Frame {
+    block: ImportedBlock,
+    /// A list item, whose first text is its own content
+    item: bool,
+    has_text: bool,
+}
+
+struct EnmlConverter<M> {
+    frames: Vec<Frame>,
+    text: String,
+    heading: Option<usize>,
+    pre: usize,
+    skip: usize,
+    /// Start of each open link's text, with its target
+    links: Vec<(usize, String)>,
+    bold: Vec<usize>,
+    media: M,
+}
+
+impl<M: FnMut(&str) -> Option<String>> EnmlConverter<M> {
+    fn start(&mut self, element: &BytesStart) {
+        let name = element.name();
+        let name = name.as_ref();
+        if SKIPPED_ELEMENTS.contains(&name) {
+            self.skip += 1;
+            return;
+        }
+        if self.skip > 0 {
+            return;
+        }
+
+        if BLOCK_ELEMENTS.contains(&name) {
+            self.flush();
+            match name {
+                b"pre" => self.pre += 1,
+                [b'h', level @ b'1'..=b'6'] => self.heading = Some((level - b'0') as usize),
+                _ => {}
+            }
+            return;
+        }
+        match name {
+            b"li" => {
+                self.flush();
+                self.frames.push(Frame { block: ImportedBlock::default(), item: true, has_text: false });
+            }
+            b"br" => self.text.push('\n'),
+            b"td" | b"th" if !self.text.trim().is_empty() => self.text.push_str(" | "),
+            b"a" => {
+                let href = attribute(element, b"href").unwrap_or_default();
+                self.links.push((self.text.len(), href));
+            }
+            b"b" | b"strong" => {
+                self.bold.push(self.text.len());
+                self.text.push_str("**");
+            }
+            b"en-todo" => {
+                let checked = attribute(element, b"checked").is_some_and(|checked| checked == "true");
+                self.text.push_str(if checked { "DONE " } else { "TODO " });
+            }
+            b"en-media" => {
+                if let Some(markdown) = attribute(element, b"hash").and_then(|hash| (self.media)(&hash)) {
+                    self.text.push_str(&markdown);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, name: &[u8]) {
+        if SKIPPED_ELEMENTS.contains(&name) {
+            self.skip = self.skip.saturating_sub(1);
+            return;
+        }
+        if self.skip > 0 {
+            return;
+        }
+
+        if BLOCK_ELEMENTS.contains(&name) {
+            self.flush();
+            match name {
+                b"pre" => self.pre = self.pre.saturating_sub(1),
+                [b'h', b'1'..=b'6'] => self.heading = None,
+                _ => {}
+            }
+            return;
+        }
+        match name {
+            b"li" => {
+                self.flush();
+                if self.frames.len() > 1 {
+                    self.close_item();
+                }
+            }
+            b"a" => {
+                let Some((start, href)) = self.links.pop() else {
+                    return;
+                };
+                let label = self.text.get(start..).unwrap_or_default().trim().to_string();
+                if !href.is_empty() && !label.is_empty() && label != href {
+                    self.text.truncate(start);
+                    self.text.push_str(&format!("[{}]({})", label, href));
+                }
+            }
+            b"b" | b"strong" => match self.bold.pop() {
+                Some(start) if self.text.len() == start + 2 => self.text.truncate(start),
+                Some(_) => self.text.push_str("**"),
+                None => {}
+            },
+            _ => {}
+        }
+    }
+
+    fn push_text(&mut self, text: &str) {
+        if self.skip > 0 {
+            return;
+        }
+        if self.pre > 0 {
+            self.text.push_str(text);
+            return;
+        }
+        // Whitespace collapses as in HTML
+        for c in text.chars() {
+            if c.is_whitespace() {
+                if !self.text.is_empty() && !self.text.ends_with([' ', '\n']) {
+                    self.text.push(' ');
+                }
+            } else {
+                self.text.push(c);
+            }
+        }
+    }
+
+    /// Turn the text collected so far into a block
+    fn flush(&mut self) {
+        let text = std::mem::take(&mut self.text);
+        let content = if self.pre > 0 {
+            let code = text.trim_matches('\n');
+            if code.trim().is_empty() { String::new() } else { format!("```\n{}\n```", code) }
+        } else {
+            let lines: Vec<&str> = text.split('\n').map(str::trim).collect();
+            lines.join("\n").trim_matches('\n').to_string()
+        };
+        if content.is_empty() {
+            return;
+        }
+        let content = match self.heading {
+            Some(level) => format!("{} {}", "#".repeat(level), content),
+            None => content,
+        };
+
+        let frame = self.frames.last_mut().unwrap();
+        if frame.item && !frame.has_text {
+            frame.block.tags = content_tags(&content);
+            frame.block.content = content;
+            frame.has_text = true;
+        } else {
+            frame.block.children.push(ImportedBlock { tags: content_tags(&content), content, ..Default::default() });
+        }
+    }
+
+    /// Attach the innermost list item to its parent; an item without text
+    /// hands its children up instead
+    fn close_item(&mut self) {
+        let frame = self.frames.pop().unwrap();
+        let parent = &mut self.frames.last_mut().unwrap().block;
+        if frame.has_text {
+            parent.children.push(frame.block);
+        } else {
+            parent.children.extend(frame.block.children);
+        }
+    }
+}
+
+fn attribute(element: &BytesStart, key: &[u8]) -> Option<String> {
+    let attribute = element.try_get_attribute(key).ok()??;
+    attribute.unescape_value().ok().map(|value| value.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mime_extension() {
+        assert_eq!(mime_extension("image/png"), "png");
+        assert_eq!(mime_extension("image/jpeg"), "jpg");
+        assert_eq!(mime_extension("image/svg+xml"), "svg");
+        assert_eq!(mime_extension("application/pdf"), "pdf");
+        assert_eq!(mime_extension("text/plain; charset=utf-8"), "txt");
+        assert_eq!(mime_extension("application/vnd.ms-excel"), "xls");
+        assert_eq!(mime_extension("application/x-something+odd"), "bin");
+        assert_eq!(mime_extension(""), "bin");
+    }
+
+    fn contents(blocks: &[ImportedBlock]) -> Vec<&str> {
+        blocks.iter().map(|block| block.content.as_str()).collect()
+    }
+
+    #[test]
+    fn test_parse_enex_time() {
+        assert_eq!(parse_enex_time("20261017T093000Z").unwrap().to_rfc3339(), "2026-10-17T09:30:00+00:00");
+        assert_eq!(parse_enex_time("yesterday"), None);
+    }
+
+    #[test]
+    fn test_enml_paragraphs_and_lists() {
+        let enml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE en-note SYSTEM "http://xml.evernote.com/pub/enml2.dtd">
+<en-note>
+  <h2>Plan</h2>
+  <div>First   <b>bold</b>&nbsp;line<br/>second line</div>
+  <div><br/></div>
+  <ul>
+    <li>Item one
+      <ul><li><en-todo checked="true"/>Nested done</li></ul>
+    </li>
+    <li><div><en-todo/>Call <a href="https://example.com">Sam</a></div></li>
+  </ul>
+  <table><tr><td>a</td><td>b</td></tr></table>
+  <en-crypt>secret</en-crypt>
+</en-note>"#;
+
+        let blocks = enml_to_blocks(enml, |_| None).unwrap();
+        assert_eq!(contents(&blocks), vec![
+            "## Plan",
+            "First **bold** line\nsecond line",
+            "Item one",
+            "TODO Call [Sam](https://example.com)",
+            "a | b",
+        ]);
+        assert_eq!(contents(&blocks[2].children), vec!["DONE Nested done"]);
+    }
+
+    #[test]
+    fn test_enml_media_and_code() {
+        let enml = "<en-note><div>Chart: <en-media hash=\"abc\" type=\"image/png\"/></div>\
+            <pre>fn main() {\n    run();\n}</pre><div><en-media hash=\"missing\"/></div></en-note>";
+        let blocks = enml_to_blocks(enml, |hash| (hash == "abc").then(|| "![chart](/files/chart.png)".to_string())).unwrap();
+        assert_eq!(contents(&blocks), vec![
+            "Chart: ![chart](/files/chart.png)",
+            "```\nfn main() {\n    run();\n}\n```",
+        ]);
+    }
+
+    #[test]
+    fn test_parse_enex_reads_notes_and_resources() {
+        let text = r#"<?xml version="1.0" encoding="UTF-8"?>
+<en-export>
+  <note>
+    <title>Groceries &amp; more</title>
+    <created>20261017T093000Z</created>
+    <updated>20261018T101500Z</updated>
+    <tag>home</tag><tag>lists</tag>
+    <content><![CDATA[<en-note><div>Milk</div></en-note>]]></content>
+    <resource>
+      <data encoding="base64">aGVs
+        bG8=</data>
+      <mime>text/plain</mime>
+      <resource-attributes><file-name>hello.txt</file-name></resource-attributes>
+    </resource>
+  </note>
+</en-export>"#;
+
+        let notes = parse_enex(text).unwrap();
+        assert_eq!(notes.len(), 1);
+        let note = &notes[0];
+        assert_eq!(note.title, "Groceries & more");
+        assert_eq!(note.tags, vec!["home", "lists"]);
+        assert_eq!(note.updated.unwrap().to_rfc3339(), "2026-10-18T10:15:00+00:00");
+        assert_eq!(note.content, "<en-note><div>Milk</div></en-note>");
+        assert_eq!(note.resources[0].data, b"hello");
+        assert_eq!(note.resources[0].hash, "5d41402abc4b2a76b9719d911017c592");
+        assert_eq!(note.resources[0].file_name.as_deref(), Some("hello.txt"));
+
+        assert!(parse_enex("<en-export><note><title>x</note>").is_err());
+    }
+}
@@ -0,0 +1,77 @@
+use regex::Regex;
+
+use crate::errors::AppError;
+
+/// Check that `key` can be written as a `key:: value` property line
+pub fn validate_habit_key(key: &str) -> Result<(), AppError> {
+    let valid = Regex::new(r"^[A-Za-z][A-Za-z0-9_-]*$").unwrap();
+    if valid.is_match(key) {
+        Ok(())
+    } else {
+        Err(AppError::InvalidBlockData(format!("Invalid habit key: {:?}", key)))
+    }
+}
+
+/// Read a habit value: `true`/`yes`/`done`/`x`/a positive number count as
+/// done, `false`/`no`/`0` as skipped. Anything else is not a habit value.
+pub fn parse_habit_value(value: &str) -> Option<bool> {
+    let value = value.trim().to_lowercase();
+    match value.as_str() {
+        "true" | "yes" | "y" | "done" | "x" | "[x]" | "✓" | "✔" => Some(true),
+        "false" | "no" | "n" | "skip" | "skipped" | "[ ]" => Some(false),
+        // `nan` and `inf` parse as numbers but are no count of anything
+        _ => value.parse::<f64>().ok().filter(|number| number.is_finite()).map(|number| number > 0.0),
+    }
+}
+
+/// Longest run of consecutive done days
+pub fn longest_streak(days: &[Option<bool>]) -> i64 {
+    let mut longest = 0;
+    let mut run = 0;
+    for day in days {
+        run = if *day == Some(true) { run + 1 } else { 0 };
+        longest = longest.max(run);
+    }
+    longest
+}
+
+/// Run of done days ending on the last day. A last day with nothing
+/// recorded yet does not break the streak.
+pub fn current_streak(days: &[Option<bool>]) -> i64 {
+    let days = match days.split_last() {
+        Some((None, rest)) => rest,
+        _ => days,
+    };
+    days.iter().rev().take_while(|day| **day == Some(true)).count() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_habit_values() {
+        assert_eq!(parse_habit_value("TRUE"), Some(true));
+        assert_eq!(parse_habit_value("2.5"), Some(true));
+        assert_eq!(parse_habit_value("0"), Some(false));
+        assert_eq!(parse_habit_value("no"), Some(false));
+        assert_eq!(parse_habit_value("later"), None);
+        for value in ["nan", "NaN", "inf", "-infinity"] {
+            assert_eq!(parse_habit_value(value), None, "{:?} is not a habit value", value);
+        }
+
+        assert!(validate_habit_key("meditate_am").is_ok());
+        assert!(validate_habit_key("2fast").is_err());
+        assert!(validate_habit_key("with space").is_err());
+    }
+
+    #[test]
+    fn test_streaks() {
+        let days = [Some(true), Some(true), Some(false), Some(true), Some(true), Some(true), None];
+        assert_eq!(longest_streak(&days), 3);
+        assert_eq!(current_streak(&days), 3);
+        assert_eq!(current_streak(&days[..3]), 0);
+        assert_eq!(current_streak(&[None, None]), 0);
+        assert_eq!(longest_streak(&[]), 0);
+    }
+}
@@ -0,0 +1,140 @@
+use regex::Regex;
+
+/// Escape text for HTML element content and quoted attribute values
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// URL-safe file name for a title: lowercase letters and digits joined by
+/// single dashes, `page` when nothing is left
+pub fn slugify(title: &str) -> String {
+    let mut slug = String::new();
+    for c in title.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() { "page".to_string() } else { slug.to_string() }
+}
+
+/// Render block content as inline HTML, one `<br>` per line break.
+///
+/// `[[links]]` become links where `resolve_link` returns a target and plain
+/// text where it does not; `#tags` link to `tag_href`. `[text](url)` links
+/// to web pages, `**bold**` and `` `code` `` are understood; everything else
+/// is escaped. `key:: value` lines whose key is in `hidden_properties` are
+/// left out.
+pub fn render_inline_html(
+    content: &str,
+    hidden_properties: &[&str],
+    resolve_link: impl Fn(&str) -> Option<String>,
+    tag_href: impl Fn(&str) -> String,
+) -> String {
+    let inline = Regex::new(
+        r#"#\[\[([^\]]+)\]\]|#([^\s#\[\],.;:!?()"']+)|\[\[([^\]]+)\]\]|\[([^\]]+)\]\((https?://[^)\s]+)\)|\*\*([^*]+)\*\*|`([^`]+)`"#,
+    )
+    .unwrap();
+    let property_line = Regex::new(r"^\s*([A-Za-z][A-Za-z0-9_-]*)::").unwrap();
+
+    let mut lines = Vec::new();
+    for line in content.lines() {
+        let hidden = property_line
+            .captures(line)
+            .is_some_and(|caps| hidden_properties.contains(&caps[1].to_lowercase().as_str()));
+        if hidden {
+            continue;
+        }
+
+        let mut html = String::new();
+        let mut last = 0;
+        for caps in inline.captures_iter(line) {
+            let whole = caps.get(0).unwrap();
+            let tag = caps.get(1).or_else(|| caps.get(2));
+            // A `#` inside a word is not a tag
+            if tag.is_some() && line[..whole.start()].chars().next_back().is_some_and(|c| !c.is_whitespace()) {
+                continue;
+            }
+
+            html.push_str(&escape_html(&line[last..whole.start()]));
+            if let Some(tag) = tag {
+                let tag = tag.as_str();
+                html.push_str(&format!("<a class=\"tag\" href=\"{}\">#{}</a>", escape_html(&tag_href(tag)), escape_html(tag)));
+            } else if let Some(title) = caps.get(3) {
+                let title = title.as_str();
+                match resolve_link(title) {
+                    Some(href) => html.push_str(&format!("<a class=\"page-ref\" href=\"{}\">{}</a>", escape_html(&href), escape_html(title))),
+                    None => html.push_str(&escape_html(title)),
+                }
+            } else if let (Some(text), Some(url)) = (caps.get(4), caps.get(5)) {
+                html.push_str(&format!("<a href=\"{}\">{}</a>", escape_html(url.as_str()), escape_html(text.as_str())));
+            } else if let Some(bold) = caps.get(6) {
+                html.push_str(&format!("<strong>{}</strong>", escape_html(bold.as_str())));
+            } else if let Some(code) = caps.get(7) {
+                html.push_str(&format!("<code>{}</code>", escape_html(code.as_str())));
+            }
+            last = whole.end();
+        }
+        html.push_str(&escape_html(&line[last..]));
+        lines.push(html);
+    }
+    lines.join("<br>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(content: &str) -> String {
+        render_inline_html(
+            content,
+            &["public"],
+            |title| (title == "Public").then(|| "public.html".to_string()),
+            |tag| format!("tags/{}.html", slugify(tag)),
+        )
+    }
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(escape_html("<a href=\"x\">Tom & Jerry's</a>"), "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&#39;s&lt;/a&gt;");
+    }
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Projects/Alpha: Plan?"), "projects-alpha-plan");
+        assert_eq!(slugify("  Café  Notes "), "café-notes");
+        assert_eq!(slugify("???"), "page");
+    }
+
+    #[test]
+    fn test_render_links_and_tags() {
+        assert_eq!(
+            render("See [[Public]] and [[Secret]] #idea #[[big plans]] issue#3"),
+            "See <a class=\"page-ref\" href=\"public.html\">Public</a> and Secret \
+             <a class=\"tag\" href=\"tags/idea.html\">#idea</a> \
+             <a class=\"tag\" href=\"tags/big-plans.html\">#big plans</a> issue#3"
+        );
+    }
+
+    #[test]
+    fn test_render_formatting_and_lines() {
+        assert_eq!(
+            render("**Bold** <b> `x < y`\npublic:: true\n[site](https://example.com?a=1&b=2)"),
+            "<strong>Bold</strong> &lt;b&gt; <code>x &lt; y</code><br>\
+             <a href=\"https://example.com?a=1&amp;b=2\">site</a>"
+        );
+    }
+}
@@ -0,0 +1,30 @@
+use chrono::NaiveDate;
+use serde_json::Value;
+
+use crate::models::ImportedPage;
+
+/// A property value read from text, as JSON when it holds a number,
+/// boolean, list or object and as the text itself otherwise
+pub fn parse_property_value(value: String) -> Value {
+    match serde_json::from_str::<Value>(&value) {
+        Ok(parsed @ (Value::Number(_) | Value::Bool(_) | Value::Array(_) | Value::Object(_))) => parsed,
+        _ => Value::String(value),
+    }
+}
+
+/// Turn an exported journal page, marked by its `type` and `date`
+/// properties, back into the journal page of that date
+pub fn restore_journal_date(page: &mut ImportedPage) {
+    if page.properties.get("type") != Some(&Value::String("daily_note".to_string())) {
+        return;
+    }
+    page.journal_date = page
+        .properties
+        .get("date")
+        .and_then(Value::as_str)
+        .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok());
+    if page.journal_date.is_some() {
+        page.properties.remove("type");
+        page.properties.remove("date");
+    }
+}
@@ -0,0 +1,547 @@
+use chrono::NaiveDate;
+use regex::{Captures, Regex};
+use serde_json::Value;
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use crate::models::{ImportedBlock, ImportedPage, NodeWithChildren};
+use crate::utils::parse_property_lines;
+
+/// Journal date named by a file stem such as `2026_10_17` or `2026-10-17`
+pub fn journal_date_from_stem(stem: &str) -> Option<NaiveDate> {
+    ["%Y_%m_%d", "%Y-%m-%d"]
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(stem, format).ok())
+}
+
+/// Page title for a file stem, undoing Logseq's namespace escaping
+/// (`projects___alpha` and `projects%2Falpha` both become `projects/alpha`)
+/// and any other `%XX` escapes
+pub fn page_title_from_stem(stem: &str) -> String {
+    percent_decode(&stem.replace("___", "/"))
+}
+
+/// Undo `%XX` escapes, as in file names and URL paths
+pub fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escape = (bytes[index] == b'%')
+            .then(|| text.get(index + 1..index + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escape {
+            Some(byte) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            None => {
+                decoded.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// File stem for a page title that `page_title_from_stem` reads back:
+/// `/` becomes `___`, and characters file systems reject are `%XX`-escaped
+pub fn file_stem_for_title(title: &str) -> String {
+    let mut stem = String::new();
+    for (index, c) in title.chars().enumerate() {
+        match c {
+            '/' => stem.push_str("___"),
+            '<' | '>' | ':' | '"' | '\\' | '|' | '?' | '*' | '%' => stem.push_str(&format!("%{:02X}", c as u32)),
+            '.' if index == 0 => stem.push_str("%2E"),
+            c if c.is_ascii_control() => stem.push_str(&format!("%{:02X}", c as u32)),
+            c => stem.push(c),
+        }
+    }
+    stem
+}
+
+/// `#tag` and `#[[multi word]]` tags in `content`, in order of appearance
+pub fn extract_tags(content: &str) -> Vec<String> {
+    let tag = Regex::new(r#"(?:^|\s)#(?:\[\[([^\]]+)\]\]|([^\s#\[\],.;:!?()"']+))"#).unwrap();
+    let mut tags: Vec<String> = Vec::new();
+    for caps in tag.captures_iter(content) {
+        let name = caps.get(1).or_else(|| caps.get(2)).unwrap().as_str().to_string();
+        if !tags.contains(&name) {
+            tags.push(name);
+        }
+    }
+    tags
+}
+
+/// Tags `content` carries, inline or in a `tags::` line
+pub fn content_tags(content: &str) -> Vec<String> {
+    let mut tags = extract_tags(content);
+    let listed = parse_property_lines(content).remove("tags").map(Value::String);
+    for tag in listed.map(|value| property_list(&value)).unwrap_or_default() {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+/// Read one Markdown file of a Logseq or Obsidian vault.
+///
+/// Indented bullets become nested blocks. Outside bullet lists, paragraphs
+/// become blocks and headings own the blocks that follow them. YAML front
+/// matter and leading `key:: value` lines become page properties; `title`,
+/// `alias` and `tags` are lifted into the page's title, aliases and tags.
+/// `id::` lines name the node ID to keep and are dropped from the content.
+pub fn parse_markdown_page(stem: &str, text: &str) -> ImportedPage {
+    let text = text.replace("\r\n", "\n");
+    let (mut properties, body) = split_front_matter(&text);
+    let mut blocks = parse_blocks(body);
+
+    // Page properties are the unbulleted `key:: value` lines at the top
+    let bullet = Regex::new(r"^\s*[-*+](\s|$)").unwrap();
+    let first_line = body.lines().find(|line| !line.trim().is_empty()).unwrap_or_default();
+    if let Some(first) = blocks.first().filter(|_| !bullet.is_match(first_line)) {
+        let lines: Vec<&str> = first.content.lines().filter(|line| !line.trim().is_empty()).collect();
+        let only_properties = (!lines.is_empty() || first.id.is_some())
+            && lines.iter().all(|line| !parse_property_lines(line).is_empty());
+        if only_properties && first.children.is_empty() {
+            let first = blocks.remove(0);
+            for (key, value) in parse_property_lines(&first.content) {
+                properties.entry(key).or_insert(Value::String(value));
+            }
+            if let Some(id) = first.id {
+                properties.insert("id".to_string(), Value::String(id));
+            }
+            if !first.tags.is_empty() {
+                properties.entry("tags".to_string()).or_insert(Value::String(first.tags.join(", ")));
+            }
+        }
+    }
+
+    let mut page = ImportedPage {
+        title: page_title_from_stem(stem),
+        journal_date: journal_date_from_stem(stem),
+        ..Default::default()
+    };
+    page.id = properties.remove("id").and_then(|value| value.as_str().map(str::to_string));
+    if let Some(title) = properties.remove("title").and_then(|value| value.as_str().map(str::to_string)) {
+        page.title = title;
+    }
+    for key in ["alias", "aliases"] {
+        if let Some(value) = properties.get(key) {
+            page.aliases.extend(property_list(value));
+        }
+    }
+    if let Some(value) = properties.remove("tags") {
+        page.tags = property_list(&value);
+    }
+    page.properties = properties;
+    page.blocks = blocks;
+    page
+}
+
+/// Write pages as Markdown that `parse_markdown_page` reads back:
+/// `properties` as leading `key:: value` lines, then `blocks` as a bullet
+/// outline indented with tabs
+pub fn render_markdown_page(properties: &[(String, String)], blocks: &[NodeWithChildren]) -> String {
+    let mut markdown = String::new();
+    for (key, value) in properties {
+        markdown.push_str(&format!("{}:: {}\n", key, value));
+    }
+    if !properties.is_empty() && !blocks.is_empty() {
+        markdown.push('\n');
+    }
+    // Walked with a stack, so outlines of any depth fit
+    let mut stack: Vec<(&NodeWithChildren, usize)> = blocks.iter().rev().map(|block| (block, 0)).collect();
+    while let Some((block, depth)) = stack.pop() {
+        render_block(block, depth, &mut markdown);
+        stack.extend(block.child_nodes.iter().rev().map(|child| (child, depth + 1)));
+    }
+    markdown
+}
+
+/// Write the lines of one block, without its children
+fn render_block(block: &NodeWithChildren, depth: usize, markdown: &mut String) {
+    let indent = "\t".repeat(depth);
+    let mut lines = block.node.content.split('\n');
+    match lines.next().unwrap_or_default() {
+        "" => markdown.push_str(&format!("{}-\n", indent)),
+        first => markdown.push_str(&format!("{}- {}\n", indent, first)),
+    }
+    // Lines that would read back as bullets are escaped, except inside code fences
+    let mut in_fence = block.node.content.split('\n').next().unwrap_or_default().matches("```").count() % 2 == 1;
+    for line in lines {
+        match line {
+            "" => markdown.push('\n'),
+            line if in_fence => markdown.push_str(&format!("{}  {}\n", indent, line)),
+            line => markdown.push_str(&format!("{}  {}\n", indent, escape_bullet_line(line))),
+        }
+        in_fence ^= line.matches("```").count() % 2 == 1;
+    }
+}
+
+/// A continuation line like `- item` or `\- item` with one more backslash
+/// before the marker, so it stays part of its block
+fn escape_bullet_line(line: &str) -> Cow<'_, str> {
+    Regex::new(r"^(\s*)(\\*[-*+](?:\s|$))").unwrap().replace(line, "${1}\\${2}")
+}
+
+/// Undo `escape_bullet_line`
+fn unescape_bullet_line(line: &str) -> Cow<'_, str> {
+    Regex::new(r"^(\s*)\\(\\*[-*+](?:\s|$))").unwrap().replace(line, "${1}${2}")
+}
+
+/// Property value as written after `key::`; lists are comma-separated
+pub fn format_property_value(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Array(items) => items.iter().map(format_property_value).collect::<Vec<_>>().join(", "),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// Rewrite the targets of `![alt](target)` images, including `<target>`
+/// ones; `rewrite` returns the new target, or `None` to leave the image as
+/// it was
+pub fn rewrite_image_links(content: &str, mut rewrite: impl FnMut(&str) -> Option<String>) -> String {
+    let image = Regex::new(r"(!\[[^\]]*\]\()(?:<([^<>\n]+)>|([^)\s]+))\)").unwrap();
+    image
+        .replace_all(content, |caps: &Captures| {
+            let target = caps.get(2).or_else(|| caps.get(3)).map_or("", |target| target.as_str());
+            match rewrite(target) {
+                Some(target) => format!("{}{})", &caps[1], markdown_link_target(&target)),
+                None => caps[0].to_string(),
+            }
+        })
+        .into_owned()
+}
+
+/// `target` as written inside `[text](...)`: in angle brackets when it has
+/// spaces or parentheses, which would otherwise end the link
+pub fn markdown_link_target(target: &str) -> String {
+    if target.contains(|c: char| c.is_whitespace() || c == '(' || c == ')') {
+        format!("<{}>", target)
+    } else {
+        target.to_string()
+    }
+}
+
+/// Comma-separated or array property value as a list, without `[[ ]]`
+pub fn property_list(value: &Value) -> Vec<String> {
+    let items: Vec<String> = match value {
+        Value::Array(items) => items.iter().filter_map(|item| item.as_str().map(str::to_string)).collect(),
+        Value::String(text) => text.split(',').map(str::to_string).collect(),
+        _ => Vec::new(),
+    };
+    items
+        .iter()
+        .map(|item| item.trim().trim_start_matches('#').trim_start_matches("[[").trim_end_matches("]]").to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+/// Split `---` YAML front matter off the top of `text`. Only the flat
+/// `key: value`, `key: [a, b]` and `key:` + `- item` forms are understood.
+fn split_front_matter(text: &str) -> (HashMap<String, Value>, &str) {
+    let mut properties = HashMap::new();
+    let Some(rest) = text.strip_prefix("---\n") else {
+        return (properties, text);
+    };
+    let Some(end) = rest.find("\n---").filter(|end| rest[end + 4..].starts_with('\n') || rest.len() == end + 4) else {
+        return (properties, text);
+    };
+    let body = rest[end + 4..].strip_prefix('\n').unwrap_or(&rest[end + 4..]);
+
+    let mut list_key: Option<String> = None;
+    for line in rest[..end].lines() {
+        if let (Some(key), Some(item)) = (&list_key, line.trim_start().strip_prefix("- ")) {
+            if let Some(Value::Array(items)) = properties.get_mut(key) {
+                items.push(Value::String(unquote(item).to_string()));
+            }
+            continue;
+        }
+        list_key = None;
+        let Some((key, value)) = line.split_once(':') else { continue };
+        let (key, value) = (key.trim().to_lowercase(), value.trim());
+        if key.is_empty() || key.contains(' ') {
+            continue;
+        }
+        let value = if value.is_empty() {
+            list_key = Some(key.clone());
+            Value::Array(Vec::new())
+        } else if let Some(inner) = value.strip_prefix('[').and_then(|value| value.strip_suffix(']')) {
+            Value::Array(inner.split(',').map(|item| Value::String(unquote(item.trim()).to_string())).collect())
+        } else if value == "true" || value == "false" {
+            Value::Bool(value == "true")
+        } else {
+            Value::String(unquote(value).to_string())
+        };
+        properties.insert(key, value);
+    }
+    (properties, body)
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .or_else(|| value.strip_prefix('\'').and_then(|value| value.strip_suffix('\'')))
+        .unwrap_or(value)
+}
+
+/// Width of leading whitespace, counting a tab as four columns
+fn indent_width(line: &str) -> usize {
+    line.chars()
+        .take_while(|c| c.is_whitespace())
+        .map(|c| if c == '\t' { 4 } else { 1 })
+        .sum()
+}
+
+/// Drop up to `width` columns of leading whitespace
+fn dedent(line: &str, width: usize) -> &str {
+    let mut columns = 0;
+    for (index, c) in line.char_indices() {
+        if columns >= width || !c.is_whitespace() {
+            return &line[index..];
+        }
+        columns += if c == '\t' { 4 } else { 1 };
+    }
+    ""
+}
+
+struct FlatBlock {
+    content: Vec<String>,
+    parent: Option<usize>,
+}
+
+fn parse_blocks(body: &str) -> Vec<ImportedBlock> {
+    let bullet = Regex::new(r"^(\s*)[-*+](?:\s+(.*))?$").unwrap();
+    let heading = Regex::new(r"^(#{1,6})\s+\S").unwrap();
+
+    let mut flat: Vec<FlatBlock> = Vec::new();
+    // Open headings as (level, block) and bullets as (indent, block)
+    let mut sections: Vec<(usize, usize)> = Vec::new();
+    let mut bullets: Vec<(usize, usize)> = Vec::new();
+    // Block that continuation lines append to, and its content column
+    let mut current: Option<(usize, usize)> = None;
+    let mut in_fence = false;
+    let mut blank_run = false;
+
+    for line in body.split('\n') {
+        let trimmed = line.trim_start();
+        if in_fence {
+            if let Some((block, column)) = current {
+                flat[block].content.push(dedent(line, column).to_string());
+            }
+            in_fence = trimmed.matches("```").count() % 2 == 0;
+            continue;
+        }
+        if trimmed.is_empty() {
+            blank_run = true;
+            continue;
+        }
+
+        let indent = indent_width(line);
+        let section = sections.last().map(|(_, block)| *block);
+
+        if let Some(caps) = bullet.captures(line).filter(|_| !trimmed.starts_with("---")) {
+            while bullets.last().is_some_and(|(width, _)| *width >= indent) {
+                bullets.pop();
+            }
+            let parent = bullets.last().map(|(_, block)| *block).or(section);
+            let content = caps.get(2).map_or("", |text| text.as_str()).to_string();
+            flat.push(FlatBlock { content: vec![content], parent });
+            let block = flat.len() - 1;
+            bullets.push((indent, block));
+            current = Some((block, indent + 2));
+        } else if let Some(caps) = heading.captures(trimmed).filter(|_| indent == 0) {
+            let level = caps[1].len();
+            while sections.last().is_some_and(|(open, _)| *open >= level) {
+                sections.pop();
+            }
+            let parent = sections.last().map(|(_, block)| *block);
+            flat.push(FlatBlock { content: vec![trimmed.to_string()], parent });
+            sections.push((level, flat.len() - 1));
+            bullets.clear();
+            current = None;
+        } else if let Some((block, column)) = current.filter(|(_, column)| {
+            // Indented text continues the bullet above; flush text continues a paragraph
+            if bullets.is_empty() { !blank_run } else { indent + 2 > *column }
+        }) {
+            if blank_run {
+                flat[block].content.push(String::new());
+            }
+            flat[block].content.push(unescape_bullet_line(dedent(line, column)).into_owned());
+        } else {
+            bullets.clear();
+            flat.push(FlatBlock { content: vec![trimmed.to_string()], parent: section });
+            current = Some((flat.len() - 1, 0));
+        }
+
+        in_fence = trimmed.matches("```").count() % 2 == 1;
+        blank_run = false;
+    }
+
+    // Assemble the tree bottom-up; children always come after their parent
+    let mut built: Vec<Option<ImportedBlock>> = flat
+        .iter()
+        .map(|block| {
+            let content = block.content.join("\n").trim_end().to_string();
+            let mut properties: HashMap<String, Value> = parse_property_lines(&content)
+                .into_iter()
+                .map(|(key, value)| (key, Value::String(value)))
+                .collect();
+
+            let id = properties.remove("id").and_then(|value| value.as_str().map(str::to_string));
+            let content = match id {
+                Some(_) => content
+                    .lines()
+                    .filter(|line| !parse_property_lines(line).contains_key("id"))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                None => content,
+            };
+
+            properties.remove("tags");
+            Some(ImportedBlock { id, tags: content_tags(&content), content, properties, ..Default::default() })
+        })
+        .collect();
+    let mut roots = Vec::new();
+    for index in (0..flat.len()).rev() {
+        let block = built[index].take().unwrap();
+        match flat[index].parent {
+            Some(parent) => built[parent].as_mut().unwrap().children.insert(0, block),
+            None => roots.insert(0, block),
+        }
+    }
+    roots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contents(blocks: &[ImportedBlock]) -> Vec<&str> {
+        blocks.iter().map(|block| block.content.as_str()).collect()
+    }
+
+    fn block(content: &str, child_nodes: Vec<NodeWithChildren>) -> NodeWithChildren {
+        let now = chrono::Utc::now();
+        let node = crate::models::Node {
+            id: String::new(),
+            content: content.to_string(),
+            parent_id: None,
+            children: Vec::new(),
+            order: 0,
+            properties: HashMap::new(),
+            tags: Vec::new(),
+            created_at: now,
+            updated_at: now,
+            created_by: String::new(),
+            version: 1,
+        };
+        NodeWithChildren { node, child_nodes }
+    }
+
+    #[test]
+    fn test_logseq_outline() {
+        let page = parse_markdown_page(
+            "projects___alpha",
+            "title:: Alpha\nalias:: A1, [[Project A]]\ntags:: work\n\n- TODO Plan #[[next up]]\n\tscheduled:: 2026-10-20\n\t- Step one\n\t\t- Detail\n\t- Step two\n- Notes\n  second line\n- ```rust\n  let x = 1;\n\n  ```\n",
+        );
+        assert_eq!(page.title, "Alpha");
+        assert_eq!(page.aliases, vec!["A1", "Project A"]);
+        assert_eq!(page.tags, vec!["work"]);
+        assert_eq!(page.journal_date, None);
+
+        assert_eq!(
+            contents(&page.blocks),
+            vec!["TODO Plan #[[next up]]\nscheduled:: 2026-10-20", "Notes\nsecond line", "```rust\nlet x = 1;\n\n```"]
+        );
+        let plan = &page.blocks[0];
+        assert_eq!(plan.tags, vec!["next up"]);
+        assert_eq!(plan.properties.get("scheduled"), Some(&Value::String("2026-10-20".to_string())));
+        assert_eq!(contents(&plan.children), vec!["Step one", "Step two"]);
+        assert_eq!(contents(&plan.children[0].children), vec!["Detail"]);
+    }
+
+    #[test]
+    fn test_obsidian_note() {
+        let page = parse_markdown_page(
+            "2026-10-17",
+            "---\ntitle: Daily\ntags:\n  - journal\n  - \"health\"\npublish: true\n---\nIntro with #idea\nstill intro\n\n# Morning\nRan 5k\n\n- item\n    - nested\n## Detail\n```\ncode\n\nmore\n```\n# Evening\nRead\n",
+        );
+        assert_eq!(page.title, "Daily");
+        assert_eq!(page.journal_date, NaiveDate::from_ymd_opt(2026, 10, 17));
+        assert_eq!(page.tags, vec!["journal", "health"]);
+        assert_eq!(page.properties.get("publish"), Some(&Value::Bool(true)));
+
+        assert_eq!(contents(&page.blocks), vec!["Intro with #idea\nstill intro", "# Morning", "# Evening"]);
+        assert_eq!(page.blocks[0].tags, vec!["idea"]);
+        let morning = &page.blocks[1];
+        assert_eq!(contents(&morning.children), vec!["Ran 5k", "item", "## Detail"]);
+        assert_eq!(contents(&morning.children[1].children), vec!["nested"]);
+        assert_eq!(contents(&morning.children[2].children), vec!["```\ncode\n\nmore\n```"]);
+        assert_eq!(contents(&page.blocks[2].children), vec!["Read"]);
+    }
+
+    #[test]
+    fn test_ids_and_images() {
+        let page = parse_markdown_page("Beta", "id:: page-1\n\n- Target\n  id:: block-1\n  tags:: one, two\n- ![x](a.png) ![y](https://x/y.png)");
+        assert_eq!(page.id.as_deref(), Some("page-1"));
+        assert_eq!(page.blocks[0].id.as_deref(), Some("block-1"));
+        assert_eq!(page.blocks[0].content, "Target\ntags:: one, two");
+        assert_eq!(page.blocks[0].tags, vec!["one", "two"]);
+        assert!(page.blocks[0].properties.is_empty());
+
+        let rewritten = rewrite_image_links(&page.blocks[1].content, |target| {
+            (!target.contains("://")).then(|| format!("/vault/{}", target))
+        });
+        assert_eq!(rewritten, "![x](/vault/a.png) ![y](https://x/y.png)");
+        let spaced = rewrite_image_links("![x](<my file.png>) ![y](a b.png)", |target| Some(format!("/vault/{}", target)));
+        assert_eq!(spaced, "![x](</vault/my file.png>) ![y](a b.png)");
+
+        // A page whose first block is a bullet has no page properties
+        let journal = parse_markdown_page("2026_10_17", "- exercise:: true");
+        assert_eq!(journal.blocks.len(), 1);
+        assert!(journal.properties.is_empty());
+    }
+
+    #[test]
+    fn test_render_round_trips_bullet_like_lines() {
+        let shopping = "Shopping\n- milk\n  * eggs\n\\- escaped\n+\n```\n- in a fence\n```";
+        let markdown = render_markdown_page(&[], &[block(shopping, vec![block("- dashed\n- again", Vec::new())])]);
+        assert!(markdown.contains("\n  \\- milk\n    \\* eggs\n  \\\\- escaped\n  \\+\n  ```\n  - in a fence\n"));
+
+        let page = parse_markdown_page("Lists", &markdown);
+        assert_eq!(contents(&page.blocks), vec![shopping]);
+        assert_eq!(contents(&page.blocks[0].children), vec!["- dashed\n- again"]);
+    }
+
+    #[test]
+    fn test_render_deep_outline() {
+        let depth = 5_000;
+        let mut tree = block("level", Vec::new());
+        for _ in 1..depth {
+            tree = block("level", vec![tree]);
+        }
+        let markdown = render_markdown_page(&[], &[tree]);
+        assert_eq!(markdown.lines().count(), depth);
+        assert!(markdown.ends_with(&format!("{}- level\n", "\t".repeat(depth - 1))));
+    }
+
+    #[test]
+    fn test_stems_and_tags() {
+        assert_eq!(journal_date_from_stem("2026_10_17"), NaiveDate::from_ymd_opt(2026, 10, 17));
+        assert_eq!(journal_date_from_stem("2026_13_01"), None);
+        assert_eq!(page_title_from_stem("a%2Fb___c"), "a/b/c");
+        for title in ["Projects/Alpha: plan?", ".hidden", "100% <done>", "Café"] {
+            assert_eq!(page_title_from_stem(&file_stem_for_title(title)), title);
+        }
+        assert_eq!(file_stem_for_title("a/b: c"), "a___b%3A c");
+        assert_eq!(format_property_value(&serde_json::json!(["a", 1])), "a, 1");
+        assert_eq!(extract_tags("#one, #two #one and a#three # heading"), vec!["one", "two"]);
+    }
+}
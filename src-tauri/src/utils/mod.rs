@@ -1,5 +1,45 @@
 pub mod uuid_gen;
 pub mod validation;
+pub mod dates;
+pub mod periods;
+pub mod templates;
+pub mod sampling;
+pub mod date_parser;
+pub mod content_parser;
+pub mod tasks;
+pub mod recurrence;
+pub mod csv;
+pub mod srs;
+pub mod habits;
+pub mod markdown;
+pub mod imported;
+pub mod opml;
+pub mod org;
+pub mod html;
+pub mod roam;
+pub mod snapshots;
+pub mod notion;
+pub mod enex;
  
 pub use uuid_gen::*;
-pub use validation::*; 
\ No newline at end of file
+pub use validation::*;
+pub use dates::*;
+pub use periods::*;
+pub use templates::*;
+pub use sampling::*;
+pub use date_parser::*;
+pub use content_parser::*;
+pub use tasks::*;
+pub use recurrence::*;
+pub use csv::*;
+pub use srs::*;
+pub use habits::*;
+pub use markdown::*;
+pub use imported::*;
+pub use opml::*;
+pub use org::*;
+pub use html::*;
+pub use roam::*;
+pub use snapshots::*;
+pub use notion::*;
+pub use enex::*;
@@ -0,0 +1,260 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use regex::{Captures, Regex};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::models::{ImportedBlock, ImportedPage};
+use crate::utils::{markdown_link_target, parse_csv, parse_markdown_page, percent_decode};
+
+/// A file or folder name without the ` 0123…cdef` ID Notion appends
+pub fn strip_notion_hash(name: &str) -> &str {
+    let hash = Regex::new(r"\s?[0-9a-f]{32}$").unwrap();
+    match hash.find(name) {
+        Some(found) if found.start() > 0 => name[..found.start()].trim_end(),
+        _ => name,
+    }
+}
+
+/// Property key for a Notion column name: `Due Date` becomes `due-date`
+pub fn notion_property_key(column: &str) -> String {
+    let mut key = String::new();
+    for c in column.trim().chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            key.push(c);
+        } else if !key.is_empty() && !key.ends_with('-') {
+            key.push('-');
+        }
+    }
+    key.trim_end_matches('-').to_string()
+}
+
+/// Typed value of a Notion database cell: checkboxes become booleans,
+/// numbers numbers and dates ISO dates; empty cells have no value
+pub fn notion_property_value(cell: &str) -> Option<Value> {
+    let cell = cell.trim();
+    if cell.is_empty() {
+        return None;
+    }
+    match cell {
+        "Yes" => return Some(Value::Bool(true)),
+        "No" => return Some(Value::Bool(false)),
+        _ => {}
+    }
+    if let Ok(number) = cell.parse::<i64>() {
+        return Some(Value::from(number));
+    }
+    if let Some(number) = cell.parse::<f64>().ok().filter(|number| number.is_finite()) {
+        return Some(Value::from(number));
+    }
+    let date = NaiveDate::parse_from_str(cell, "%B %d, %Y")
+        .ok()
+        .or_else(|| NaiveDateTime::parse_from_str(cell, "%B %d, %Y %I:%M %p").ok().map(|time| time.date()));
+    if let Some(date) = date {
+        return Some(Value::String(date.format("%Y-%m-%d").to_string()));
+    }
+    Some(Value::String(cell.to_string()))
+}
+
+/// Convert the Markdown and CSV files of a Notion export, keyed by their
+/// path in the export, into pages.
+///
+/// Nested pages keep their place as namespaces (`Parent/Child`), with
+/// Notion's IDs dropped from the names. Each CSV database becomes a page
+/// listing its rows, and each row a page under it with the columns as typed
+/// properties; a row's own Markdown file adds its body. Links between
+/// exported files become `[[links]]`, and other local files (images and
+/// attachments) are handed to `store_file`, which returns the path to link
+/// to instead.
+pub fn parse_notion_export(
+    files: &BTreeMap<String, String>,
+    mut store_file: impl FnMut(&str) -> Option<String>,
+) -> Vec<ImportedPage> {
+    // `Name_all.csv` has every row of a database that `Name.csv` may filter
+    let databases: Vec<&String> = files
+        .keys()
+        .filter(|path| path.ends_with(".csv"))
+        .filter(|path| path.ends_with("_all.csv") || !files.contains_key(&path.replace(".csv", "_all.csv")))
+        .collect();
+    let mut titles: HashMap<String, String> = HashMap::new();
+    for path in files.keys() {
+        titles.insert(path.clone(), notion_title(path, files));
+    }
+
+    let mut pages = Vec::new();
+    let mut row_folders = Vec::new();
+    for path in databases {
+        let title = &titles[path];
+        let mut records = parse_csv(&files[path]).into_iter();
+        let Some(header) = records.next() else {
+            continue;
+        };
+        let keys: Vec<String> = header.iter().map(|column| notion_property_key(column)).collect();
+        let header: Vec<String> = header.into_iter().map(|column| column.trim().to_string()).collect();
+
+        let mut database = ImportedPage { title: title.clone(), ..Default::default() };
+        for record in records {
+            let name = record.first().map(|name| name.trim()).filter(|name| !name.is_empty()).unwrap_or("Untitled");
+            let row_title = format!("{}/{}", title, name);
+            let mut row = ImportedPage { title: row_title.clone(), ..Default::default() };
+            for (key, cell) in keys.iter().zip(&record).skip(1) {
+                if let (false, Some(value)) = (key.is_empty(), notion_property_value(cell)) {
+                    row.properties.insert(key.clone(), value);
+                }
+            }
+            database.blocks.push(ImportedBlock { content: format!("[[{}]]", row_title), ..Default::default() });
+            pages.push(row);
+        }
+        pages.insert(pages.len() - database.blocks.len(), database);
+
+        let folder = path.trim_end_matches(".csv").trim_end_matches("_all");
+        row_folders.push((format!("{}/", folder), header));
+    }
+
+    let link = Regex::new(r"(!?)\[([^\]]*)\]\(([^)\s]+)\)").unwrap();
+    for (path, text) in files.iter().filter(|(path, _)| path.ends_with(".md")) {
+        let title = &titles[path];
+        let text = text.strip_prefix('\u{feff}').unwrap_or(text).replace("\r\n", "\n");
+        let mut lines = text.lines().peekable();
+        // The first heading repeats the title
+        if lines.peek().is_some_and(|line| line.starts_with("# ")) {
+            lines.next();
+        }
+        let mut body: Vec<&str> = lines.collect();
+        // Database rows start with their cells as `Column: value` lines
+        if let Some((_, columns)) = row_folders.iter().find(|(folder, _)| path.starts_with(folder.as_str())) {
+            let is_cell = |line: &&str| line.split_once(": ").is_some_and(|(column, _)| columns.iter().any(|known| known == column));
+            let start = body.iter().position(|line| !line.trim().is_empty()).unwrap_or(body.len());
+            let end = body[start..].iter().position(|line| !is_cell(line)).map_or(body.len(), |offset| start + offset);
+            body.drain(start..end);
+        }
+
+        let folder = path.rsplit_once('/').map_or("", |(folder, _)| folder);
+        let body = body.join("\n");
+        let body = link.replace_all(&body, |caps: &Captures| {
+            let target = &caps[3];
+            if target.contains("://") || target.starts_with('#') || target.starts_with("mailto:") {
+                return caps[0].to_string();
+            }
+            let resolved = resolve_relative(folder, &percent_decode(target));
+            if let Some(linked) = titles.get(&resolved).or_else(|| titles.get(&resolved.replace(".csv", "_all.csv"))) {
+                return format!("[[{}]]", linked);
+            }
+            match store_file(&resolved) {
+                Some(stored) => format!("{}[{}]({})", &caps[1], &caps[2], markdown_link_target(&stored)),
+                None => caps[0].to_string(),
+            }
+        });
+
+        let mut page = parse_markdown_page("", &body);
+        page.title = title.clone();
+        page.journal_date = None;
+        pages.push(page);
+    }
+    pages
+}
+
+/// Title of an exported file: its name and those of the pages it is
+/// nested in, without Notion's IDs. Folders that belong to no exported
+/// page, like the export's own top folder, are left out.
+fn notion_title(path: &str, files: &BTreeMap<String, String>) -> String {
+    let mut parts = Vec::new();
+    let mut prefix = String::new();
+    let components: Vec<&str> = path.split('/').collect();
+    for (index, component) in components.iter().enumerate() {
+        if !prefix.is_empty() {
+            prefix.push('/');
+        }
+        prefix.push_str(component);
+        let name = if index + 1 == components.len() {
+            component.trim_end_matches(".md").trim_end_matches(".csv").trim_end_matches("_all")
+        } else if ["md", "csv"].iter().any(|extension| files.contains_key(&format!("{}.{}", prefix, extension)))
+            || files.contains_key(&format!("{}_all.csv", prefix))
+        {
+            component
+        } else {
+            continue;
+        };
+        parts.push(strip_notion_hash(name).to_string());
+    }
+    parts.join("/")
+}
+
+/// `target` relative to `folder`, with `.` and `..` resolved
+fn resolve_relative(folder: &str, target: &str) -> String {
+    let mut parts: Vec<&str> = folder.split('/').filter(|part| !part.is_empty()).collect();
+    for part in target.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+    parts.join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH: &str = "0123456789abcdef0123456789abcdef";
+
+    #[test]
+    fn test_strip_notion_hash() {
+        assert_eq!(strip_notion_hash(&format!("Project Plan {}", HASH)), "Project Plan");
+        assert_eq!(strip_notion_hash(&format!("Plan{}", HASH)), "Plan");
+        assert_eq!(strip_notion_hash(HASH), HASH);
+        assert_eq!(strip_notion_hash("Plain name"), "Plain name");
+    }
+
+    #[test]
+    fn test_notion_property_values() {
+        assert_eq!(notion_property_key(" Due Date "), "due-date");
+        assert_eq!(notion_property_value("Yes"), Some(Value::Bool(true)));
+        assert_eq!(notion_property_value("42"), Some(Value::from(42)));
+        assert_eq!(notion_property_value("2.5"), Some(Value::from(2.5)));
+        assert_eq!(notion_property_value("October 17, 2026"), Some(Value::from("2026-10-17")));
+        assert_eq!(notion_property_value("October 17, 2026 3:30 PM"), Some(Value::from("2026-10-17")));
+        assert_eq!(notion_property_value("In progress"), Some(Value::from("In progress")));
+        assert_eq!(notion_property_value("  "), None);
+    }
+
+    #[test]
+    fn test_parse_notion_export() {
+        let files: BTreeMap<String, String> = [
+            (format!("Export/Home {}.md", HASH), format!(
+                "# Home\n\nWelcome, see [Plan](Home%20{h}/Plan%20{h}.md) and [Tasks](Home%20{h}/Tasks%20{h}.csv)\n\n![chart](Home%20{h}/chart.png)",
+                h = HASH
+            )),
+            (format!("Export/Home {}/Plan {}.md", HASH, HASH), "# Plan\n\n- Step one\n    - Detail".to_string()),
+            (format!("Export/Home {}/Tasks {}.csv", HASH, HASH), "Name,Done,Points\nWrite,Yes,3\n,No,\n".to_string()),
+            (format!("Export/Home {}/Tasks {}/Write {}.md", HASH, HASH, HASH), "# Write\n\nDone: Yes\nPoints: 3\n\nDraft first".to_string()),
+        ]
+        .into_iter()
+        .collect();
+
+        let mut stored = Vec::new();
+        let pages = parse_notion_export(&files, |path| {
+            stored.push(path.to_string());
+            Some(format!("/attachments/{}", path.rsplit('/').next().unwrap()))
+        });
+        assert_eq!(stored, vec![format!("Export/Home {}/chart.png", HASH)]);
+
+        let titles: Vec<&str> = pages.iter().map(|page| page.title.as_str()).collect();
+        assert_eq!(titles, vec!["Home/Tasks", "Home/Tasks/Write", "Home/Tasks/Untitled", "Home", "Home/Plan", "Home/Tasks/Write"]);
+
+        let database = &pages[0];
+        assert_eq!(database.blocks[0].content, "[[Home/Tasks/Write]]");
+        assert_eq!(pages[1].properties.get("done"), Some(&Value::Bool(true)));
+        assert_eq!(pages[1].properties.get("points"), Some(&Value::from(3)));
+        assert!(!pages[2].properties.contains_key("points"));
+
+        let home = &pages[3];
+        assert_eq!(home.blocks[0].content, "Welcome, see [[Home/Plan]] and [[Home/Tasks]]");
+        assert_eq!(home.blocks[1].content, "![chart](/attachments/chart.png)");
+        assert_eq!(pages[4].blocks[0].children[0].content, "Detail");
+        assert_eq!(pages[5].blocks.len(), 1);
+        assert_eq!(pages[5].blocks[0].content, "Draft first");
+    }
+}
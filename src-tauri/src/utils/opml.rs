@@ -0,0 +1,288 @@
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde_json::Value;
+
+use crate::errors::AppError;
+use crate::models::{ImportedBlock, ImportedPage, NodeWithChildren};
+use crate::utils::{parse_property_value, restore_journal_date};
+
+/// Outline attributes with a meaning of their own; everything else maps to
+/// a node property
+const RESERVED_ATTRIBUTES: [&str; 4] = ["text", "category", "_note", "_complete"];
+
+/// Render a node and its descendants as an OPML 2.0 document. The node
+/// itself is the single top-level outline, so its properties and tags
+/// survive; `text` holds the full content, tags go in `category` and every
+/// property whose key is a valid attribute name becomes an attribute.
+pub fn render_opml(tree: &NodeWithChildren) -> String {
+    let title = tree.node.content.lines().next().unwrap_or_default();
+    let mut opml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<opml version=\"2.0\">\n");
+    opml.push_str(&format!("  <head>\n    <title>{}</title>\n  </head>\n  <body>\n", escape_xml(title)));
+
+    // `None` closes the outline opened at that depth
+    let mut stack: Vec<(Option<&NodeWithChildren>, usize)> = vec![(Some(tree), 2)];
+    while let Some((entry, depth)) = stack.pop() {
+        let indent = "  ".repeat(depth);
+        let Some(entry) = entry else {
+            opml.push_str(&format!("{}</outline>\n", indent));
+            continue;
+        };
+
+        opml.push_str(&format!("{}<outline text=\"{}\"", indent, escape_xml(&entry.node.content)));
+        if !entry.node.tags.is_empty() {
+            opml.push_str(&format!(" category=\"{}\"", escape_xml(&entry.node.tags.join(","))));
+        }
+        let mut properties: Vec<_> = entry
+            .node
+            .properties
+            .iter()
+            .filter(|(key, _)| is_attribute_name(key) && !RESERVED_ATTRIBUTES.contains(&key.as_str()))
+            .collect();
+        properties.sort_by(|a, b| a.0.cmp(b.0));
+        for (key, value) in properties {
+            let value = match value {
+                Value::String(value) => value.clone(),
+                value => value.to_string(),
+            };
+            opml.push_str(&format!(" {}=\"{}\"", key, escape_xml(&value)));
+        }
+
+        if entry.child_nodes.is_empty() {
+            opml.push_str("/>\n");
+        } else {
+            opml.push_str(">\n");
+            stack.push((None, depth));
+            stack.extend(entry.child_nodes.iter().rev().map(|child| (Some(child), depth + 1)));
+        }
+    }
+
+    opml.push_str("  </body>\n</opml>\n");
+    opml
+}
+
+/// Read an OPML document as a page. A body with a single outline titled
+/// like the document (what `render_opml` writes) becomes the page itself;
+/// otherwise the outlines are the page's blocks under the document title,
+/// or `fallback_title` when there is none.
+///
+/// Outline `text` and `_note` make up the content, `category` the tags, and
+/// `_complete="true"` marks the block `DONE`. Other attributes become
+/// properties, parsed as JSON when they hold a number, boolean, list or
+/// object.
+pub fn parse_opml(text: &str, fallback_title: &str) -> Result<ImportedPage, AppError> {
+    let invalid = |e: &dyn std::fmt::Display| AppError::InvalidPageData(format!("Invalid OPML: {}", e));
+
+    let mut reader = Reader::from_str(text);
+    let mut title: Option<String> = None;
+    let mut in_title = false;
+    let mut in_body = false;
+    let mut roots: Vec<ImportedBlock> = Vec::new();
+    // Outlines opened but not yet closed, innermost last
+    let mut open: Vec<ImportedBlock> = Vec::new();
+
+    loop {
+        match reader.read_event().map_err(|e| invalid(&e))? {
+            Event::Start(element) => match element.name().as_ref() {
+                b"title" => in_title = true,
+                b"body" => in_body = true,
+                b"outline" if in_body => open.push(outline_block(&element).map_err(|e| invalid(&e))?),
+                _ => {}
+            },
+            Event::Empty(element) if in_body && element.name().as_ref() == b"outline" => {
+                let block = outline_block(&element).map_err(|e| invalid(&e))?;
+                match open.last_mut() {
+                    Some(parent) => parent.children.push(block),
+                    None => roots.push(block),
+                }
+            }
+            Event::Text(content) if in_title => {
+                let content = content.unescape().map_err(|e| invalid(&e))?;
+                title.get_or_insert_with(String::new).push_str(&content);
+            }
+            Event::End(element) => match element.name().as_ref() {
+                b"title" => in_title = false,
+                b"body" => in_body = false,
+                b"outline" if in_body => {
+                    let block = open.pop().ok_or_else(|| invalid(&"unexpected </outline>"))?;
+                    match open.last_mut() {
+                        Some(parent) => parent.children.push(block),
+                        None => roots.push(block),
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    if !open.is_empty() {
+        return Err(invalid(&"unclosed <outline>"));
+    }
+
+    let title = title.map(|title| title.trim().to_string()).filter(|title| !title.is_empty());
+    let mut page = ImportedPage {
+        title: title.clone().unwrap_or_else(|| fallback_title.to_string()),
+        ..Default::default()
+    };
+    let is_page = roots.len() == 1
+        && title.as_deref().is_some_and(|title| roots[0].content.lines().next() == Some(title));
+    if is_page {
+        let root = roots.remove(0);
+        page.title = root.content;
+        page.properties = root.properties;
+        page.tags = root.tags;
+        page.blocks = root.children;
+    } else {
+        page.blocks = roots;
+    }
+
+    restore_journal_date(&mut page);
+    Ok(page)
+}
+
+fn outline_block(element: &BytesStart) -> Result<ImportedBlock, quick_xml::Error> {
+    let mut block = ImportedBlock::default();
+    let mut note = None;
+    let mut complete = false;
+    for attribute in element.attributes() {
+        let attribute = attribute.map_err(quick_xml::Error::from)?;
+        let value = attribute.unescape_value()?.into_owned();
+        match attribute.key.as_ref() {
+            b"text" => block.content = value,
+            b"_note" => note = Some(value),
+            b"_complete" => complete = value == "true",
+            b"category" => {
+                block.tags = value
+                    .split(',')
+                    .map(|tag| tag.trim().trim_start_matches('/').to_string())
+                    .filter(|tag| !tag.is_empty())
+                    .collect();
+            }
+            key => {
+                let key = String::from_utf8_lossy(key).into_owned();
+                block.properties.insert(key, parse_property_value(value));
+            }
+        }
+    }
+
+    if let Some(note) = note.filter(|note| !note.is_empty()) {
+        block.content = format!("{}\n{}", block.content, note);
+    }
+    if complete && !block.content.starts_with("DONE ") {
+        block.content = format!("DONE {}", block.content);
+    }
+    Ok(block)
+}
+
+/// Whether `key` can be written as an XML attribute name as is
+fn is_attribute_name(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars.next().is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+/// Escape text for an attribute value or element. Line breaks and tabs are
+/// written as character references so they survive attribute
+/// normalisation; other control characters are not allowed in XML at all.
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\n' => escaped.push_str("&#10;"),
+            '\r' => escaped.push_str("&#13;"),
+            '\t' => escaped.push_str("&#9;"),
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_xml() {
+        assert_eq!(escape_xml("a & <b> \"c\"\nd\te\u{1}"), "a &amp; &lt;b&gt; &quot;c&quot;&#10;d&#9;e");
+    }
+
+    #[test]
+    fn test_is_attribute_name() {
+        assert!(is_attribute_name("owner"));
+        assert!(is_attribute_name("_due-date.v2"));
+        assert!(!is_attribute_name("2fa"));
+        assert!(!is_attribute_name("has space"));
+        assert!(!is_attribute_name(""));
+    }
+
+    #[test]
+    fn test_parse_opml_outlines() {
+        let text = r#"<?xml version="1.0"?>
+<opml version="2.0">
+  <head><title>Reading &amp; notes</title></head>
+  <body>
+    <outline text="Books" category="/reading,later">
+      <outline text="Dune" _note="Re-read&#10;in winter" rating="5" owner="Sam"/>
+      <outline text="Ship it" _complete="true"/>
+    </outline>
+    <outline text="Articles"/>
+  </body>
+</opml>"#;
+
+        let page = parse_opml(text, "fallback").unwrap();
+        assert_eq!(page.title, "Reading & notes");
+        assert_eq!(page.blocks.len(), 2);
+        let books = &page.blocks[0];
+        assert_eq!(books.tags, vec!["reading", "later"]);
+        assert_eq!(books.children[0].content, "Dune\nRe-read\nin winter");
+        assert_eq!(books.children[0].properties.get("rating"), Some(&Value::from(5)));
+        assert_eq!(books.children[0].properties.get("owner"), Some(&Value::from("Sam")));
+        assert_eq!(books.children[1].content, "DONE Ship it");
+        assert_eq!(page.blocks[1].content, "Articles");
+    }
+
+    #[test]
+    fn test_parse_opml_single_root_is_the_page() {
+        let text = "<opml><head><title>Plan</title></head><body>\
+            <outline text=\"Plan\" category=\"work\" status=\"active\"><outline text=\"Step\"/></outline>\
+            </body></opml>";
+        let page = parse_opml(text, "fallback").unwrap();
+        assert_eq!(page.title, "Plan");
+        assert_eq!(page.tags, vec!["work"]);
+        assert_eq!(page.properties.get("status"), Some(&Value::from("active")));
+        assert_eq!(page.blocks.len(), 1);
+
+        let untitled = parse_opml("<opml><body><outline text=\"Only\"/></body></opml>", "fallback").unwrap();
+        assert_eq!(untitled.title, "fallback");
+        assert_eq!(untitled.blocks[0].content, "Only");
+    }
+
+    #[test]
+    fn test_parse_opml_deep_outline() {
+        let depth = 10_000;
+        let text = format!(
+            "<opml><body>{}{}</body></opml>",
+            "<outline text=\"level\">".repeat(depth),
+            "</outline>".repeat(depth),
+        );
+        let page = parse_opml(&text, "deep").unwrap();
+        let mut levels = 0;
+        let mut blocks = &page.blocks;
+        while let Some(block) = blocks.first() {
+            levels += 1;
+            blocks = &block.children;
+        }
+        assert_eq!(levels, depth);
+    }
+
+    #[test]
+    fn test_parse_opml_rejects_broken_documents() {
+        assert!(parse_opml("<opml><body><outline text=\"a\"></body></opml>", "x").is_err());
+        assert!(parse_opml("<opml><body><outline text=\"a\">", "x").is_err());
+    }
+}
@@ -0,0 +1,336 @@
+use regex::Regex;
+use serde_json::Value;
+
+use crate::models::{ImportedBlock, ImportedPage, NodeWithChildren};
+use crate::utils::{content_tags, parse_property_lines, parse_property_value, percent_decode, restore_journal_date};
+
+/// Render a node and its descendants as an Org document. The node itself is
+/// the single top-level headline under a `#+TITLE:` of its first line, and
+/// each level below it one more star.
+///
+/// A node's first line is its headline, so task markers and `[#A]`
+/// priorities read as Org TODO keywords and priorities, followed by its tags
+/// as `:tag:`. Tag and property names escape whitespace, `:` and `%` as
+/// `%XX`. `SCHEDULED:` / `DEADLINE:` lines directly after the first line
+/// stay right under the headline, then come the properties as a property
+/// drawer, then the rest of the content, indented under the headline.
+pub fn render_org(tree: &NodeWithChildren) -> String {
+    let mut org = String::new();
+    let title = tree.node.content.lines().next().unwrap_or_default();
+    if !title.trim().is_empty() {
+        org.push_str(&format!("#+TITLE: {}\n\n", title));
+    }
+
+    let mut stack = vec![(tree, 1)];
+    while let Some((entry, depth)) = stack.pop() {
+        render_section(entry, depth, &mut org);
+        stack.extend(entry.child_nodes.iter().rev().map(|child| (child, depth + 1)));
+    }
+    org
+}
+
+fn render_section(entry: &NodeWithChildren, depth: usize, org: &mut String) {
+    let node = &entry.node;
+    let mut lines = node.content.split('\n').peekable();
+    let mut headline = format!("{} {}", "*".repeat(depth), lines.next().unwrap_or_default());
+    let tags: Vec<String> = node.tags.iter().filter(|tag| !tag.is_empty()).map(|tag| org_name(tag)).collect();
+    if !tags.is_empty() {
+        headline = format!("{} :{}:", headline.trim_end(), tags.join(":"));
+    }
+    org.push_str(headline.trim_end());
+    org.push('\n');
+
+    let indent = " ".repeat(depth + 1);
+    while let Some(line) = lines.next_if(|line| is_planning_line(line)) {
+        org.push_str(&format!("{}{}\n", indent, line));
+    }
+
+    // Properties the content spells out itself, like the dates of its
+    // planning line, are not repeated in the drawer
+    let mut in_content: Vec<String> = parse_property_lines(&node.content).into_keys().collect();
+    for line in node.content.lines().filter(|line| is_planning_line(line)) {
+        for (keyword, key) in [("SCHEDULED:", "scheduled"), ("DEADLINE:", "deadline")] {
+            if line.contains(keyword) {
+                in_content.push(key.to_string());
+            }
+        }
+    }
+    let mut properties: Vec<_> = node
+        .properties
+        .iter()
+        .filter(|(key, _)| !key.is_empty() && !in_content.contains(&key.to_lowercase()))
+        .collect();
+    properties.sort_by(|a, b| a.0.cmp(b.0));
+    if !properties.is_empty() {
+        org.push_str(&format!("{}:PROPERTIES:\n", indent));
+        for (key, value) in properties {
+            let value = match value {
+                Value::String(value) => value.replace('\n', " "),
+                value => value.to_string(),
+            };
+            match value.is_empty() {
+                true => org.push_str(&format!("{}:{}:\n", indent, org_name(key))),
+                false => org.push_str(&format!("{}:{}: {}\n", indent, org_name(key), value)),
+            }
+        }
+        org.push_str(&format!("{}:END:\n", indent));
+    }
+
+    for line in lines {
+        match line {
+            "" => org.push('\n'),
+            line => org.push_str(&format!("{}{}\n", indent, line)),
+        }
+    }
+}
+
+/// Read an Org document as a page. A document with a single top-level
+/// headline titled like its `#+TITLE:` (what `render_org` writes) becomes
+/// the page itself; otherwise the headlines are the page's blocks under the
+/// title, or `fallback_title` when there is none, with any text before the
+/// first headline as a block of its own.
+///
+/// Headline tags and `#+FILETAGS:` become tags and property drawers become
+/// properties, parsed as JSON when they hold a number, boolean, list or
+/// object. TODO keywords, priorities and planning lines stay in the content,
+/// where they are read like any other task; `NEXT` and `STARTED` become
+/// `DOING`.
+pub fn parse_org(text: &str, fallback_title: &str) -> ImportedPage {
+    let headline = Regex::new(r"^(\*+)(?:[ \t]+(.*?))?[ \t]*$").unwrap();
+    let keyword = Regex::new(r"^#\+([A-Za-z_]+):[ \t]*(.*?)\s*$").unwrap();
+
+    let mut title: Option<String> = None;
+    let mut file_tags = Vec::new();
+    let mut preamble = Vec::new();
+    let mut roots: Vec<ImportedBlock> = Vec::new();
+    // The section being read, with its level and the lines below its
+    // headline so far
+    let mut current: Option<(usize, ImportedBlock, Vec<&str>)> = None;
+    // Read sections that may still get children, outermost first
+    let mut ancestors: Vec<(usize, ImportedBlock)> = Vec::new();
+
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    for line in text.lines() {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        if let Some(caps) = headline.captures(line) {
+            let level = caps[1].len();
+            if let Some((current_level, mut block, body)) = current.take() {
+                apply_body(&mut block, &body, current_level + 1);
+                ancestors.push((current_level, block));
+            }
+            close_sections(&mut ancestors, &mut roots, level);
+            current = Some((level, headline_block(caps.get(2).map_or("", |text| text.as_str())), Vec::new()));
+            continue;
+        }
+
+        match current.as_mut() {
+            Some((_, _, body)) => body.push(line),
+            None => match keyword.captures(line) {
+                Some(caps) => match caps[1].to_ascii_uppercase().as_str() {
+                    "TITLE" => title = Some(caps[2].to_string()),
+                    "FILETAGS" => file_tags = org_tags(&caps[2]),
+                    _ => {}
+                },
+                None => preamble.push(line),
+            },
+        }
+    }
+    if let Some((current_level, mut block, body)) = current {
+        apply_body(&mut block, &body, current_level + 1);
+        ancestors.push((current_level, block));
+    }
+    close_sections(&mut ancestors, &mut roots, 0);
+
+    let mut intro = ImportedBlock::default();
+    apply_body(&mut intro, &preamble, 0);
+    let intro_text = intro.content.trim_start_matches('\n').to_string();
+
+    let title = title.filter(|title| !title.is_empty());
+    let mut page = ImportedPage {
+        title: title.clone().unwrap_or_else(|| fallback_title.to_string()),
+        properties: intro.properties,
+        tags: file_tags,
+        ..Default::default()
+    };
+    let is_page = roots.len() == 1
+        && intro_text.is_empty()
+        && title.as_deref().is_some_and(|title| roots[0].content.lines().next() == Some(title));
+    if is_page {
+        let root = roots.remove(0);
+        page.title = root.content;
+        page.properties.extend(root.properties);
+        for tag in root.tags {
+            if !page.tags.contains(&tag) {
+                page.tags.push(tag);
+            }
+        }
+        page.blocks = root.children;
+    } else {
+        if !intro_text.is_empty() {
+            roots.insert(0, ImportedBlock { tags: intro.tags, content: intro_text, ..Default::default() });
+        }
+        page.blocks = roots;
+    }
+
+    restore_journal_date(&mut page);
+    page
+}
+
+/// Attach the sections at `level` or deeper, which can get no more
+/// children, to their parents
+fn close_sections(ancestors: &mut Vec<(usize, ImportedBlock)>, roots: &mut Vec<ImportedBlock>, level: usize) {
+    while ancestors.last().is_some_and(|(section_level, _)| *section_level >= level) {
+        let (_, block) = ancestors.pop().unwrap();
+        match ancestors.last_mut() {
+            Some((_, parent)) => parent.children.push(block),
+            None => roots.push(block),
+        }
+    }
+}
+
+fn headline_block(text: &str) -> ImportedBlock {
+    let tagged = Regex::new(r"^(.*?)(?:^|[ \t]+)(:(?:[^\s:]+:)+)$").unwrap();
+    let (text, tags) = match tagged.captures(text) {
+        Some(caps) => (caps.get(1).map_or("", |text| text.as_str()), org_tags(&caps[2])),
+        None => (text, Vec::new()),
+    };
+    let content = match text.split_once(' ').unwrap_or((text, "")) {
+        ("NEXT" | "STARTED", rest) => format!("DOING {}", rest).trim_end().to_string(),
+        _ => text.to_string(),
+    };
+    ImportedBlock { tags, content, ..Default::default() }
+}
+
+/// Add the lines of a section below its headline: the property drawer to
+/// the properties and everything else to the content, without the
+/// indentation `render_org` adds
+fn apply_body(block: &mut ImportedBlock, body: &[&str], indent: usize) {
+    let property = Regex::new(r"^:([^\s:]+):(?:[ \t]+(.*?))?\s*$").unwrap();
+
+    // The drawer may only follow the headline and its planning line, and
+    // needs its `:END:`
+    let start = body.iter().position(|line| !line.trim().is_empty() && !is_planning_line(line));
+    let drawer = start
+        .filter(|start| body[*start].trim().eq_ignore_ascii_case(":PROPERTIES:"))
+        .and_then(|start| {
+            let end = body[start..].iter().position(|line| line.trim().eq_ignore_ascii_case(":END:"))?;
+            Some(start..start + end + 1)
+        });
+
+    let mut lines: Vec<&str> = Vec::new();
+    for (index, line) in body.iter().enumerate() {
+        if let Some(drawer) = drawer.as_ref().filter(|drawer| drawer.contains(&index)) {
+            if let Some(caps) = property.captures(line.trim()).filter(|_| index != drawer.start && index + 1 != drawer.end) {
+                let value = caps.get(2).map_or("", |value| value.as_str()).to_string();
+                block.properties.insert(percent_decode(&caps[1]), parse_property_value(value));
+            }
+            continue;
+        }
+        let spaces = line.len() - line.trim_start_matches(' ').len();
+        lines.push(&line[spaces.min(indent)..]);
+    }
+    while lines.last().is_some_and(|line| line.trim().is_empty()) {
+        lines.pop();
+    }
+
+    for line in lines {
+        block.content.push('\n');
+        block.content.push_str(line);
+    }
+    for tag in content_tags(&block.content) {
+        if !block.tags.contains(&tag) {
+            block.tags.push(tag);
+        }
+    }
+}
+
+fn org_tags(text: &str) -> Vec<String> {
+    text.split(|c: char| c == ':' || c.is_whitespace())
+        .filter(|tag| !tag.is_empty())
+        .map(percent_decode)
+        .collect()
+}
+
+/// Whether `line` is an Org planning line (`SCHEDULED: <2026-10-20 Tue>`)
+fn is_planning_line(line: &str) -> bool {
+    let line = line.trim_start();
+    ["SCHEDULED:", "DEADLINE:", "CLOSED:"].iter().any(|keyword| line.starts_with(keyword))
+}
+
+/// `name` as an Org tag or property name, with whitespace, `:` and `%`
+/// written as `%XX` escapes that `parse_org` undoes
+fn org_name(name: &str) -> String {
+    let mut escaped = String::with_capacity(name.len());
+    for c in name.chars() {
+        if c == ':' || c == '%' || c.is_whitespace() {
+            let mut bytes = [0; 4];
+            for byte in c.encode_utf8(&mut bytes).bytes() {
+                escaped.push_str(&format!("%{:02X}", byte));
+            }
+        } else {
+            escaped.push(c);
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_headline_block() {
+        let block = headline_block("TODO [#A] Ship it   :work:urgent:");
+        assert_eq!(block.content, "TODO [#A] Ship it");
+        assert_eq!(block.tags, vec!["work", "urgent"]);
+        assert_eq!(headline_block("NEXT Call Sam").content, "DOING Call Sam");
+        assert_eq!(headline_block("Ratio 1:2:3").content, "Ratio 1:2:3");
+        assert_eq!(headline_block(":solo:").tags, vec!["solo"]);
+    }
+
+    #[test]
+    fn test_parse_org_document() {
+        let text = "#+TITLE: Projects\n#+FILETAGS: :work:\n#+STARTUP: overview\n\
+Some intro.\n\
+* TODO Launch :release:\n\
+  DEADLINE: <2026-10-23 Fri>\n\
+  :PROPERTIES:\n\
+  :owner: Sam\n\
+  :points: 3\n\
+  :END:\n\
+  Checklist first.\n\
+\n\
+*** Skipped a level\n\
+** Second child\n\
+* Archive\n\
+:PROPERTIES:\n\
+:not: a drawer\n";
+
+        let page = parse_org(text, "fallback");
+        assert_eq!(page.title, "Projects");
+        assert_eq!(page.tags, vec!["work"]);
+        let contents: Vec<&str> = page.blocks.iter().map(|block| block.content.as_str()).collect();
+        assert_eq!(contents, vec!["Some intro.", "TODO Launch\nDEADLINE: <2026-10-23 Fri>\nChecklist first.", "Archive\n:PROPERTIES:\n:not: a drawer"]);
+
+        let launch = &page.blocks[1];
+        assert_eq!(launch.tags, vec!["release"]);
+        assert_eq!(launch.properties.get("owner"), Some(&Value::from("Sam")));
+        assert_eq!(launch.properties.get("points"), Some(&Value::from(3)));
+        let children: Vec<&str> = launch.children.iter().map(|block| block.content.as_str()).collect();
+        assert_eq!(children, vec!["Skipped a level", "Second child"]);
+        assert!(page.blocks[2].properties.is_empty());
+    }
+
+    #[test]
+    fn test_parse_org_single_tree_is_page() {
+        let page = parse_org("#+TITLE: Journal\n\n* Journal\n  :PROPERTIES:\n  :type: daily_note\n  :date: 2026-10-17\n  :END:\n** Entry\n", "x");
+        assert_eq!(page.title, "Journal");
+        assert_eq!(page.journal_date, chrono::NaiveDate::from_ymd_opt(2026, 10, 17));
+        assert!(page.properties.is_empty());
+        assert_eq!(page.blocks[0].content, "Entry");
+
+        let page = parse_org("* One\n* Two\n", "notes");
+        assert_eq!(page.title, "notes");
+        assert_eq!(page.blocks.len(), 2);
+    }
+}
@@ -0,0 +1,119 @@
+use chrono::{Datelike, NaiveDate, Weekday};
+
+use crate::models::PeriodKind;
+
+/// The calendar span a periodic page covers, with its stable key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeriodRange {
+    pub kind: PeriodKind,
+    pub key: String,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+/// Last day of the given month
+pub fn last_day_of_month(year: i32, month: u32) -> NaiveDate {
+    let (next_year, next_month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+    NaiveDate::from_ymd_opt(next_year, next_month, 1)
+        .and_then(|first| first.pred_opt())
+        .expect("month arithmetic stays within chrono's range")
+}
+
+/// Compute the period of `kind` that contains `date`.
+///
+/// Weeks follow ISO-8601 (Monday start, week-numbering year), so the key of
+/// 2027-01-01 is `2026-W53`.
+pub fn period_range(kind: PeriodKind, date: NaiveDate) -> PeriodRange {
+    let (key, start, end) = match kind {
+        PeriodKind::Week => {
+            let week = date.iso_week();
+            let start = NaiveDate::from_isoywd_opt(week.year(), week.week(), Weekday::Mon)
+                .expect("ISO week of a valid date exists");
+            let end = NaiveDate::from_isoywd_opt(week.year(), week.week(), Weekday::Sun)
+                .expect("ISO week of a valid date exists");
+            (format!("{}-W{:02}", week.year(), week.week()), start, end)
+        }
+        PeriodKind::Month => {
+            let start = NaiveDate::from_ymd_opt(date.year(), date.month(), 1).expect("first of month exists");
+            (
+                format!("{}-{:02}", date.year(), date.month()),
+                start,
+                last_day_of_month(date.year(), date.month()),
+            )
+        }
+        PeriodKind::Quarter => {
+            let quarter = (date.month() - 1) / 3 + 1;
+            let first_month = (quarter - 1) * 3 + 1;
+            let start = NaiveDate::from_ymd_opt(date.year(), first_month, 1).expect("first of quarter exists");
+            (
+                format!("{}-Q{}", date.year(), quarter),
+                start,
+                last_day_of_month(date.year(), first_month + 2),
+            )
+        }
+        PeriodKind::Year => (
+            date.year().to_string(),
+            NaiveDate::from_ymd_opt(date.year(), 1, 1).expect("January 1st exists"),
+            NaiveDate::from_ymd_opt(date.year(), 12, 31).expect("December 31st exists"),
+        ),
+    };
+
+    PeriodRange { kind, key, start, end }
+}
+
+/// Human-readable title for a periodic page
+pub fn period_title(range: &PeriodRange) -> String {
+    match range.kind {
+        PeriodKind::Week => {
+            let week = range.start.iso_week();
+            format!("Week {}, {}", week.week(), week.year())
+        }
+        PeriodKind::Month => range.start.format("%B %Y").to_string(),
+        PeriodKind::Quarter => format!("Q{} {}", (range.start.month() - 1) / 3 + 1, range.start.year()),
+        PeriodKind::Year => range.start.year().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_last_day_of_month() {
+        assert_eq!(last_day_of_month(2026, 2), date(2026, 2, 28));
+        assert_eq!(last_day_of_month(2028, 2), date(2028, 2, 29));
+        assert_eq!(last_day_of_month(2026, 12), date(2026, 12, 31));
+        assert_eq!(last_day_of_month(2026, 4), date(2026, 4, 30));
+    }
+
+    #[test]
+    fn test_week_range_uses_iso_weeks() {
+        let range = period_range(PeriodKind::Week, date(2026, 10, 17));
+        assert_eq!(range.key, "2026-W42");
+        assert_eq!(range.start, date(2026, 10, 12));
+        assert_eq!(range.end, date(2026, 10, 18));
+        assert_eq!(period_title(&range), "Week 42, 2026");
+
+        let new_year = period_range(PeriodKind::Week, date(2027, 1, 1));
+        assert_eq!(new_year.key, "2026-W53");
+        assert_eq!(new_year.start, date(2026, 12, 28));
+    }
+
+    #[test]
+    fn test_month_quarter_year_ranges() {
+        let month = period_range(PeriodKind::Month, date(2026, 2, 14));
+        assert_eq!((month.key.as_str(), month.start, month.end), ("2026-02", date(2026, 2, 1), date(2026, 2, 28)));
+        assert_eq!(period_title(&month), "February 2026");
+
+        let quarter = period_range(PeriodKind::Quarter, date(2026, 11, 30));
+        assert_eq!((quarter.key.as_str(), quarter.start, quarter.end), ("2026-Q4", date(2026, 10, 1), date(2026, 12, 31)));
+        assert_eq!(period_title(&quarter), "Q4 2026");
+
+        let year = period_range(PeriodKind::Year, date(2026, 6, 1));
+        assert_eq!((year.key.as_str(), year.start, year.end), ("2026", date(2026, 1, 1), date(2026, 12, 31)));
+    }
+}
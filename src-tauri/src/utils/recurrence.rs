@@ -0,0 +1,341 @@
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use super::{add_months, format_iso_date, parse_iso_date, parse_property_lines, set_task_marker};
+use crate::errors::AppError;
+use crate::models::TaskState;
+
+/// How the next occurrence is computed once a repeating task is done
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RepeatKind {
+    /// `+1w`: shift the date by one interval, even if it stays in the past
+    Cumulative,
+    /// `++1w`: shift by whole intervals until the date is in the future
+    CatchUp,
+    /// `.+1w`: one interval after the completion day
+    Restart,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RepeatUnit {
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+/// A repeat rule: org-mode repeaters or a small RRULE subset
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RepeatRule {
+    pub kind: RepeatKind,
+    pub interval: u32,
+    pub unit: RepeatUnit,
+}
+
+impl RepeatRule {
+    /// Next occurrence after `date` for a task completed on `today`.
+    ///
+    /// Dates are calendar dates with no time zone, so there are no DST
+    /// gaps; month and year steps clamp to the end of shorter months.
+    pub fn next_date(&self, date: NaiveDate, today: NaiveDate) -> Option<NaiveDate> {
+        match self.kind {
+            RepeatKind::Cumulative => self.shift(date, 1),
+            RepeatKind::Restart => self.shift(today, 1),
+            RepeatKind::CatchUp => {
+                // Count steps from the original date so month ends do not drift
+                let mut steps = match self.unit {
+                    RepeatUnit::Day | RepeatUnit::Week => {
+                        let step_days = self.interval as i64 * if self.unit == RepeatUnit::Week { 7 } else { 1 };
+                        (today - date).num_days().div_euclid(step_days)
+                    }
+                    RepeatUnit::Month | RepeatUnit::Year => {
+                        let months = (today.year() as i64 - date.year() as i64) * 12 + today.month0() as i64
+                            - date.month0() as i64;
+                        let step_months = self.interval as i64 * if self.unit == RepeatUnit::Year { 12 } else { 1 };
+                        months.div_euclid(step_months) - 1
+                    }
+                }
+                .max(1);
+                loop {
+                    let next = self.shift(date, steps)?;
+                    if next > today {
+                        return Some(next);
+                    }
+                    steps += 1;
+                }
+            }
+        }
+    }
+
+    fn shift(&self, date: NaiveDate, steps: i64) -> Option<NaiveDate> {
+        let count = steps.checked_mul(self.interval as i64)?;
+        match self.unit {
+            RepeatUnit::Day => date.checked_add_signed(Duration::try_days(count)?),
+            RepeatUnit::Week => date.checked_add_signed(Duration::try_weeks(count)?),
+            RepeatUnit::Month => add_months(date, i32::try_from(count).ok()?),
+            RepeatUnit::Year => add_months(date, i32::try_from(count.checked_mul(12)?).ok()?),
+        }
+    }
+}
+
+impl fmt::Display for RepeatRule {
+    /// Org-mode spelling (`.+2w`)
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let prefix = match self.kind {
+            RepeatKind::Cumulative => "+",
+            RepeatKind::CatchUp => "++",
+            RepeatKind::Restart => ".+",
+        };
+        let unit = match self.unit {
+            RepeatUnit::Day => 'd',
+            RepeatUnit::Week => 'w',
+            RepeatUnit::Month => 'm',
+            RepeatUnit::Year => 'y',
+        };
+        write!(f, "{}{}{}", prefix, self.interval, unit)
+    }
+}
+
+/// Parse a repeat rule.
+///
+/// Accepts org-mode repeaters (`+1w`, `++1m`, `.+1d`, units d/w/m/y) and
+/// the RRULE subset `FREQ=DAILY|WEEKLY|MONTHLY|YEARLY[;INTERVAL=n]`, which
+/// repeats like `+`.
+pub fn parse_repeat_rule(text: &str) -> Result<RepeatRule, AppError> {
+    let text = text.trim();
+    let invalid = |reason: &str| AppError::InvalidDate(format!("Invalid repeat rule '{}': {}", text, reason));
+
+    let org = Regex::new(r"^(\.\+|\+\+|\+)(\d+)([a-zA-Z])$").unwrap();
+    if let Some(caps) = org.captures(text) {
+        let kind = match &caps[1] {
+            "+" => RepeatKind::Cumulative,
+            "++" => RepeatKind::CatchUp,
+            _ => RepeatKind::Restart,
+        };
+        let unit = match &caps[3] {
+            "d" => RepeatUnit::Day,
+            "w" => RepeatUnit::Week,
+            "m" => RepeatUnit::Month,
+            "y" => RepeatUnit::Year,
+            "h" => return Err(invalid("hourly repeats are not supported")),
+            _ => return Err(invalid("unknown unit")),
+        };
+        let interval = caps[2].parse().map_err(|_| invalid("interval is too large"))?;
+        if interval == 0 {
+            return Err(invalid("interval must be at least 1"));
+        }
+        return Ok(RepeatRule { kind, interval, unit });
+    }
+
+    let rrule = text.strip_prefix("RRULE:").unwrap_or(text);
+    if !rrule.to_uppercase().contains("FREQ=") {
+        return Err(invalid("expected an org repeater such as +1w or an RRULE"));
+    }
+    let mut unit = None;
+    let mut interval = 1;
+    for part in rrule.split(';').filter(|part| !part.is_empty()) {
+        let (key, value) = part.split_once('=').ok_or_else(|| invalid("expected KEY=VALUE parts"))?;
+        match key.to_uppercase().as_str() {
+            "FREQ" => {
+                unit = Some(match value.to_uppercase().as_str() {
+                    "DAILY" => RepeatUnit::Day,
+                    "WEEKLY" => RepeatUnit::Week,
+                    "MONTHLY" => RepeatUnit::Month,
+                    "YEARLY" => RepeatUnit::Year,
+                    _ => return Err(invalid("FREQ must be DAILY, WEEKLY, MONTHLY or YEARLY")),
+                })
+            }
+            "INTERVAL" => {
+                interval = value.parse().map_err(|_| invalid("INTERVAL must be a positive number"))?;
+                if interval == 0 {
+                    return Err(invalid("INTERVAL must be a positive number"));
+                }
+            }
+            other => return Err(invalid(&format!("{} is not supported", other))),
+        }
+    }
+    Ok(RepeatRule {
+        kind: RepeatKind::Cumulative,
+        interval,
+        unit: unit.ok_or_else(|| invalid("FREQ is required"))?,
+    })
+}
+
+/// Result of completing a repeating task
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecurringAdvance {
+    /// Content reset to TODO with every repeating date moved forward
+    pub content: String,
+    /// The dates that were just completed, keyed like `node_dates`
+    pub completed: Vec<(String, NaiveDate)>,
+}
+
+/// Advance the repeating dates of a task completed on `today`.
+///
+/// Repeaters live in planning timestamps (`SCHEDULED: <2026-10-20 Tue +1w>`);
+/// a `repeat::` property line applies its rule to planning timestamps without
+/// their own repeater and to `scheduled::`, `deadline::` and `due::` lines.
+/// Returns `None` when nothing in `content` repeats.
+pub fn advance_recurring_task(content: &str, today: NaiveDate) -> Option<RecurringAdvance> {
+    let timestamp = Regex::new(r"\b(SCHEDULED|DEADLINE):\s*<(\d{4}-\d{2}-\d{2})([^>]*)>").unwrap();
+    let date_property = Regex::new(r"^(\s*)(scheduled|deadline|due)::\s*(\d{4}-\d{2}-\d{2})\s*$").unwrap();
+
+    let default_rule = parse_property_lines(content)
+        .get("repeat")
+        .and_then(|rule| parse_repeat_rule(rule).ok());
+
+    let mut completed = Vec::new();
+    let mut lines = Vec::new();
+    for line in content.split('\n') {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("SCHEDULED:") || trimmed.starts_with("DEADLINE:") {
+            let advanced = timestamp.replace_all(line, |caps: &regex::Captures| {
+                let rest: Vec<&str> = caps[3]
+                    .split_whitespace()
+                    .filter(|token| token.parse::<Weekday>().is_err())
+                    .collect();
+                let rule = rest
+                    .iter()
+                    .find_map(|token| parse_repeat_rule(token).ok())
+                    .or(default_rule);
+                let date = parse_iso_date(&caps[2]).ok();
+                match (rule, date) {
+                    (Some(rule), Some(date)) => match rule.next_date(date, today) {
+                        Some(next) => {
+                            completed.push((caps[1].to_lowercase(), date));
+                            let mut stamp = format!("{} {}", format_iso_date(next), next.format("%a"));
+                            for token in &rest {
+                                stamp.push(' ');
+                                stamp.push_str(token);
+                            }
+                            format!("{}: <{}>", &caps[1], stamp)
+                        }
+                        None => caps[0].to_string(),
+                    },
+                    _ => caps[0].to_string(),
+                }
+            });
+            lines.push(advanced.into_owned());
+            continue;
+        }
+
+        let advanced = date_property.captures(line).and_then(|caps| {
+            let date = parse_iso_date(&caps[3]).ok()?;
+            let next = default_rule?.next_date(date, today)?;
+            completed.push((caps[2].to_string(), date));
+            Some(format!("{}{}:: {}", &caps[1], &caps[2], format_iso_date(next)))
+        });
+        lines.push(advanced.unwrap_or_else(|| line.to_string()));
+    }
+
+    if completed.is_empty() {
+        return None;
+    }
+    Some(RecurringAdvance {
+        content: set_task_marker(&lines.join("\n"), Some(TaskState::Todo)),
+        completed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn rule(text: &str) -> RepeatRule {
+        parse_repeat_rule(text).unwrap()
+    }
+
+    #[test]
+    fn test_parse_org_repeaters() {
+        assert_eq!(rule("+1w"), RepeatRule { kind: RepeatKind::Cumulative, interval: 1, unit: RepeatUnit::Week });
+        assert_eq!(rule("++2d"), RepeatRule { kind: RepeatKind::CatchUp, interval: 2, unit: RepeatUnit::Day });
+        assert_eq!(rule(".+3m"), RepeatRule { kind: RepeatKind::Restart, interval: 3, unit: RepeatUnit::Month });
+        assert_eq!(rule("+1y").unit, RepeatUnit::Year);
+        for text in ["+0d", "+1h", "+1x", "1w", "+w", "+-1d", "+++1d", "", "+99999999999d"] {
+            assert!(parse_repeat_rule(text).is_err(), "{:?} should be rejected", text);
+        }
+    }
+
+    #[test]
+    fn test_parse_rrule_subset() {
+        assert_eq!(rule("FREQ=WEEKLY"), RepeatRule { kind: RepeatKind::Cumulative, interval: 1, unit: RepeatUnit::Week });
+        assert_eq!(rule("RRULE:FREQ=MONTHLY;INTERVAL=2").interval, 2);
+        assert_eq!(rule("freq=daily;interval=10").unit, RepeatUnit::Day);
+        for text in ["FREQ=HOURLY", "INTERVAL=2", "FREQ=DAILY;INTERVAL=0", "FREQ=WEEKLY;BYDAY=MO", "FREQ=DAILY;COUNT"] {
+            assert!(parse_repeat_rule(text).is_err(), "{:?} should be rejected", text);
+        }
+    }
+
+    #[test]
+    fn test_display_round_trips() {
+        for text in ["+1d", "++2w", ".+3m", "+10y"] {
+            assert_eq!(rule(text).to_string(), text);
+        }
+    }
+
+    #[test]
+    fn test_cumulative_shifts_once() {
+        let today = date(2026, 10, 17);
+        assert_eq!(rule("+1d").next_date(date(2026, 10, 17), today), Some(date(2026, 10, 18)));
+        assert_eq!(rule("+1w").next_date(date(2026, 10, 1), today), Some(date(2026, 10, 8)));
+        assert_eq!(rule("+2w").next_date(date(2026, 12, 25), today), Some(date(2027, 1, 8)));
+    }
+
+    #[test]
+    fn test_month_steps_clamp_to_month_end() {
+        let today = date(2026, 1, 1);
+        assert_eq!(rule("+1m").next_date(date(2026, 1, 31), today), Some(date(2026, 2, 28)));
+        assert_eq!(rule("+1m").next_date(date(2028, 1, 31), today), Some(date(2028, 2, 29)));
+        assert_eq!(rule("+1m").next_date(date(2026, 3, 31), today), Some(date(2026, 4, 30)));
+        assert_eq!(rule("+1m").next_date(date(2026, 12, 31), today), Some(date(2027, 1, 31)));
+        assert_eq!(rule("+3m").next_date(date(2026, 11, 30), today), Some(date(2027, 2, 28)));
+        assert_eq!(rule("+1y").next_date(date(2028, 2, 29), today), Some(date(2029, 2, 28)));
+        assert_eq!(rule("+4y").next_date(date(2028, 2, 29), today), Some(date(2032, 2, 29)));
+    }
+
+    #[test]
+    fn test_catch_up_lands_in_the_future() {
+        let today = date(2026, 10, 17);
+        assert_eq!(rule("++1w").next_date(date(2026, 9, 5), today), Some(date(2026, 10, 24)));
+        assert_eq!(rule("++1d").next_date(date(2026, 10, 17), today), Some(date(2026, 10, 18)));
+        assert_eq!(rule("++1d").next_date(date(2020, 1, 1), today), Some(date(2026, 10, 18)));
+        assert_eq!(rule("++1w").next_date(date(2026, 10, 30), today), Some(date(2026, 11, 6)));
+        // Steps count from the original date, so the 31st is kept when it exists
+        assert_eq!(rule("++1m").next_date(date(2026, 1, 31), today), Some(date(2026, 10, 31)));
+        assert_eq!(rule("++1m").next_date(date(2026, 7, 31), date(2026, 10, 31)), Some(date(2026, 11, 30)));
+        assert_eq!(rule("++1y").next_date(date(2024, 2, 29), date(2026, 3, 1)), Some(date(2027, 2, 28)));
+    }
+
+    #[test]
+    fn test_restart_counts_from_completion() {
+        let today = date(2026, 10, 17);
+        assert_eq!(rule(".+1d").next_date(date(2026, 9, 1), today), Some(date(2026, 10, 18)));
+        assert_eq!(rule(".+1m").next_date(date(2026, 12, 1), date(2027, 1, 31)), Some(date(2027, 2, 28)));
+    }
+
+    #[test]
+    fn test_advance_recurring_task() {
+        let today = date(2026, 10, 17);
+        let advance = advance_recurring_task(
+            "DONE water plants\nSCHEDULED: <2026-10-17 Sat +1w> DEADLINE: <2026-10-18 Sun>",
+            today,
+        )
+        .unwrap();
+        assert_eq!(advance.content, "TODO water plants\nSCHEDULED: <2026-10-24 Sat +1w> DEADLINE: <2026-10-18 Sun>");
+        assert_eq!(advance.completed, vec![("scheduled".to_string(), date(2026, 10, 17))]);
+
+        let advance = advance_recurring_task("DONE standup\nrepeat:: FREQ=DAILY\ndue:: 2026-10-16", today).unwrap();
+        assert_eq!(advance.content, "TODO standup\nrepeat:: FREQ=DAILY\ndue:: 2026-10-17");
+
+        assert_eq!(advance_recurring_task("DONE once\nSCHEDULED: <2026-10-17 Sat>", today), None);
+        assert_eq!(advance_recurring_task("DONE once\nrepeat:: sometimes\ndue:: 2026-10-16", today), None);
+    }
+}
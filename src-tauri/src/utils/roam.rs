@@ -0,0 +1,189 @@
+use chrono::{DateTime, NaiveDate};
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::errors::AppError;
+use crate::models::{ImportedBlock, ImportedPage};
+use crate::utils::content_tags;
+
+#[derive(Debug, Deserialize)]
+struct RoamPage {
+    title: String,
+    uid: Option<String>,
+    #[serde(rename = "create-time")]
+    create_time: Option<i64>,
+    #[serde(rename = "edit-time")]
+    edit_time: Option<i64>,
+    #[serde(default)]
+    children: Vec<RoamBlock>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RoamBlock {
+    #[serde(default)]
+    string: String,
+    uid: Option<String>,
+    #[serde(rename = "create-time")]
+    create_time: Option<i64>,
+    #[serde(rename = "edit-time")]
+    edit_time: Option<i64>,
+    heading: Option<u8>,
+    #[serde(default)]
+    children: Vec<RoamBlock>,
+}
+
+/// Journal date of a Roam daily page title such as `October 17th, 2026`
+pub fn roam_journal_date(title: &str) -> Option<NaiveDate> {
+    let daily = Regex::new(r"^([A-Za-z]+) (\d{1,2})(?:st|nd|rd|th), (\d{4})$").unwrap();
+    let caps = daily.captures(title.trim())?;
+    NaiveDate::parse_from_str(&format!("{} {} {}", &caps[1], &caps[2], &caps[3]), "%B %d %Y").ok()
+}
+
+/// Read a Roam Research JSON export.
+///
+/// Block and page `uid`s become the node IDs to keep and `create-time` /
+/// `edit-time` the node timestamps. Daily pages become journal pages that
+/// keep their Roam title as an alias, so `[[October 17th, 2026]]` links
+/// still resolve. `{{[[TODO]]}}` and `{{[[DONE]]}}` become task markers and
+/// headings get their `#` prefix back.
+pub fn parse_roam_export(text: &str) -> Result<Vec<ImportedPage>, AppError> {
+    let invalid = |e: serde_json::Error| AppError::InvalidPageData(format!("Invalid Roam export: {}", e));
+    // Outlines can nest deeper than serde_json's default limit, so the
+    // stack grows on demand instead
+    let mut deserializer = serde_json::Deserializer::from_str(text);
+    deserializer.disable_recursion_limit();
+    let pages = Vec::<RoamPage>::deserialize(serde_stacker::Deserializer::new(&mut deserializer)).map_err(invalid)?;
+    deserializer.end().map_err(invalid)?;
+
+    Ok(pages
+        .into_iter()
+        .map(|page| {
+            let journal_date = roam_journal_date(&page.title);
+            ImportedPage {
+                id: page.uid,
+                created_at: page.create_time.and_then(DateTime::from_timestamp_millis),
+                updated_at: page.edit_time.and_then(DateTime::from_timestamp_millis),
+                aliases: journal_date.map(|_| vec![page.title.clone()]).unwrap_or_default(),
+                journal_date,
+                title: page.title,
+                blocks: roam_blocks(page.children),
+                ..Default::default()
+            }
+        })
+        .collect())
+}
+
+/// Convert blocks and their descendants without recursing, so deep
+/// outlines don't exhaust the stack
+fn roam_blocks(blocks: Vec<RoamBlock>) -> Vec<ImportedBlock> {
+    let mut roots = Vec::new();
+    let mut remaining = blocks.into_iter();
+    // Converted blocks still taking children, with the children left
+    let mut open: Vec<(ImportedBlock, std::vec::IntoIter<RoamBlock>)> = Vec::new();
+    loop {
+        let next = match open.last_mut() {
+            Some((_, children)) => children.next(),
+            None => remaining.next(),
+        };
+        match next {
+            Some(mut block) => {
+                let children = std::mem::take(&mut block.children).into_iter();
+                open.push((roam_block(block), children));
+            }
+            None => match open.pop() {
+                Some((block, _)) => match open.last_mut() {
+                    Some((parent, _)) => parent.children.push(block),
+                    None => roots.push(block),
+                },
+                None => return roots,
+            },
+        }
+    }
+}
+
+/// A block without its children
+fn roam_block(block: RoamBlock) -> ImportedBlock {
+    let content = roam_content(&block.string, block.heading);
+    ImportedBlock {
+        id: block.uid,
+        created_at: block.create_time.and_then(DateTime::from_timestamp_millis),
+        updated_at: block.edit_time.and_then(DateTime::from_timestamp_millis),
+        tags: content_tags(&content),
+        content,
+        ..Default::default()
+    }
+}
+
+fn roam_content(text: &str, heading: Option<u8>) -> String {
+    let marker = Regex::new(r"^\{\{(?:\[\[)?(TODO|DONE)(?:\]\])?\}\}\s*").unwrap();
+    let content = marker.replace(text, "$1 ").into_owned();
+    match heading.filter(|level| (1..=6).contains(level)) {
+        Some(level) => format!("{} {}", "#".repeat(level as usize), content),
+        None => content,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roam_journal_date() {
+        assert_eq!(roam_journal_date("October 17th, 2026"), NaiveDate::from_ymd_opt(2026, 10, 17));
+        assert_eq!(roam_journal_date("March 1st, 2024"), NaiveDate::from_ymd_opt(2024, 3, 1));
+        assert_eq!(roam_journal_date("February 30th, 2024"), None);
+        assert_eq!(roam_journal_date("Project October"), None);
+    }
+
+    #[test]
+    fn test_roam_content() {
+        assert_eq!(roam_content("{{[[TODO]]}} Call Sam", None), "TODO Call Sam");
+        assert_eq!(roam_content("{{DONE}} Filed", None), "DONE Filed");
+        assert_eq!(roam_content("Overview", Some(2)), "## Overview");
+        assert_eq!(roam_content("Plain {{embed}}", None), "Plain {{embed}}");
+    }
+
+    #[test]
+    fn test_parse_roam_export() {
+        let text = r#"[
+            {"title": "Project", "uid": "page1", "create-time": 1700000000000, "children": [
+                {"string": "Goal #work", "uid": "blk-1", "edit-time": 1700000500000, "children": [
+                    {"string": "See ((blk-1))", "uid": "blk-2"}
+                ]}
+            ]},
+            {"title": "October 17th, 2026", "children": [{"string": "Daily"}]}
+        ]"#;
+
+        let pages = parse_roam_export(text).unwrap();
+        assert_eq!(pages[0].id.as_deref(), Some("page1"));
+        assert_eq!(pages[0].created_at.unwrap().timestamp(), 1_700_000_000);
+        let goal = &pages[0].blocks[0];
+        assert_eq!((goal.id.as_deref(), goal.tags.clone()), (Some("blk-1"), vec!["work".to_string()]));
+        assert_eq!(goal.updated_at.unwrap().timestamp(), 1_700_000_500);
+        assert_eq!(goal.children[0].content, "See ((blk-1))");
+        assert_eq!(pages[1].journal_date, NaiveDate::from_ymd_opt(2026, 10, 17));
+        assert_eq!(pages[1].aliases, vec!["October 17th, 2026"]);
+
+        assert!(parse_roam_export("{\"title\": 1}").is_err());
+        assert!(parse_roam_export("[] trailing").is_err());
+    }
+
+    #[test]
+    fn test_parse_deep_roam_export() {
+        let depth = 1000;
+        let text = format!(
+            "[{{\"title\": \"Deep\", \"children\": {}[]{}}}]",
+            "[{\"string\": \"level\", \"children\": ".repeat(depth),
+            "}]".repeat(depth)
+        );
+
+        let pages = parse_roam_export(&text).unwrap();
+        let mut block = &pages[0].blocks[0];
+        for _ in 1..depth {
+            assert_eq!(block.children.len(), 1);
+            block = &block.children[0];
+        }
+        assert_eq!(block.content, "level");
+        assert!(block.children.is_empty());
+    }
+}
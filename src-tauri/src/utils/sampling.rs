@@ -0,0 +1,55 @@
+use rand::Rng;
+
+/// Pick up to `count` distinct indices, each chosen with probability
+/// proportional to its weight (Efraimidis-Spirakis sampling).
+///
+/// Non-positive or non-finite weights are never picked.
+pub fn weighted_sample<R: Rng + ?Sized>(weights: &[f64], count: usize, rng: &mut R) -> Vec<usize> {
+    let mut keyed: Vec<(f64, usize)> = weights
+        .iter()
+        .enumerate()
+        .filter(|(_, weight)| weight.is_finite() && **weight > 0.0)
+        .map(|(index, weight)| {
+            let u: f64 = rng.gen_range(f64::EPSILON..1.0);
+            (u.ln() / weight, index)
+        })
+        .collect();
+
+    // Largest key wins; ln(u) / w is a monotonic stand-in for u^(1/w)
+    keyed.sort_by(|a, b| b.0.total_cmp(&a.0));
+    keyed.into_iter().take(count).map(|(_, index)| index).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_weighted_sample_returns_distinct_indices() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let picks = weighted_sample(&[1.0, 2.0, 3.0, 4.0], 3, &mut rng);
+        assert_eq!(picks.len(), 3);
+        let mut unique = picks.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), 3);
+    }
+
+    #[test]
+    fn test_weighted_sample_skips_zero_weights() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let picks = weighted_sample(&[0.0, 5.0, -1.0, f64::NAN], 4, &mut rng);
+        assert_eq!(picks, vec![1]);
+    }
+
+    #[test]
+    fn test_weighted_sample_favours_heavy_items() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let heavy_wins = (0..1000)
+            .filter(|_| weighted_sample(&[1.0, 9.0], 1, &mut rng) == vec![1])
+            .count();
+        assert!((850..=950).contains(&heavy_wins), "heavy item won {} times", heavy_wins);
+    }
+}
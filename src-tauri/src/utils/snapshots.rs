@@ -0,0 +1,86 @@
+use chrono::{DateTime, Datelike, NaiveDateTime, Timelike, Utc};
+use std::collections::HashSet;
+
+const SNAPSHOT_NAME_FORMAT: &str = "note-%Y%m%dT%H%M%SZ.db";
+
+/// Maps a time to the rotation period (hour, day or week) it falls in
+type Period = fn(&DateTime<Utc>) -> (i32, u32, u32);
+
+/// File name of a database snapshot taken at `time`
+pub fn snapshot_file_name(time: DateTime<Utc>) -> String {
+    time.format(SNAPSHOT_NAME_FORMAT).to_string()
+}
+
+/// When the snapshot named `name` was taken, or `None` when the file is not
+/// a snapshot
+pub fn parse_snapshot_file_name(name: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(name, SNAPSHOT_NAME_FORMAT).ok().map(|time| time.and_utc())
+}
+
+/// Which snapshots a rotation keeps, as indexes into `times`: the newest
+/// snapshot of each of the last `hourly` hours, `daily` days and `weekly`
+/// ISO weeks that have one (in UTC). The newest snapshot is always kept.
+pub fn snapshots_to_keep(times: &[DateTime<Utc>], hourly: usize, daily: usize, weekly: usize) -> HashSet<usize> {
+    let mut newest_first: Vec<usize> = (0..times.len()).collect();
+    newest_first.sort_by(|a, b| times[*b].cmp(&times[*a]));
+
+    let mut keep: HashSet<usize> = newest_first.first().copied().into_iter().collect();
+    let rules: [(usize, Period); 3] = [
+        (hourly, |time| (time.year(), time.ordinal(), time.hour())),
+        (daily, |time| (time.year(), time.ordinal(), 0)),
+        (weekly, |time| (time.iso_week().year(), time.iso_week().week(), 0)),
+    ];
+    for (count, period) in rules {
+        let mut periods = HashSet::new();
+        for index in &newest_first {
+            if periods.len() == count {
+                break;
+            }
+            if periods.insert(period(&times[*index])) {
+                keep.insert(*index);
+            }
+        }
+    }
+    keep
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 10, day, hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn test_snapshot_file_names() {
+        let time = at(18, 9, 5);
+        assert_eq!(snapshot_file_name(time), "note-20261018T090500Z.db");
+        assert_eq!(parse_snapshot_file_name("note-20261018T090500Z.db"), Some(time));
+        assert_eq!(parse_snapshot_file_name("note-20261018T090500Z.db.partial"), None);
+        assert_eq!(parse_snapshot_file_name("notes.db"), None);
+    }
+
+    #[test]
+    fn test_snapshots_to_keep() {
+        let times = vec![
+            at(18, 10, 30), // 0: newest
+            at(18, 10, 0),  // 1: same hour as 0
+            at(18, 9, 0),   // 2: previous hour
+            at(18, 8, 0),   // 3: third hour
+            at(17, 23, 0),  // 4: newest of the 17th
+            at(17, 12, 0),  // 5
+            at(16, 12, 0),  // 6: newest of the 16th
+            at(11, 12, 0),  // 7: newest of the previous ISO week
+            at(4, 12, 0),   // 8: two weeks back
+        ];
+        let mut keep: Vec<usize> = snapshots_to_keep(&times, 2, 3, 2).into_iter().collect();
+        keep.sort();
+        assert_eq!(keep, vec![0, 2, 4, 6, 7]);
+
+        let keep = snapshots_to_keep(&times, 0, 0, 0);
+        assert_eq!(keep, HashSet::from([0]));
+        assert!(snapshots_to_keep(&[], 24, 7, 4).is_empty());
+    }
+}
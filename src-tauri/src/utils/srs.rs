@@ -0,0 +1,117 @@
+use regex::Regex;
+
+use crate::errors::AppError;
+use crate::models::CardSchedule;
+
+/// Tag that turns a node into a flashcard
+pub const CARD_TAG: &str = "card";
+
+/// Lowest ease factor SM-2 allows
+const MIN_EASE: f64 = 1.3;
+
+/// Highest ease factor, so easy cards still come back now and then
+const MAX_EASE: f64 = 5.0;
+
+/// Longest interval between reviews, about a century
+const MAX_INTERVAL_DAYS: i64 = 36500;
+
+/// Whether `content` carries an inline `#card` tag
+pub fn has_card_tag(content: &str) -> bool {
+    let tag = Regex::new(r"(^|\s)#card\b").unwrap();
+    tag.is_match(content)
+}
+
+/// Split card text into question and, for `question ?? answer` cloze
+/// syntax, the inline answer. The `#card` tag itself is dropped.
+pub fn split_card_text(content: &str) -> (String, Option<String>) {
+    let tag = Regex::new(r"(^|\s)#card\b").unwrap();
+    let text = tag.replace_all(content, "").trim().to_string();
+    match text.split_once("??") {
+        Some((question, answer)) if !answer.trim().is_empty() => {
+            (question.trim().to_string(), Some(answer.trim().to_string()))
+        }
+        _ => (text, None),
+    }
+}
+
+/// Apply one SM-2 review with `grade` 0 (blackout) to 5 (perfect recall)
+pub fn sm2_review(schedule: &CardSchedule, grade: u8) -> Result<CardSchedule, AppError> {
+    if grade > 5 {
+        return Err(AppError::InvalidBlockData(format!("Review grade must be 0-5, got {}", grade)));
+    }
+
+    let mut next = schedule.clone();
+    if grade >= 3 {
+        next.interval_days = match schedule.repetitions {
+            0 => 1,
+            1 => 6,
+            _ => ((schedule.interval_days as f64) * schedule.ease).round().min(MAX_INTERVAL_DAYS as f64) as i64,
+        };
+        next.repetitions += 1;
+    } else {
+        next.repetitions = 0;
+        next.interval_days = 1;
+        next.lapses += 1;
+    }
+
+    let miss = (5 - grade) as f64;
+    next.ease = (schedule.ease + 0.1 - miss * (0.08 + miss * 0.02)).clamp(MIN_EASE, MAX_EASE);
+    Ok(next)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_card_text() {
+        assert!(has_card_tag("Capital of France #card"));
+        assert!(!has_card_tag("See #cards and #flashcard"));
+        assert_eq!(
+            split_card_text("Capital of France ?? Paris #card"),
+            ("Capital of France".to_string(), Some("Paris".to_string()))
+        );
+        assert_eq!(split_card_text("#card What is SM-2?"), ("What is SM-2?".to_string(), None));
+        assert_eq!(split_card_text("Dangling ?? "), ("Dangling ??".to_string(), None));
+    }
+
+    #[test]
+    fn test_sm2_caps_interval_and_ease() {
+        let mut schedule = CardSchedule::default();
+        for _ in 0..1000 {
+            schedule = sm2_review(&schedule, 5).unwrap();
+        }
+        assert_eq!(schedule.interval_days, MAX_INTERVAL_DAYS);
+        assert_eq!(schedule.ease, MAX_EASE);
+        assert_eq!(schedule.repetitions, 1000);
+    }
+
+    #[test]
+    fn test_sm2_intervals_grow() {
+        let mut schedule = CardSchedule::default();
+        let mut intervals = Vec::new();
+        for _ in 0..4 {
+            schedule = sm2_review(&schedule, 4).unwrap();
+            intervals.push(schedule.interval_days);
+        }
+        assert_eq!(intervals, vec![1, 6, 15, 38]);
+        assert_eq!(schedule.repetitions, 4);
+        assert!((schedule.ease - 2.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_sm2_lapse_resets_and_ease_has_a_floor() {
+        let mut schedule = sm2_review(&CardSchedule::default(), 5).unwrap();
+        schedule = sm2_review(&schedule, 5).unwrap();
+        assert!((schedule.ease - 2.7).abs() < 1e-9);
+
+        schedule = sm2_review(&schedule, 1).unwrap();
+        assert_eq!((schedule.repetitions, schedule.interval_days, schedule.lapses), (0, 1, 1));
+
+        for _ in 0..10 {
+            schedule = sm2_review(&schedule, 0).unwrap();
+        }
+        assert_eq!(schedule.ease, MIN_EASE);
+        assert!(sm2_review(&schedule, 6).is_err());
+    }
+}
@@ -0,0 +1,124 @@
+use regex::Regex;
+
+use crate::models::{Priority, TaskState};
+
+/// Task state named by the marker at the start of `content`, with the
+/// remaining text (`"TODO buy milk"` -> `(Todo, "buy milk")`)
+pub fn parse_task_marker(content: &str) -> Option<(TaskState, &str)> {
+    let trimmed = content.trim_start();
+    let marker_end = trimmed.find(char::is_whitespace).unwrap_or(trimmed.len());
+    let state = TaskState::from_marker(&trimmed[..marker_end])?;
+    Some((state, trimmed[marker_end..].trim_start()))
+}
+
+/// Replace (or add, or with `None` remove) the task marker of `content`
+pub fn set_task_marker(content: &str, state: Option<TaskState>) -> String {
+    let text = match parse_task_marker(content) {
+        Some((_, rest)) => rest,
+        None => content.trim_start(),
+    };
+    match (state, text.is_empty()) {
+        (Some(state), true) => state.marker().to_string(),
+        (Some(state), false) => format!("{} {}", state.marker(), text),
+        (None, _) => text.to_string(),
+    }
+}
+
+/// Priority cookie (`[#A]`) on the first line of `content`
+pub fn parse_priority(content: &str) -> Option<Priority> {
+    let cookie = Regex::new(r"\[#([ABC])\]").unwrap();
+    let first_line = content.lines().next()?;
+    cookie.captures(first_line).and_then(|caps| Priority::from_letter(&caps[1]))
+}
+
+/// Effort estimate in minutes from an `effort::` line
+pub fn parse_effort_property(content: &str) -> Option<i64> {
+    let line = Regex::new(r"(?mi)^\s*effort::\s*(.+?)\s*$").unwrap();
+    line.captures(content).and_then(|caps| parse_effort(&caps[1]))
+}
+
+/// Parse an effort estimate into minutes: `90`, `45m`, `2h`, `1.5h`,
+/// `1h30m`, `1h 30m`, `1:30` or `1d` (a working day of 8 hours)
+pub fn parse_effort(text: &str) -> Option<i64> {
+    let text = text.trim().to_lowercase();
+    if let Some((hours, minutes)) = text.split_once(':') {
+        let (hours, minutes): (i64, i64) = (hours.parse().ok()?, minutes.parse().ok()?);
+        let total = hours.checked_mul(60)?.checked_add(minutes)?;
+        return (minutes < 60 && !text.starts_with('-') && total > 0).then_some(total);
+    }
+    if text.chars().all(|c| c.is_ascii_digit()) {
+        return text.parse().ok().filter(|minutes| *minutes > 0);
+    }
+
+    let part = Regex::new(r"^(\d+(?:\.\d+)?)\s*(mins|min|hrs|hr|d|h|m)").unwrap();
+    let mut rest = text.as_str();
+    let mut minutes = 0.0;
+    while !rest.is_empty() {
+        let caps = part.captures(rest)?;
+        let value: f64 = caps[1].parse().ok()?;
+        minutes += value * match &caps[2] {
+            "d" => 8.0 * 60.0,
+            "h" | "hr" | "hrs" => 60.0,
+            _ => 1.0,
+        };
+        rest = rest[caps[0].len()..].trim_start();
+    }
+    // Too large to be an estimate rather than saturating at `i64::MAX`
+    (minutes > 0.0 && minutes < i64::MAX as f64).then(|| minutes.round() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_task_marker() {
+        assert_eq!(parse_task_marker("TODO buy milk"), Some((TaskState::Todo, "buy milk")));
+        assert_eq!(parse_task_marker("  DOING  write report"), Some((TaskState::Doing, "write report")));
+        assert_eq!(parse_task_marker("DONE"), Some((TaskState::Done, "")));
+        assert_eq!(parse_task_marker("CANCELED\nnotes"), Some((TaskState::Canceled, "notes")));
+        assert_eq!(parse_task_marker("CANCELLED trip"), Some((TaskState::Canceled, "trip")));
+        assert_eq!(parse_task_marker("todo lowercase is text"), None);
+        assert_eq!(parse_task_marker("TODOS are fine"), None);
+        assert_eq!(parse_task_marker("Not a TODO"), None);
+    }
+
+    #[test]
+    fn test_set_task_marker() {
+        assert_eq!(set_task_marker("buy milk", Some(TaskState::Todo)), "TODO buy milk");
+        assert_eq!(set_task_marker("TODO buy milk", Some(TaskState::Done)), "DONE buy milk");
+        assert_eq!(set_task_marker("DOING buy milk", None), "buy milk");
+        assert_eq!(set_task_marker("", Some(TaskState::Todo)), "TODO");
+    }
+
+    #[test]
+    fn test_parse_priority() {
+        assert_eq!(parse_priority("TODO [#A] ship it"), Some(Priority::A));
+        assert_eq!(parse_priority("[#C] later"), Some(Priority::C));
+        assert_eq!(parse_priority("TODO ship it\n[#A] in the notes"), None);
+        assert_eq!(parse_priority("TODO [#D] ship it"), None);
+        assert_eq!(parse_priority(""), None);
+    }
+
+    #[test]
+    fn test_parse_effort() {
+        assert_eq!(parse_effort("90"), Some(90));
+        assert_eq!(parse_effort("45m"), Some(45));
+        assert_eq!(parse_effort("2h"), Some(120));
+        assert_eq!(parse_effort("1.5h"), Some(90));
+        assert_eq!(parse_effort("1h30m"), Some(90));
+        assert_eq!(parse_effort("1h 30min"), Some(90));
+        assert_eq!(parse_effort("1:30"), Some(90));
+        assert_eq!(parse_effort("1d"), Some(480));
+        for text in ["", "soon", "1:75", "h", "2 hours", "-1:30", "0m", "0", "0:00"] {
+            assert_eq!(parse_effort(text), None, "{:?} should not parse", text);
+        }
+        // Out of range rather than overflowing
+        let huge = format!("1{}h", "0".repeat(400));
+        for text in ["153722867280912931:00", "99999999999999999999", "99999999999999999999d", huge.as_str()] {
+            assert_eq!(parse_effort(text), None, "{:?} should not parse", text);
+        }
+        assert_eq!(parse_effort_property("TODO x\nEffort:: 2h"), Some(120));
+        assert_eq!(parse_effort_property("TODO x"), None);
+    }
+}
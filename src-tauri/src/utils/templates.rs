@@ -0,0 +1,65 @@
+use regex::{Captures, Regex};
+use std::collections::HashMap;
+
+/// Replace `{{name}}` placeholders with values from `vars`.
+///
+/// Unknown placeholders are left untouched so user text that happens to
+/// contain braces survives a template run.
+pub fn render_template(text: &str, vars: &HashMap<String, String>) -> String {
+    let placeholder = Regex::new(r"\{\{\s*([A-Za-z_][A-Za-z0-9_]*)\s*\}\}").unwrap();
+    placeholder
+        .replace_all(text, |caps: &Captures| {
+            vars.get(&caps[1]).cloned().unwrap_or_else(|| caps[0].to_string())
+        })
+        .into_owned()
+}
+
+/// Apply `render_template` to every string inside a JSON value
+pub fn render_template_value(value: &serde_json::Value, vars: &HashMap<String, String>) -> serde_json::Value {
+    match value {
+        serde_json::Value::String(s) => serde_json::Value::String(render_template(s, vars)),
+        serde_json::Value::Array(items) => {
+            serde_json::Value::Array(items.iter().map(|item| render_template_value(item, vars)).collect())
+        }
+        serde_json::Value::Object(map) => serde_json::Value::Object(
+            map.iter()
+                .map(|(key, item)| (key.clone(), render_template_value(item, vars)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars() -> HashMap<String, String> {
+        let mut vars = HashMap::new();
+        vars.insert("date".to_string(), "2026-10-17".to_string());
+        vars.insert("weekday".to_string(), "Saturday".to_string());
+        vars
+    }
+
+    #[test]
+    fn test_render_template() {
+        assert_eq!(render_template("{{weekday}}, {{date}}", &vars()), "Saturday, 2026-10-17");
+        assert_eq!(render_template("{{ date }}", &vars()), "2026-10-17");
+        assert_eq!(render_template("no placeholders", &vars()), "no placeholders");
+    }
+
+    #[test]
+    fn test_unknown_placeholders_are_kept() {
+        assert_eq!(render_template("{{unknown}} {{date}}", &vars()), "{{unknown}} 2026-10-17");
+        assert_eq!(render_template("{{not a var}}", &vars()), "{{not a var}}");
+    }
+
+    #[test]
+    fn test_render_template_value() {
+        let value = serde_json::json!({"due": "{{date}}", "tags": ["{{weekday}}"], "count": 3});
+        assert_eq!(
+            render_template_value(&value, &vars()),
+            serde_json::json!({"due": "2026-10-17", "tags": ["Saturday"], "count": 3})
+        );
+    }
+}
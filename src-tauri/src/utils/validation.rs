@@ -52,6 +52,19 @@ pub fn sanitize_text(text: &str) -> String {
         .collect()
 }
 
+/// Escape `\\`, `%` and `_` so `text` matches literally in a
+/// `LIKE ? ESCAPE '\\'` pattern
+pub fn escape_like(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '%' | '_') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -82,4 +95,10 @@ mod tests {
         let output = sanitize_text(input);
         assert_eq!(output, "HelloWorld\nNew Line\tTab");
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn test_escape_like() {
+        assert_eq!(escape_like("100%_done\\"), "100\\%\\_done\\\\");
+        assert_eq!(escape_like("plain"), "plain");
+    }
+}